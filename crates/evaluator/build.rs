@@ -1,9 +1,5 @@
 use chrono::Utc;
-use flate2::{write::ZlibEncoder, Compression};
 use rustc_version::version_meta;
-use serde::Deserialize;
-use serde_json::Value;
-use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
 use std::io::Write;
@@ -15,17 +11,19 @@ const DEFAULT_GROUND_TRUTH: &str = concat!(
 );
 const SCHEMA_VERSION: u32 = 1;
 
-#[derive(Deserialize)]
-struct DocumentProbe {
-    document_id: String,
-    fields: Value,
-}
+#[path = "src/digest.rs"]
+mod digest;
+#[path = "src/shards.rs"]
+mod shards;
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/digest.rs");
+    println!("cargo:rerun-if-changed=src/shards.rs");
     println!("cargo:rerun-if-changed=../../resources/fixtures/dummy_ground_truth.json");
     println!("cargo:rerun-if-env-changed=GROUND_TRUTH_PATH");
     println!("cargo:rerun-if-env-changed=GROUND_TRUTH_JSON");
+    println!("cargo:rerun-if-env-changed=GROUND_TRUTH_SHARDS");
 
     let path = env::var("GROUND_TRUTH_PATH")
         .or_else(|_| env::var("GROUND_TRUTH_JSON"))
@@ -34,28 +32,23 @@ fn main() {
         panic!("Failed to read ground truth file '{path}': {err}");
     });
 
-    let documents: Vec<DocumentProbe> = serde_json::from_slice(&raw).unwrap_or_else(|err| {
-        panic!("Ground truth must be a JSON array of documents: {err}");
-    });
-    if documents.is_empty() {
-        panic!("Ground truth cannot be empty");
-    }
-    for doc in &documents {
-        if doc.document_id.trim().is_empty() {
-            panic!("Each document must declare a non-empty document_id");
-        }
-        if !doc.fields.is_object() {
-            panic!("Each document must use an object for the fields payload");
-        }
-    }
-
-    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
-    encoder.write_all(&raw).expect("compression failed");
-    let compressed = encoder.finish().expect("compression finalize failed");
+    let summary = digest::digest_ground_truth(&raw).unwrap_or_else(|err| panic!("{err}"));
 
-    let mut hasher = Sha256::new();
-    hasher.update(&raw);
-    let digest = format!("{:x}", hasher.finalize());
+    let shard_count = env::var("GROUND_TRUTH_SHARDS").map_or(1, |count| {
+        count
+            .trim()
+            .parse::<usize>()
+            .unwrap_or_else(|_| panic!("GROUND_TRUTH_SHARDS must be a number, got '{count}'"))
+    });
+    let payloads = shards::split_documents(&raw, shard_count).unwrap_or_else(|err| panic!("{err}"));
+    let shard_digests: Vec<String> = payloads
+        .iter()
+        .map(|payload| digest::sha256_hex(payload))
+        .collect();
+    let compressed: Vec<String> = payloads
+        .iter()
+        .map(|payload| format!("&{:?}", shards::compress(payload)))
+        .collect();
 
     let rustc = version_meta().expect("failed to obtain rustc version");
     let timestamp = Utc::now().to_rfc3339();
@@ -77,8 +70,10 @@ fn main() {
         "package_version": package_version,
         "rustc_version": rustc.short_version_string,
         "build_timestamp_utc": timestamp,
-        "ground_truth_sha256": digest,
-        "document_count": documents.len(),
+        "ground_truth_sha256": summary.sha256,
+        "document_count": summary.document_count,
+        "ground_truth_shards": payloads.len(),
+        "ground_truth_shard_sha256": shard_digests,
         "source_commit": git_commit,
     });
 
@@ -87,14 +82,14 @@ fn main() {
     let mut file = fs::File::create(dest).expect("failed to create ground_truth.rs");
     writeln!(
         file,
-        "pub const GROUND_TRUTH_BYTES: &[u8] = &{:?};",
-        compressed
+        "pub const GROUND_TRUTH_SHARDS: &[&[u8]] = &[{}];",
+        compressed.join(", ")
     )
     .unwrap();
     writeln!(
         file,
         "pub const BUILD_INFO_JSON: &str = r#\"{}\"#;",
-        build_info.to_string()
+        build_info
     )
     .unwrap();
 }
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::annotations::field_pattern;
+use crate::metrics::{round, DocumentScore};
+use crate::options::FieldMinimum;
+
+/// Minimum number of documents a field must appear in before a bias is reported.
+const MIN_BIAS_SAMPLES: usize = 2;
+/// Maximum spread of the observed offsets or ratios, relative to the bias itself.
+const BIAS_SPREAD_TOLERANCE: f64 = 0.05;
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BiasKind {
+    /// `predicted - expected` is (nearly) the same non-zero constant in every document.
+    Offset,
+    /// `predicted / expected` is (nearly) the same constant other than one in every document.
+    Ratio,
+}
+
+/// A numeric field whose errors look like a consistent transformation rather than noise.
+#[derive(Debug, Clone, Serialize)]
+pub struct SystematicBias {
+    pub field: String,
+    pub kind: BiasKind,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub documents: u32,
+}
+
+/// Flags numeric fields whose `(expected, predicted)` pairs across documents share a
+/// consistent non-zero offset or a consistent ratio other than one.
+///
+/// Offsets are checked first, so a field is reported at most once.
+pub fn detect_systematic_biases(pairs: &BTreeMap<String, Vec<(f64, f64)>>) -> Vec<SystematicBias> {
+    let mut biases = Vec::new();
+    for (field, observations) in pairs {
+        if observations.len() < MIN_BIAS_SAMPLES {
+            continue;
+        }
+        let offsets: Vec<f64> = observations
+            .iter()
+            .map(|(expected, predicted)| predicted - expected)
+            .collect();
+        if let Some(bias) = consistent_bias(field, BiasKind::Offset, &offsets, 0.0) {
+            biases.push(bias);
+            continue;
+        }
+        if observations
+            .iter()
+            .any(|(expected, _)| expected.abs() < EPSILON)
+        {
+            continue;
+        }
+        let ratios: Vec<f64> = observations
+            .iter()
+            .map(|(expected, predicted)| predicted / expected)
+            .collect();
+        if let Some(bias) = consistent_bias(field, BiasKind::Ratio, &ratios, 1.0) {
+            biases.push(bias);
+        }
+    }
+    biases
+}
+
+/// A numeric prediction off from the expected value by at least `--outlier-factor`, in either
+/// direction: likely a misplaced decimal point or a unit mix-up rather than a close miss.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumericOutlier {
+    pub document_id: String,
+    pub field: String,
+    pub expected: f64,
+    pub predicted: f64,
+    /// How many times larger the larger magnitude is than the smaller one.
+    pub factor: f64,
+}
+
+/// The magnitude ratio between two non-zero numbers when it reaches `min_factor`.
+pub fn outlier_factor(expected: f64, predicted: f64, min_factor: f64) -> Option<f64> {
+    let (expected, predicted) = (expected.abs(), predicted.abs());
+    if expected < EPSILON || predicted < EPSILON {
+        return None;
+    }
+    let factor = expected.max(predicted) / expected.min(predicted);
+    (factor >= min_factor).then_some(factor)
+}
+
+/// A field whose mean score across the predicted documents is below `--worst-field-threshold`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeakField {
+    /// Field pattern, with array indices as `*` (e.g. `items.*.code`).
+    pub field: String,
+    pub mean_score: f64,
+    /// Predicted documents in which the field was scored.
+    pub documents: u32,
+}
+
+/// The weakest fields of `--worst-fields`, with the settings that selected them.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorstFields {
+    pub threshold: f64,
+    pub limit: usize,
+    pub fields: Vec<WeakField>,
+}
+
+/// Averages per-field scores by field pattern over the documents that have a prediction and
+/// returns up to `limit` patterns whose mean is below `threshold`, worst first.
+pub fn worst_fields(documents: &[DocumentScore], threshold: f64, limit: usize) -> WorstFields {
+    let mut fields: Vec<WeakField> = field_score_sums(documents)
+        .into_iter()
+        .map(|(field, (sum, count))| WeakField {
+            field,
+            mean_score: round(sum / f64::from(count)),
+            documents: count,
+        })
+        .filter(|field| field.mean_score < threshold)
+        .collect();
+    fields.sort_by(|a, b| a.mean_score.total_cmp(&b.mean_score));
+    fields.truncate(limit);
+    WorstFields {
+        threshold,
+        limit,
+        fields,
+    }
+}
+
+/// Outcome of one `--field-min` (or `x-min-score`) check.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldMinimumCheck {
+    /// Field pattern, with array indices as `*`.
+    pub field: String,
+    pub min_score: f64,
+    /// Mean score over the predicted documents, or `None` when the field was never scored.
+    pub mean_score: Option<f64>,
+    pub documents: u32,
+    /// Whether `mean_score` reaches `min_score`. A field that was never scored fails, so a
+    /// misspelt path cannot pass silently.
+    pub passed: bool,
+}
+
+/// Checks the mean score of each critical field, computed as for [`worst_fields`], against its
+/// minimum.
+pub fn check_field_minimums(
+    documents: &[DocumentScore],
+    minimums: &[FieldMinimum],
+) -> Vec<FieldMinimumCheck> {
+    let sums = field_score_sums(documents);
+    minimums
+        .iter()
+        .map(|minimum| {
+            let (mean_score, documents) = match sums.get(&minimum.field) {
+                Some(&(sum, count)) => (Some(round(sum / f64::from(count))), count),
+                None => (None, 0),
+            };
+            FieldMinimumCheck {
+                field: minimum.field.clone(),
+                min_score: minimum.min_score,
+                mean_score,
+                documents,
+                passed: mean_score.is_some_and(|mean| mean >= minimum.min_score),
+            }
+        })
+        .collect()
+}
+
+/// Sum and count of per-field scores by field pattern, over the documents with a prediction.
+fn field_score_sums(documents: &[DocumentScore]) -> BTreeMap<String, (f64, u32)> {
+    let mut sums: BTreeMap<String, (f64, u32)> = BTreeMap::new();
+    for document in documents.iter().filter(|document| document.has_prediction) {
+        for field in &document.field_scores {
+            let (sum, count) = sums.entry(field_pattern(&field.path)).or_default();
+            *sum += field.score;
+            *count += 1;
+        }
+    }
+    sums
+}
+
+fn consistent_bias(
+    field: &str,
+    kind: BiasKind,
+    values: &[f64],
+    neutral: f64,
+) -> Option<SystematicBias> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let deviation = (mean - neutral).abs();
+    if deviation < EPSILON {
+        return None;
+    }
+    // Every observation must sit on the same side of the neutral value.
+    if (min - neutral).signum() != (max - neutral).signum() {
+        return None;
+    }
+    if max - min > deviation * BIAS_SPREAD_TOLERANCE {
+        return None;
+    }
+    Some(SystematicBias {
+        field: field.to_string(),
+        kind,
+        mean: round(mean),
+        min: round(min),
+        max: round(max),
+        documents: values.len() as u32,
+    })
+}
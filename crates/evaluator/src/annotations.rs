@@ -0,0 +1,339 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::numbers::{NumericAsymmetry, NumericTolerance};
+use crate::options::TextMetric;
+use crate::sets::SetMode;
+use crate::synonyms::SynonymMap;
+use crate::transforms::Transform;
+use crate::units::UnitSpec;
+
+/// Segment used in annotation patterns for any array index.
+const ARRAY_WILDCARD: &str = "*";
+
+/// Evaluator hints attached to a single property of the extraction template through
+/// `x-`-prefixed keywords, e.g. `"x-text-metric": "hierarchical-path"`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldAnnotation {
+    /// `x-text-metric`: text metric used for this field instead of `--text-metric`.
+    /// Properties with `"format": "date-time"` default to [`TextMetric::Timestamp`] and
+    /// properties with `"contentEncoding": "base64"` to [`TextMetric::Binary`].
+    pub text_metric: Option<TextMetric>,
+    /// `x-path-separator`: separator for `hierarchical-path` scoring of this field.
+    pub path_separator: Option<String>,
+    /// `x-numeric-tolerance`: `0.01` (absolute) or `"5%"` (relative), overriding
+    /// `--numeric-tolerance`.
+    pub numeric_tolerance: Option<NumericTolerance>,
+    /// `x-numeric-asymmetry`: `{"over": 2, "under": 1}` (or `"over=2,under=1"`), overriding
+    /// `--numeric-asymmetry`.
+    pub numeric_asymmetry: Option<NumericAsymmetry>,
+    /// `x-synonyms`: `{"invoice": ["bill"]}`; values sharing a canonical term score 1.0,
+    /// overriding `--synonyms`.
+    pub synonyms: Option<SynonymMap>,
+    /// `x-identifier`: `true`, or `{"ignore-separators": true}`, for id fields whose
+    /// leading zeros are insignificant.
+    pub identifier: Option<IdentifierFormat>,
+    /// `x-percent`: `true` for fields that may be written as a fraction (`0.2`) or a
+    /// percentage string (`"20%"`); both are compared numerically as fractions.
+    pub percent: bool,
+    /// `x-number-sequence`: `true` for text fields such as `"2 x 500ml"` scored by the numbers
+    /// written in them, compared in order, instead of as text.
+    pub number_sequence: bool,
+    /// `x-coordinate`: `true` for `{"lat": .., "lon": ..}` objects scored by geographic
+    /// distance as a single field.
+    pub coordinate: bool,
+    /// `x-money`: `true` for `{"amount": .., "currency": ..}` objects scored as one numeric
+    /// field by amount and currency code.
+    pub money: bool,
+    /// `x-duration`: `true` for fields holding a span of time, written in seconds or as
+    /// `"2h30m"`, `"150 minutes"`, `"2:30:00"` or `"PT2H30M"`; both sides are compared
+    /// numerically in seconds.
+    pub duration: bool,
+    /// Whether the property's `type` is `number` or `integer`, alone or in a list such as
+    /// `["number", "null"]`; ground-truth strings in scientific notation are then scored as
+    /// numbers.
+    pub numeric: bool,
+    /// `x-unit`: `true`, or `{"conversions": {"kg": 1, "t": 1000}, "synonyms": {..}}`, for
+    /// strings such as `"1500 kg"` whose number is scored numerically (after converting
+    /// declared units) and whose unit is matched exactly or through synonyms.
+    pub unit: Option<UnitSpec>,
+    /// `x-set`: `true` (or `"multiset"` to count duplicates) for array fields scored as one
+    /// value by the overlap of their elements, regardless of order.
+    pub set: Option<SetMode>,
+    /// `x-sequence`: `true` for ordered array fields scored as one value by aligning their
+    /// elements, so an inserted or dropped element does not shift the rest out of place.
+    pub sequence: bool,
+    /// `x-json-string`: `true` for fields whose string value is JSON-encoded (`"{\"a\": 1}"`);
+    /// it is decoded and compared structurally.
+    pub json_string: bool,
+    /// `x-stop-words`: `true` to drop the `--stop-words` list from both values before
+    /// scoring.
+    pub stop_words: bool,
+    /// `x-transform`: `["trim", "strip-currency", "parse-number"]`, transforms applied in
+    /// order to both values before scoring. A value the pipeline turns into a number is
+    /// scored numerically.
+    pub transforms: Vec<Transform>,
+    /// `x-min-score`: mean score the field must reach across documents; a lower mean fails
+    /// the run like `--field-min`.
+    pub min_score: Option<f64>,
+    /// `x-aliases`: `["amount"]`, other key names of this property; a key with one of these
+    /// names, at any nesting level, is read as this property's name before matching.
+    pub aliases: Vec<String>,
+    /// Whether the property is left out of its parent's `required` list.
+    pub optional: bool,
+}
+
+impl FieldAnnotation {
+    fn from_schema(node: &Value, optional: bool) -> Option<Self> {
+        let annotation = Self {
+            text_metric: node
+                .get("x-text-metric")
+                .and_then(Value::as_str)
+                .and_then(TextMetric::from_name)
+                .or_else(|| {
+                    (node.get("format").and_then(Value::as_str) == Some("date-time"))
+                        .then_some(TextMetric::Timestamp)
+                })
+                .or_else(|| {
+                    (node.get("contentEncoding").and_then(Value::as_str) == Some("base64"))
+                        .then_some(TextMetric::Binary)
+                }),
+            path_separator: node
+                .get("x-path-separator")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            numeric_tolerance: node
+                .get("x-numeric-tolerance")
+                .and_then(NumericTolerance::from_json),
+            numeric_asymmetry: node
+                .get("x-numeric-asymmetry")
+                .and_then(NumericAsymmetry::from_json),
+            synonyms: node
+                .get("x-synonyms")
+                .and_then(|synonyms| SynonymMap::from_json(synonyms).ok()),
+            identifier: node
+                .get("x-identifier")
+                .and_then(IdentifierFormat::from_json),
+            percent: node
+                .get("x-percent")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            coordinate: node
+                .get("x-coordinate")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            money: node
+                .get("x-money")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            duration: node
+                .get("x-duration")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            numeric: is_numeric_type(node.get("type")),
+            number_sequence: node
+                .get("x-number-sequence")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            unit: node.get("x-unit").and_then(UnitSpec::from_json),
+            set: node.get("x-set").and_then(SetMode::from_json),
+            sequence: node
+                .get("x-sequence")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            min_score: node
+                .get("x-min-score")
+                .and_then(Value::as_f64)
+                .filter(|score| (0.0..=1.0).contains(score)),
+            json_string: node
+                .get("x-json-string")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            stop_words: node
+                .get("x-stop-words")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            transforms: node
+                .get("x-transform")
+                .and_then(Transform::pipeline_from_json)
+                .unwrap_or_default(),
+            aliases: node
+                .get("x-aliases")
+                .and_then(Value::as_array)
+                .map(|aliases| {
+                    aliases
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            optional,
+        };
+        (annotation != Self::default()).then_some(annotation)
+    }
+}
+
+fn is_numeric_type(schema_type: Option<&Value>) -> bool {
+    let is_numeric = |name: &Value| matches!(name.as_str(), Some("number" | "integer"));
+    match schema_type {
+        Some(Value::Array(names)) => names.iter().any(is_numeric),
+        Some(name) => is_numeric(name),
+        None => false,
+    }
+}
+
+/// Normalization applied to both sides of an identifier field before scoring.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdentifierFormat {
+    /// Also drop separators (`-`, `_`, `/`, `.` and whitespace), so `AB-12` matches `AB12`.
+    pub ignore_separators: bool,
+}
+
+impl IdentifierFormat {
+    pub(crate) fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(true) => Some(Self::default()),
+            Value::Object(map) => Some(Self {
+                ignore_separators: map
+                    .get("ignore-separators")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Strips leading zeros from every run of digits (`INV-007` becomes `INV-7`, `000`
+    /// becomes `0`), then drops separators if requested.
+    pub fn normalize(&self, id: &str) -> String {
+        let mut normalized = String::with_capacity(id.len());
+        let mut run_start: Option<usize> = None;
+        for c in id.chars() {
+            if c.is_ascii_digit() {
+                let start = *run_start.get_or_insert(normalized.len());
+                if c != '0' || normalized.len() > start {
+                    normalized.push(c);
+                }
+                continue;
+            }
+            close_digit_run(&mut normalized, run_start.take());
+            if !(self.ignore_separators && is_separator(c)) {
+                normalized.push(c);
+            }
+        }
+        close_digit_run(&mut normalized, run_start);
+        normalized
+    }
+}
+
+/// Keeps a single `0` for a digit run that consisted only of zeros.
+fn close_digit_run(normalized: &mut String, run_start: Option<usize>) {
+    if run_start == Some(normalized.len()) {
+        normalized.push('0');
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '-' | '_' | '/' | '.') || c.is_whitespace()
+}
+
+/// Field annotations collected from an extraction template, keyed by flattened field
+/// pattern (array indices appear as `*`, e.g. `items.*.code`).
+#[derive(Debug, Clone, Default)]
+pub struct FieldAnnotations {
+    by_pattern: BTreeMap<String, FieldAnnotation>,
+}
+
+impl FieldAnnotations {
+    /// Walks the `properties` and `items` of a JSON-schema template and records every
+    /// node that carries evaluator annotations.
+    pub fn from_template(template: &Value) -> Self {
+        let mut annotations = Self::default();
+        annotations.collect(template, &mut Vec::new(), false);
+        annotations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_pattern.is_empty()
+    }
+
+    /// Annotations by field pattern.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &FieldAnnotation)> {
+        self.by_pattern.iter()
+    }
+
+    /// Whether any annotated field satisfies `predicate`.
+    pub fn any(&self, predicate: impl Fn(&FieldAnnotation) -> bool) -> bool {
+        self.by_pattern.values().any(predicate)
+    }
+
+    /// Looks up the annotation for a flattened field path such as `items.3.code`.
+    pub fn get(&self, path: &str) -> Option<&FieldAnnotation> {
+        if self.by_pattern.is_empty() {
+            return None;
+        }
+        self.by_pattern.get(&field_pattern(path))
+    }
+
+    /// Whether the field, or any object or array containing it, is optional in the template.
+    pub fn is_optional(&self, path: &str) -> bool {
+        if self.by_pattern.is_empty() {
+            return false;
+        }
+        let pattern = field_pattern(path);
+        let mut prefix = pattern.as_str();
+        loop {
+            if self
+                .by_pattern
+                .get(prefix)
+                .is_some_and(|annotation| annotation.optional)
+            {
+                return true;
+            }
+            match prefix.rfind('.') {
+                Some(end) => prefix = &prefix[..end],
+                None => return false,
+            }
+        }
+    }
+
+    fn collect(&mut self, node: &Value, path: &mut Vec<String>, optional: bool) {
+        if !path.is_empty() {
+            if let Some(annotation) = FieldAnnotation::from_schema(node, optional) {
+                self.by_pattern.insert(path.join("."), annotation);
+            }
+        }
+        if let Some(properties) = node.get("properties").and_then(Value::as_object) {
+            let required: Vec<&str> = node
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|names| names.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            for (key, child) in properties {
+                path.push(key.clone());
+                self.collect(child, path, !required.contains(&key.as_str()));
+                path.pop();
+            }
+        }
+        if let Some(items) = node.get("items").filter(|items| items.is_object()) {
+            path.push(ARRAY_WILDCARD.to_string());
+            self.collect(items, path, false);
+            path.pop();
+        }
+    }
+}
+
+/// Replaces numeric path segments (array indices) with the wildcard segment.
+pub fn field_pattern(path: &str) -> String {
+    path.split('.')
+        .map(|segment| {
+            if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                ARRAY_WILDCARD
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,72 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::EvaluationError;
+use crate::metrics::{round, EvaluationMetrics};
+
+/// Headline metrics compared against the baseline run.
+const COMPARED_METRICS: &[&str] = &[
+    "overall_score",
+    "document_coverage",
+    "numeric_field_similarity",
+    "text_field_similarity",
+    "structural_completeness",
+];
+
+/// Headline metrics of this run against an archived run.
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineComparison {
+    /// The archived metrics file compared against.
+    pub path: PathBuf,
+    /// Current minus baseline value of each headline metric present in both runs.
+    pub deltas: BTreeMap<String, f64>,
+}
+
+/// The most recently modified `*.json` file directly inside `dir`; files modified at the
+/// same time are ordered by name, so date-stamped names also break ties.
+pub fn latest_metrics_file(dir: &Path) -> Result<PathBuf, EvaluationError> {
+    let mut latest: Option<(SystemTime, PathBuf)> = None;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let modified = fs::metadata(&path)?.modified()?;
+        let candidate = (modified, path);
+        if latest.as_ref().is_none_or(|latest| candidate > *latest) {
+            latest = Some(candidate);
+        }
+    }
+    latest
+        .map(|(_, path)| path)
+        .ok_or_else(|| EvaluationError::NoBaseline(dir.to_path_buf()))
+}
+
+/// Compares `metrics` with the metrics file at `path`: a pdf_eval metrics output, or one
+/// `--append` run-log entry with the metrics under `metrics`.
+pub fn compare_with_baseline(
+    metrics: &EvaluationMetrics,
+    path: &Path,
+) -> Result<BaselineComparison, EvaluationError> {
+    let payload = fs::read_to_string(path)?;
+    let baseline: Value = serde_json::from_str(&payload)
+        .map_err(|err| EvaluationError::invalid_json(err, &payload))?;
+    let baseline = baseline.get("metrics").unwrap_or(&baseline);
+    let current = serde_json::to_value(metrics)?;
+    let deltas = COMPARED_METRICS
+        .iter()
+        .filter_map(|&name| {
+            let current = current.get(name)?.as_f64()?;
+            let previous = baseline.get(name)?.as_f64()?;
+            Some((name.to_string(), round(current - previous)))
+        })
+        .collect();
+    Ok(BaselineComparison {
+        path: path.to_path_buf(),
+        deltas,
+    })
+}
@@ -0,0 +1,54 @@
+use sha2::{Digest, Sha256};
+
+/// Scores two base64-encoded blobs: 1.0 when the SHA-256 digests of their decoded bytes are
+/// equal and 0.0 otherwise. Returns `None` when either side is not valid base64.
+pub fn binary_similarity(expected: &str, predicted: &str) -> Option<f64> {
+    let expected = Sha256::digest(decode_base64(expected)?);
+    let predicted = Sha256::digest(decode_base64(predicted)?);
+    Some(if expected == predicted { 1.0 } else { 0.0 })
+}
+
+/// Decodes standard or URL-safe base64. Whitespace (as in line-wrapped blobs) is skipped and
+/// trailing `=` padding is optional.
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut sextets = Vec::with_capacity(text.len());
+    let mut padding = 0;
+    for byte in text.bytes().filter(|byte| !byte.is_ascii_whitespace()) {
+        if byte == b'=' {
+            padding += 1;
+            continue;
+        }
+        if padding > 0 {
+            return None;
+        }
+        sextets.push(sextet(byte)?);
+    }
+    if padding > 2 || sextets.len() % 4 == 1 {
+        return None;
+    }
+    if padding > 0 && (sextets.len() + padding) % 4 != 0 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(sextets.len() * 3 / 4);
+    for chunk in sextets.chunks(4) {
+        let word = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |word, (i, &s)| word | u32::from(s) << (18 - 6 * i));
+        let decoded = [(word >> 16) as u8, (word >> 8) as u8, word as u8];
+        bytes.extend_from_slice(&decoded[..chunk.len() - 1]);
+    }
+    Some(bytes)
+}
+
+fn sextet(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
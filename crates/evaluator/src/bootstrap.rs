@@ -0,0 +1,95 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{selected_documents, Document, MetricsAccumulator};
+use crate::metrics::round;
+use crate::options::EvaluationOptions;
+
+/// Two-sided confidence level of the bootstrap interval.
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// Percentile bootstrap interval around the overall score, with `--bootstrap`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ConfidenceInterval {
+    pub level: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub resamples: u32,
+    /// Seed of the resampling; passing it to `--seed` reproduces the interval.
+    pub seed: u64,
+}
+
+/// Resamples the scored ground-truth documents with replacement `resamples` times and
+/// reports the 2.5th and 97.5th percentiles (nearest rank) of the recomputed overall scores.
+///
+/// Each document is scored once and its contribution reused across resamples. Predictions
+/// without a ground-truth document are kept in every resample, since they are not drawn
+/// from the ground truth.
+pub fn bootstrap_overall_score(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    resamples: u32,
+    seed: u64,
+) -> Result<ConfidenceInterval, EvaluationError> {
+    let selection = selected_documents(ground_truth, predictions, options);
+    let (ground_truth, predictions) = (&selection.ground_truth, &selection.predictions);
+    if ground_truth.is_empty() || resamples == 0 {
+        return Err(EvaluationError::EmptyInput);
+    }
+    let contributions = ground_truth
+        .iter()
+        .map(|(doc_id, gt_doc)| {
+            let mut contribution = MetricsAccumulator::new(options.clone());
+            contribution.add_document(gt_doc, predictions.get(doc_id))?;
+            Ok(contribution)
+        })
+        .collect::<Result<Vec<_>, EvaluationError>>()?;
+    let mut extras = MetricsAccumulator::new(options.clone());
+    for (doc_id, pred_doc) in predictions.iter() {
+        if !ground_truth.contains_key(doc_id) {
+            extras.add_extra_prediction(pred_doc)?;
+        }
+    }
+
+    let mut rng = SplitMix64(seed);
+    let mut scores = Vec::with_capacity(resamples as usize);
+    for _ in 0..resamples {
+        let mut resample = MetricsAccumulator::new(options.clone());
+        for _ in 0..contributions.len() {
+            let index = (rng.next_u64() % contributions.len() as u64) as usize;
+            resample.absorb(&contributions[index]);
+        }
+        resample.absorb(&extras);
+        scores.push(resample.finalize()?.overall_score);
+    }
+    scores.sort_by(f64::total_cmp);
+    let tail = (1.0 - CONFIDENCE_LEVEL) / 2.0;
+    Ok(ConfidenceInterval {
+        level: CONFIDENCE_LEVEL,
+        lower: round(nearest_rank(&scores, tail)),
+        upper: round(nearest_rank(&scores, 1.0 - tail)),
+        resamples,
+        seed,
+    })
+}
+
+fn nearest_rank(sorted: &[f64], quantile: f64) -> f64 {
+    let rank = (quantile * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// SplitMix64, a small deterministic generator: the same seed always draws the same
+/// resamples, on every platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{field_score, ground_truth_fields, predicted_fields, Document};
+use crate::metrics::round;
+use crate::options::EvaluationOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeOutcome {
+    Improved,
+    Worsened,
+    Unchanged,
+}
+
+/// A ground-truth field whose predicted value differs between two prediction runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub document_id: String,
+    pub field: String,
+    pub previous_score: f64,
+    pub current_score: f64,
+    pub delta: f64,
+    pub outcome: ChangeOutcome,
+}
+
+/// Scores only the fields whose predicted value changed since a previous run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChangeReport {
+    pub changed_fields: u32,
+    pub improved: u32,
+    pub worsened: u32,
+    pub unchanged: u32,
+    pub net_delta: f64,
+    pub changes: Vec<FieldChange>,
+}
+
+impl ChangeReport {
+    /// Combines the reports of two disjoint document shards.
+    pub fn merge(mut self, other: ChangeReport) -> ChangeReport {
+        self.changed_fields += other.changed_fields;
+        self.improved += other.improved;
+        self.worsened += other.worsened;
+        self.unchanged += other.unchanged;
+        self.net_delta = round(self.net_delta + other.net_delta);
+        self.changes.extend(other.changes);
+        self.changes
+            .sort_by(|a, b| a.document_id.cmp(&b.document_id));
+        self
+    }
+}
+
+/// Compares `previous` and `current` predictions field by field and scores every
+/// ground-truth field whose predicted value was added, removed, or modified.
+pub fn compare_changes(
+    ground_truth: &BTreeMap<String, Document>,
+    previous: &BTreeMap<String, Document>,
+    current: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<ChangeReport, EvaluationError> {
+    let mut report = ChangeReport::default();
+    let mut net_delta = 0.0;
+    for (doc_id, gt_doc) in ground_truth {
+        let gt_flat = ground_truth_fields(&gt_doc.fields, options)?;
+        let previous_flat = match previous.get(doc_id) {
+            Some(doc) => predicted_fields(&doc.fields, options)?.0,
+            None => BTreeMap::new(),
+        };
+        let current_flat = match current.get(doc_id) {
+            Some(doc) => predicted_fields(&doc.fields, options)?.0,
+            None => BTreeMap::new(),
+        };
+        for (path, expected) in &gt_flat {
+            let before = previous_flat.get(path);
+            let after = current_flat.get(path);
+            if before == after {
+                continue;
+            }
+            let previous_score = field_score(options, path, expected, before);
+            let current_score = field_score(options, path, expected, after);
+            let delta = current_score - previous_score;
+            let outcome = if delta > 0.0 {
+                report.improved += 1;
+                ChangeOutcome::Improved
+            } else if delta < 0.0 {
+                report.worsened += 1;
+                ChangeOutcome::Worsened
+            } else {
+                report.unchanged += 1;
+                ChangeOutcome::Unchanged
+            };
+            net_delta += delta;
+            report.changes.push(FieldChange {
+                document_id: doc_id.clone(),
+                field: path.clone(),
+                previous_score: round(previous_score),
+                current_score: round(current_score),
+                delta: round(delta),
+                outcome,
+            });
+        }
+    }
+    report.changed_fields = report.changes.len() as u32;
+    report.net_delta = round(net_delta);
+    Ok(report)
+}
@@ -0,0 +1,52 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Labels of the codes a field may hold, e.g. `{"1": "Pending", "2": "Paid"}`, so a code on
+/// one side matches its label on the other.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeTable {
+    labels: BTreeMap<String, String>,
+}
+
+impl CodeTable {
+    /// Reads a JSON object mapping each code to its label.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let entries = value
+            .as_object()
+            .ok_or("code table must be an object of code to label")?;
+        let mut labels = BTreeMap::new();
+        for (code, label) in entries {
+            let label = label
+                .as_str()
+                .ok_or_else(|| format!("label of code '{code}' must be a string"))?;
+            labels.insert(code.trim().to_string(), label.to_string());
+        }
+        Ok(Self { labels })
+    }
+
+    /// The label of a code written as a JSON number (`2`, or `2.0`) or string (`"2"`).
+    pub fn label(&self, value: &Value) -> Option<&str> {
+        let code = match value {
+            Value::String(code) => code.trim().to_string(),
+            Value::Number(number) => match number.as_f64() {
+                Some(code) if code.fract() == 0.0 && code.abs() < 1e15 => format!("{code:.0}"),
+                _ => number.to_string(),
+            },
+            _ => return None,
+        };
+        self.labels.get(&code).map(String::as_str)
+    }
+}
+
+/// Parses `--code-table FIELD=FILE`.
+pub fn parse_code_table_source(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((field, path)) if !field.trim().is_empty() && !path.trim().is_empty() => {
+            Ok((field.trim().to_string(), PathBuf::from(path.trim())))
+        }
+        _ => Err(format!(
+            "invalid code table '{value}' (use FIELD=FILE, e.g. status=status_codes.json)"
+        )),
+    }
+}
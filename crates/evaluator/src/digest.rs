@@ -0,0 +1,49 @@
+//! Ground-truth checks and digest shared by `build.rs` (which includes this file) and the
+//! `digest` subcommand, so both report the same values. Only depends on crates that are also
+//! build dependencies.
+
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[derive(Deserialize)]
+struct DocumentProbe {
+    document_id: String,
+    fields: Value,
+}
+
+/// What the build info records about an embedded ground-truth payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroundTruthDigest {
+    /// Lowercase hex SHA-256 of the raw file bytes.
+    pub sha256: String,
+    pub document_count: usize,
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Validates a ground-truth payload the way the build does and digests its raw bytes.
+pub fn digest_ground_truth(raw: &[u8]) -> Result<GroundTruthDigest, String> {
+    let documents: Vec<DocumentProbe> = serde_json::from_slice(raw)
+        .map_err(|err| format!("Ground truth must be a JSON array of documents: {err}"))?;
+    if documents.is_empty() {
+        return Err("Ground truth cannot be empty".to_string());
+    }
+    for doc in &documents {
+        if doc.document_id.trim().is_empty() {
+            return Err("Each document must declare a non-empty document_id".to_string());
+        }
+        if !doc.fields.is_object() {
+            return Err("Each document must use an object for the fields payload".to_string());
+        }
+    }
+    Ok(GroundTruthDigest {
+        sha256: sha256_hex(raw),
+        document_count: documents.len(),
+    })
+}
@@ -0,0 +1,129 @@
+use serde_json::Value;
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+const WEEK: f64 = 7.0 * DAY;
+
+/// Reads a duration as a number of seconds. Accepted forms:
+///
+/// - a JSON number or a plain numeric string, in seconds (`9000`);
+/// - numbers each followed by a unit, optionally separated by spaces, commas or `and`
+///   (`"2h30m"`, `"150 minutes"`, `"1 hour and 30 mins"`), where the units are `s`/`sec`/
+///   `second`, `m`/`min`/`minute`, `h`/`hr`/`hour`, `d`/`day` and `w`/`wk`/`week`, in the
+///   singular or plural;
+/// - clock notation, `h:mm` or `h:mm:ss` (`"2:30"` is two and a half hours);
+/// - ISO 8601 durations without years or months (`"PT2H30M"`, `"P1DT12H"`).
+///
+/// Returns `None` for anything else, including negative amounts.
+pub fn parse_duration(value: &Value) -> Option<f64> {
+    if let Some(seconds) = value.as_f64() {
+        return (seconds >= 0.0).then_some(seconds);
+    }
+    let text = value.as_str()?.trim().to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = text.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then_some(seconds);
+    }
+    if let Some(iso) = text.strip_prefix('p') {
+        return parse_iso(iso);
+    }
+    if text.contains(':') {
+        return parse_clock(&text);
+    }
+    parse_units(&text)
+}
+
+/// Seconds in one `unit`, written as a name or an abbreviation.
+fn unit_seconds(unit: &str) -> Option<f64> {
+    let seconds = match unit {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+        "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+        "d" | "day" | "days" => DAY,
+        "w" | "wk" | "wks" | "week" | "weeks" => WEEK,
+        _ => return None,
+    };
+    Some(seconds)
+}
+
+/// `2h30m`, `150 minutes`, `1 hour and 30 mins`: every number must have a unit.
+fn parse_units(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let mut rest = text;
+    let mut parts = 0;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if let Some(after) = rest.strip_prefix("and ") {
+            rest = after;
+            continue;
+        }
+        if rest.is_empty() {
+            break;
+        }
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let amount: f64 = rest[..number_end].parse().ok()?;
+        rest = rest[number_end..].trim_start();
+        let unit_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        total += amount * unit_seconds(&rest[..unit_end])?;
+        rest = &rest[unit_end..];
+        parts += 1;
+    }
+    (parts > 0).then_some(total)
+}
+
+/// `h:mm` or `h:mm:ss`, with minutes and seconds below 60.
+fn parse_clock(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let hours: u64 = parts[0].parse().ok()?;
+    let mut seconds = hours as f64 * HOUR;
+    for (part, unit) in parts[1..].iter().zip([MINUTE, 1.0]) {
+        if part.len() != 2 {
+            return None;
+        }
+        let amount: f64 = part.parse().ok()?;
+        if amount >= 60.0 {
+            return None;
+        }
+        seconds += amount * unit;
+    }
+    Some(seconds)
+}
+
+/// The part of an ISO 8601 duration after its `P`: `[nW][nD][T[nH][nM][nS]]`.
+fn parse_iso(iso: &str) -> Option<f64> {
+    let (date, time) = match iso.split_once('t') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (iso, None),
+    };
+    let mut total = 0.0;
+    let mut parts = 0;
+    for (designators, part) in [
+        (&[('w', WEEK), ('d', DAY)][..], Some(date)),
+        (&[('h', HOUR), ('m', MINUTE), ('s', 1.0)][..], time),
+    ] {
+        let mut rest = part.unwrap_or_default();
+        let mut designators = designators.iter();
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+            let amount: f64 = rest[..end].parse().ok()?;
+            let designator = rest[end..].chars().next()?;
+            // Designators must come in order, each at most once.
+            let (_, unit) = designators.find(|(name, _)| *name == designator)?;
+            total += amount * unit;
+            rest = &rest[end + 1..];
+            parts += 1;
+        }
+    }
+    (parts > 0).then_some(total)
+}
@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{ground_truth_fields, predicted_fields, truncate_chars, Document};
+use crate::options::{EvaluationOptions, TextMetric};
+
+/// Character edits that turn expected text values into the predicted ones, summed over
+/// text fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EditOperations {
+    /// Text fields compared (both sides are strings).
+    pub fields: u32,
+    /// Characters present in the prediction but not in the ground truth.
+    pub insertions: u32,
+    /// Ground-truth characters missing from the prediction.
+    pub deletions: u32,
+    /// Ground-truth characters replaced by a different predicted character.
+    pub substitutions: u32,
+}
+
+impl EditOperations {
+    /// Counts the operations of one minimal Levenshtein alignment of `expected` to
+    /// `predicted`, preferring substitutions over insertion/deletion pairs on ties.
+    pub fn between(expected: &str, predicted: &str) -> Self {
+        let expected: Vec<char> = expected.chars().collect();
+        let predicted: Vec<char> = predicted.chars().collect();
+        let (rows, cols) = (expected.len() + 1, predicted.len() + 1);
+        let mut distance = vec![0_u32; rows * cols];
+        for i in 0..rows {
+            distance[i * cols] = i as u32;
+        }
+        for (j, cell) in distance[..cols].iter_mut().enumerate() {
+            *cell = j as u32;
+        }
+        for i in 1..rows {
+            for j in 1..cols {
+                let cost = u32::from(expected[i - 1] != predicted[j - 1]);
+                distance[i * cols + j] = (distance[(i - 1) * cols + j - 1] + cost)
+                    .min(distance[(i - 1) * cols + j] + 1)
+                    .min(distance[i * cols + j - 1] + 1);
+            }
+        }
+
+        let mut operations = Self {
+            fields: 1,
+            ..Self::default()
+        };
+        let (mut i, mut j) = (expected.len(), predicted.len());
+        while i > 0 || j > 0 {
+            let current = distance[i * cols + j];
+            if i > 0 && j > 0 {
+                let cost = u32::from(expected[i - 1] != predicted[j - 1]);
+                if current == distance[(i - 1) * cols + j - 1] + cost {
+                    operations.substitutions += cost;
+                    i -= 1;
+                    j -= 1;
+                    continue;
+                }
+            }
+            if i > 0 && current == distance[(i - 1) * cols + j] + 1 {
+                operations.deletions += 1;
+                i -= 1;
+            } else {
+                operations.insertions += 1;
+                j -= 1;
+            }
+        }
+        operations
+    }
+
+    pub fn add(&mut self, other: &EditOperations) {
+        self.fields += other.fields;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+        self.substitutions += other.substitutions;
+    }
+}
+
+/// Sums [`EditOperations`] over every text field that is a string on both sides, after the
+/// same `--max-field-length` truncation used for scoring. Binary and `x-percent` fields are
+/// skipped.
+pub fn count_edit_operations(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<EditOperations, EvaluationError> {
+    let mut totals = EditOperations::default();
+    for (doc_id, gt_doc) in ground_truth {
+        let Some(pred_doc) = predictions.get(doc_id) else {
+            continue;
+        };
+        let gt_flat = ground_truth_fields(&gt_doc.fields, options)?;
+        let (pred_flat, _, _) = predicted_fields(&pred_doc.fields, options)?;
+        for (path, expected) in &gt_flat {
+            if options.text_metric_for(path) == TextMetric::Binary || options.is_percent(path) {
+                continue;
+            }
+            let (Some(expected), Some(predicted)) = (
+                expected.as_str(),
+                pred_flat.get(path).and_then(|value| value.as_str()),
+            ) else {
+                continue;
+            };
+            totals.add(&EditOperations::between(
+                truncate_chars(expected, options.max_field_length),
+                truncate_chars(predicted, options.max_field_length),
+            ));
+        }
+    }
+    Ok(totals)
+}
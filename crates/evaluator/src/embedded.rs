@@ -1,11 +1,76 @@
+use std::io::{self, Cursor, Read};
+
+use crate::digest::sha256_hex;
+use crate::error::EvaluationError;
+
 pub(crate) mod data {
     include!(concat!(env!("OUT_DIR"), "/ground_truth.rs"));
 }
 
-pub(crate) fn ground_truth_bytes() -> &'static [u8] {
-    data::GROUND_TRUTH_BYTES
+/// The compressed ground-truth shards, in document order.
+pub(crate) fn ground_truth_shards() -> &'static [&'static [u8]] {
+    data::GROUND_TRUTH_SHARDS
+}
+
+/// Decompresses a single ground-truth shard into its JSON array.
+pub(crate) fn decompress_shard(bytes: &[u8]) -> io::Result<String> {
+    let mut decoder = flate2::read::ZlibDecoder::new(Cursor::new(bytes));
+    let mut payload = String::new();
+    decoder.read_to_string(&mut payload)?;
+    Ok(payload)
 }
 
 pub fn build_info_json() -> &'static str {
     data::BUILD_INFO_JSON
 }
+
+/// Decompresses the embedded ground truth and checks its SHA-256 against the
+/// `ground_truth_sha256` recorded by `build.rs`, returning the verified digest (see
+/// [`verify_shards`]).
+pub fn verify_ground_truth() -> Result<String, EvaluationError> {
+    verify_shards(build_info_json(), ground_truth_shards())
+}
+
+/// Checks compressed ground-truth `shards` against the digests in `build_info`, returning
+/// the recorded `ground_truth_sha256` of the whole file. A single shard holds the exact file
+/// bytes and is checked against that digest. Shards of a sharded build are re-serialized
+/// document arrays, whose concatenation is not the file, so each is checked against its
+/// entry in `ground_truth_shard_sha256` instead.
+pub fn verify_shards(build_info: &str, shards: &[&[u8]]) -> Result<String, EvaluationError> {
+    let build_info: serde_json::Value = serde_json::from_str(build_info)?;
+    let file_digest = build_info
+        .get("ground_truth_sha256")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if let [single] = shards {
+        let actual = sha256_hex(decompress_shard(single)?.as_bytes());
+        if actual != file_digest {
+            return Err(EvaluationError::GroundTruthDigestMismatch {
+                expected: file_digest,
+                actual,
+            });
+        }
+        return Ok(file_digest);
+    }
+    let shard_digests = build_info
+        .get("ground_truth_shard_sha256")
+        .and_then(serde_json::Value::as_array);
+    if shard_digests.map_or(0, Vec::len) != shards.len() {
+        return Err(EvaluationError::GroundTruthDigestMismatch {
+            expected: format!("{} shard digests", shards.len()),
+            actual: format!("{} recorded", shard_digests.map_or(0, Vec::len)),
+        });
+    }
+    for (shard, shard_digest) in shards.iter().zip(shard_digests.into_iter().flatten()) {
+        let shard_digest = shard_digest.as_str().unwrap_or_default().to_string();
+        let actual = sha256_hex(decompress_shard(shard)?.as_bytes());
+        if actual != shard_digest {
+            return Err(EvaluationError::GroundTruthDigestMismatch {
+                expected: shard_digest,
+                actual,
+            });
+        }
+    }
+    Ok(file_digest)
+}
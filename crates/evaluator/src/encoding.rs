@@ -0,0 +1,24 @@
+/// Characters that bytes 0x80-0x9F become when decoded as Windows-1252.
+const CP1252_CONTINUATIONS: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+/// Whether `text` looks like UTF-8 that was decoded as Latin-1 or Windows-1252 (mojibake):
+/// it holds a character that reads as a UTF-8 lead byte (`Ã`, `â`, ..) followed by as many
+/// characters reading as continuation bytes as that lead announces, as in `CafÃ©` for
+/// `Café` or `â€™` for `’`. A heuristic: such sequences are rare in genuine text.
+pub fn looks_mojibake(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    chars.iter().enumerate().any(|(index, &c)| {
+        let continuations = match c {
+            '\u{c2}'..='\u{df}' => 1,
+            '\u{e0}'..='\u{ef}' => 2,
+            _ => return false,
+        };
+        chars
+            .get(index + 1..=index + continuations)
+            .is_some_and(|next| next.iter().all(|&c| is_continuation(c)))
+    })
+}
+
+fn is_continuation(c: char) -> bool {
+    ('\u{80}'..='\u{bf}').contains(&c) || CP1252_CONTINUATIONS.contains(c)
+}
@@ -15,8 +15,143 @@ pub enum EvaluationError {
     InvalidFields(String),
     #[error("field structures must be JSON objects or arrays")]
     InvalidFieldStructure,
-    #[error("failed to parse JSON: {0}")]
-    InvalidJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    InvalidRecord(RecordIssue),
+    #[error(
+        "{} invalid records:{}",
+        .0.len(),
+        .0.iter().map(|issue| format!("\n  - {issue}")).collect::<String>()
+    )]
+    InvalidRecords(Vec<RecordIssue>),
+    #[error("embedded ground truth digest {actual} does not match build info ({expected})")]
+    GroundTruthDigestMismatch { expected: String, actual: String },
+    #[error("ground-truth document '{0}' has no field '{1}'")]
+    UnknownField(String, String),
+    #[error("invalid score formula: {0}")]
+    InvalidScoreFormula(String),
+    #[error("failed to parse JSON: {source}{snippet}")]
+    InvalidJson {
+        #[source]
+        source: serde_json::Error,
+        snippet: String,
+    },
+    #[error("included file not found: {}", .0.display())]
+    IncludeNotFound(PathBuf),
+    #[error(
+        "cyclic $include: {}",
+        .0.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" -> ")
+    )]
+    IncludeCycle(Vec<PathBuf>),
+    #[error("no *.json metrics files in baseline directory {}", .0.display())]
+    NoBaseline(PathBuf),
+    #[error("object-map input must be a JSON object of document id to fields")]
+    InvalidObjectMap,
     #[error(transparent)]
     Io(#[from] io::Error),
 }
+
+/// What is wrong with a single record of a documents payload.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RecordProblem {
+    #[error("record is not a JSON object")]
+    NotAnObject,
+    #[error("missing or non-string 'document_id'")]
+    MissingDocumentId,
+    #[error("'document_id' is blank")]
+    BlankDocumentId,
+    #[error("duplicate document_id (first seen in record {0})")]
+    DuplicateDocumentId(usize),
+    #[error("'fields' must be a JSON object")]
+    InvalidFields,
+    #[error("field structures must be JSON objects or arrays")]
+    InvalidFieldStructure,
+}
+
+/// A [`RecordProblem`] located by its zero-based position in the payload array.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "record {index}{}: {problem}",
+    .document_id.as_ref().map(|id| format!(" (document '{id}')")).unwrap_or_default()
+)]
+pub struct RecordIssue {
+    pub index: usize,
+    pub document_id: Option<String>,
+    pub problem: RecordProblem,
+}
+
+/// Lines of source shown on each side of the line a JSON parse error points at.
+const SNIPPET_CONTEXT_LINES: usize = 2;
+/// Characters shown on each side of the error column when the offending line is long.
+const SNIPPET_COLUMN_WINDOW: usize = 60;
+
+impl EvaluationError {
+    /// Wraps a JSON parse error together with a preview of `payload` around the error location.
+    pub fn invalid_json(source: serde_json::Error, payload: &str) -> Self {
+        let snippet = json_snippet(payload, source.line(), source.column());
+        Self::InvalidJson { source, snippet }
+    }
+}
+
+impl From<serde_json::Error> for EvaluationError {
+    fn from(source: serde_json::Error) -> Self {
+        Self::InvalidJson {
+            source,
+            snippet: String::new(),
+        }
+    }
+}
+
+fn json_snippet(payload: &str, line: usize, column: usize) -> String {
+    if line == 0 {
+        return String::new();
+    }
+    let lines: Vec<&str> = payload.lines().collect();
+    if line > lines.len() {
+        return String::new();
+    }
+    let first = line.saturating_sub(SNIPPET_CONTEXT_LINES).max(1);
+    let last = (line + SNIPPET_CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+    let mut snippet = String::new();
+    for number in first..=last {
+        let text = lines[number - 1];
+        if number == line {
+            let (excerpt, caret) = excerpt_around(text, char_column(text, column));
+            snippet.push_str(&format!("\n> {number:>width$} | {excerpt}"));
+            snippet.push_str(&format!("\n  {:>width$} | {}^", "", " ".repeat(caret)));
+        } else {
+            let (excerpt, _) = excerpt_around(text, 1);
+            snippet.push_str(&format!("\n  {number:>width$} | {excerpt}"));
+        }
+    }
+    snippet
+}
+
+/// Converts serde_json's one-based byte column to a one-based character column, so the caret
+/// lands under the right character on lines with multibyte text.
+fn char_column(text: &str, column: usize) -> usize {
+    let byte = column.saturating_sub(1);
+    text.char_indices()
+        .take_while(|(start, _)| *start < byte)
+        .count()
+        + 1
+}
+
+/// Trims very long lines (e.g. minified JSON) to a window around `column`, returning the
+/// excerpt and the caret offset of `column` within it.
+fn excerpt_around(text: &str, column: usize) -> (String, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    let index = column.saturating_sub(1).min(chars.len());
+    let start = index.saturating_sub(SNIPPET_COLUMN_WINDOW);
+    let end = (index + SNIPPET_COLUMN_WINDOW).min(chars.len());
+    let mut excerpt: String = chars[start..end].iter().collect();
+    let mut caret = index - start;
+    if start > 0 {
+        excerpt.insert_str(0, "...");
+        caret += 3;
+    }
+    if end < chars.len() {
+        excerpt.push_str("...");
+    }
+    (excerpt, caret)
+}
@@ -1,15 +1,45 @@
-use serde::Deserialize;
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::io::{Cursor, Read};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::analysis::{
+    check_field_minimums, detect_systematic_biases, outlier_factor, NumericOutlier,
+};
+use crate::annotations::field_pattern;
+use crate::binary::{binary_similarity, decode_base64};
+use crate::durations::parse_duration;
 use crate::embedded;
-use crate::error::EvaluationError;
-use crate::metrics::EvaluationMetrics;
+use crate::encoding::looks_mojibake;
+use crate::error::{EvaluationError, RecordIssue, RecordProblem};
+use crate::formula::{ComponentValues, ScoreFormula};
+use crate::geo::{coordinate_similarity, Coordinate};
+use crate::includes::resolve_includes;
+use crate::metrics::{
+    round, sort_outliers, AggregateTotals, DocumentCap, DocumentScore, EvaluationMetrics,
+    ExcludedDocuments, FieldKind, FieldScore,
+};
+use crate::money::{base_amount, money_similarity};
+use crate::numbers::{
+    extract_numbers, parse_localized, parse_percent, parse_scientific, split_numbers,
+};
+use crate::options::{
+    ComponentWeighting, CoverageMode, EvaluationOptions, InputFormat, TextMetric, ValidationMode,
+};
+use crate::profile::{self, PhaseProfile};
+use crate::redact::{to_output_json, to_output_line};
+use crate::retry::{read_with_retry, ReadRetry};
+use crate::sequences::{align_sequences, SequenceAlignment, SequenceOperations};
+use crate::sets::{set_similarity, SetMode};
+use crate::transforms::apply_pipeline;
+use crate::units::{quantity_score, Quantity};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Document {
     pub document_id: String,
     pub fields: Value,
@@ -22,30 +52,272 @@ struct RawDocument {
 }
 
 pub fn load_ground_truth_from_embed() -> Result<BTreeMap<String, Document>, EvaluationError> {
-    let bytes = embedded::ground_truth_bytes();
-    let mut decoder = flate2::read::ZlibDecoder::new(Cursor::new(bytes));
-    let mut payload = String::new();
-    decoder.read_to_string(&mut payload)?;
-    parse_documents(&payload)
+    load_ground_truth_from_shards(embedded::ground_truth_shards())
+}
+
+/// Reassembles ground truth from zlib-compressed JSON shards, as `build.rs` embeds them.
+/// Shards are decompressed and parsed one at a time, so only a single decompressed shard is
+/// held in memory; a document id repeated in a later shard wins, as in a single file.
+pub fn load_ground_truth_from_shards(
+    shards: &[&[u8]],
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    if shards.is_empty() {
+        return Err(EvaluationError::EmptyInput);
+    }
+    let mut documents = BTreeMap::new();
+    for shard in shards {
+        documents.extend(parse_documents(&embedded::decompress_shard(shard)?)?);
+    }
+    Ok(documents)
 }
 
 pub fn load_ground_truth_from_path(
     path: &Path,
 ) -> Result<BTreeMap<String, Document>, EvaluationError> {
-    let payload = fs::read_to_string(path)?;
+    load_ground_truth_from_path_with(path, ValidationMode::default())
+}
+
+pub fn load_ground_truth_from_path_with(
+    path: &Path,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    let payload = resolve_includes(&read_payload(path)?, path)?;
+    parse_documents_with(&payload, validation)
+}
+
+/// Parses ground truth already read into memory (e.g. fetched over HTTP), validated as
+/// [`load_ground_truth_from_path_with`] validates a file. `$include` directives are left as
+/// they are.
+pub fn load_ground_truth_from_payload_with(
+    payload: &str,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    parse_documents_with(payload, validation)
+}
+
+/// Document id given to a document passed inline with `--truth-json` / `--predict-json`.
+pub const INLINE_DOCUMENT_ID: &str = "inline";
+
+/// Loads a single document from the JSON of its `fields` object, as passed inline on the
+/// command line. The document gets the id [`INLINE_DOCUMENT_ID`].
+pub fn load_inline_document(fields: &str) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    let fields: Value =
+        serde_json::from_str(fields).map_err(|err| EvaluationError::invalid_json(err, fields))?;
+    let payload = serde_json::to_string(&serde_json::json!([{
+        "document_id": INLINE_DOCUMENT_ID,
+        "fields": fields,
+    }]))?;
     parse_documents(&payload)
 }
 
 pub fn load_predictions(path: &Path) -> Result<BTreeMap<String, Document>, EvaluationError> {
-    if !path.exists() {
+    load_predictions_with(path, ValidationMode::default())
+}
+
+pub fn load_predictions_with(
+    path: &Path,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    load_predictions_as(path, validation, InputFormat::Records)
+}
+
+/// Loads predictions laid out as `format`; object maps are read as the equivalent records.
+pub fn load_predictions_as(
+    path: &Path,
+    validation: ValidationMode,
+    format: InputFormat,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    load_predictions_retrying(path, validation, format, &ReadRetry::default())
+}
+
+/// [`load_predictions_as`], retrying the file read on transient IO errors as `retry` allows.
+/// A missing file is reported at once as [`EvaluationError::FileNotFound`].
+pub fn load_predictions_retrying(
+    path: &Path,
+    validation: ValidationMode,
+    format: InputFormat,
+    retry: &ReadRetry,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    if !is_stdin(path) && !path.exists() {
         return Err(EvaluationError::FileNotFound(path.to_path_buf()));
     }
-    let payload = fs::read_to_string(path)?;
-    parse_documents(&payload)
+    let payload = if is_stdin(path) {
+        read_payload(path)?
+    } else {
+        read_with_retry(retry, || fs::read_to_string(path))?
+    };
+    match format {
+        InputFormat::Records => parse_documents_with(&payload, validation),
+        InputFormat::ObjectMap => parse_documents_with(&object_map_records(&payload)?, validation),
+    }
+}
+
+/// Rewrites an object of document id to fields as an array of records, in key order.
+fn object_map_records(payload: &str) -> Result<String, EvaluationError> {
+    let map: Value =
+        serde_json::from_str(payload).map_err(|err| EvaluationError::invalid_json(err, payload))?;
+    let Value::Object(map) = map else {
+        return Err(EvaluationError::InvalidObjectMap);
+    };
+    let records: Vec<Value> = map
+        .into_iter()
+        .map(|(document_id, fields)| {
+            serde_json::json!({
+                "document_id": document_id,
+                "fields": fields,
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string(&records)?)
+}
+
+/// Replaces every object that carries `value_key` with that key's value, so fields wrapped
+/// with provenance such as `{"value": 12.5, "page": 3, "bbox": [...]}` score as bare values.
+pub fn unwrap_field_values(documents: &mut BTreeMap<String, Document>, value_key: &str) {
+    for document in documents.values_mut() {
+        let fields = std::mem::take(&mut document.fields);
+        document.fields = match fields {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, unwrap_value(value, value_key)))
+                    .collect(),
+            ),
+            other => other,
+        };
+    }
+}
+
+fn unwrap_value(value: Value, value_key: &str) -> Value {
+    match value {
+        Value::Object(mut map) => match map.remove(value_key) {
+            Some(inner) => unwrap_value(inner, value_key),
+            None => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, unwrap_value(value, value_key)))
+                    .collect(),
+            ),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| unwrap_value(item, value_key))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Returns `true` when `path` is the conventional `-` placeholder for standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Whether a `--ground-truth` argument is an `http://` or `https://` URL rather than a path.
+pub fn is_url(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|path| path.starts_with("http://") || path.starts_with("https://"))
+}
+
+fn read_payload(path: &Path) -> Result<String, EvaluationError> {
+    if is_stdin(path) {
+        let mut payload = String::new();
+        io::stdin().read_to_string(&mut payload)?;
+        return Ok(payload);
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+fn parse_documents_with(
+    payload: &str,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    match validation {
+        ValidationMode::Lenient => parse_documents(payload),
+        ValidationMode::FailFast => parse_documents_strict(payload, false),
+        ValidationMode::CollectErrors => parse_documents_strict(payload, true),
+    }
+}
+
+/// Parses records one by one so that any problem can be reported with its position.
+/// Returns the first [`RecordIssue`] found, or every issue when `collect` is set.
+fn parse_documents_strict(
+    payload: &str,
+    collect: bool,
+) -> Result<BTreeMap<String, Document>, EvaluationError> {
+    let records: Vec<Value> =
+        serde_json::from_str(payload).map_err(|err| EvaluationError::invalid_json(err, payload))?;
+    if records.is_empty() {
+        return Err(EvaluationError::EmptyInput);
+    }
+    let mut first_seen: BTreeMap<String, usize> = BTreeMap::new();
+    let mut documents = BTreeMap::new();
+    let mut issues = Vec::new();
+    for (index, record) in records.into_iter().enumerate() {
+        match validate_record(index, record, &first_seen) {
+            Ok(document) => {
+                first_seen.insert(document.document_id.clone(), index);
+                documents.insert(document.document_id.clone(), document);
+            }
+            Err(issue) if !collect => return Err(EvaluationError::InvalidRecord(issue)),
+            Err(issue) => {
+                // Remember ids of rejected records so later copies are still flagged.
+                if let Some(document_id) = &issue.document_id {
+                    first_seen.entry(document_id.clone()).or_insert(index);
+                }
+                issues.push(issue);
+            }
+        }
+    }
+    if !issues.is_empty() {
+        return Err(EvaluationError::InvalidRecords(issues));
+    }
+    Ok(documents)
+}
+
+fn validate_record(
+    index: usize,
+    record: Value,
+    first_seen: &BTreeMap<String, usize>,
+) -> Result<Document, RecordIssue> {
+    let issue = |document_id: Option<&str>, problem| RecordIssue {
+        index,
+        document_id: document_id.map(str::to_string),
+        problem,
+    };
+    let Value::Object(mut record) = record else {
+        return Err(issue(None, RecordProblem::NotAnObject));
+    };
+    let Some(Value::String(document_id)) = record.remove("document_id") else {
+        return Err(issue(None, RecordProblem::MissingDocumentId));
+    };
+    if document_id.trim().is_empty() {
+        return Err(issue(Some(&document_id), RecordProblem::BlankDocumentId));
+    }
+    if let Some(first) = first_seen.get(&document_id) {
+        return Err(issue(
+            Some(&document_id),
+            RecordProblem::DuplicateDocumentId(*first),
+        ));
+    }
+    let fields = match record.remove("fields") {
+        Some(fields @ Value::Object(_)) => fields,
+        _ => return Err(issue(Some(&document_id), RecordProblem::InvalidFields)),
+    };
+    if flatten_fields(&fields, Vec::new()).is_err() {
+        return Err(issue(
+            Some(&document_id),
+            RecordProblem::InvalidFieldStructure,
+        ));
+    }
+    Ok(Document {
+        document_id,
+        fields,
+    })
 }
 
 fn parse_documents(payload: &str) -> Result<BTreeMap<String, Document>, EvaluationError> {
-    let records: Vec<RawDocument> = serde_json::from_str(payload)?;
+    let records: Vec<RawDocument> =
+        serde_json::from_str(payload).map_err(|err| EvaluationError::invalid_json(err, payload))?;
     if records.is_empty() {
         return Err(EvaluationError::EmptyInput);
     }
@@ -69,149 +341,1131 @@ pub fn evaluate_predictions(
     ground_truth: &BTreeMap<String, Document>,
     predictions: &BTreeMap<String, Document>,
 ) -> Result<EvaluationMetrics, EvaluationError> {
-    if ground_truth.is_empty() {
-        return Err(EvaluationError::EmptyInput);
-    }
+    evaluate_predictions_with_options(ground_truth, predictions, &EvaluationOptions::default())
+}
 
-    let mut total_fields = 0_u32;
-    let mut docs_with_predictions = 0_u32;
-    let mut matched_fields = 0_u32;
+pub fn evaluate_predictions_with_options(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<EvaluationMetrics, EvaluationError> {
+    evaluate_selected(ground_truth, predictions, options, |gt, preds| {
+        score_in_parallel(gt, preds, options)
+    })
+}
 
-    let mut numeric_total = 0_u32;
-    let mut numeric_score = 0.0_f64;
-    let mut text_total = 0_u32;
-    let mut text_score = 0.0_f64;
+/// Where and how often [`evaluate_predictions_with_checkpoint`] saves its progress.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub path: PathBuf,
+    /// Ground-truth documents scored between two saves.
+    pub every: usize,
+    /// Continue from `path`, if it exists, instead of starting over.
+    pub resume: bool,
+}
 
-    let mut missing_docs: Vec<String> = Vec::new();
-    let extra_docs: Vec<String> = predictions
-        .keys()
-        .filter(|key| !ground_truth.contains_key(*key))
-        .cloned()
+/// Like [`evaluate_predictions_with_options`], but scores sequentially and saves the
+/// [`MetricsAccumulator`] state to the checkpoint after every `every` ground-truth documents
+/// and once more when all of them are scored. When resuming, documents already in the
+/// checkpoint are skipped; because documents are always scored in id order, the result is
+/// the same as that of an uninterrupted run with the same options.
+pub fn evaluate_predictions_with_checkpoint(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    checkpoint: &Checkpoint,
+) -> Result<EvaluationMetrics, EvaluationError> {
+    evaluate_selected(ground_truth, predictions, options, |gt, preds| {
+        let mut accumulator = if checkpoint.resume && checkpoint.path.exists() {
+            MetricsAccumulator::resume(&checkpoint.path, options.clone())?
+        } else {
+            MetricsAccumulator::new(options.clone())
+        };
+        let mut unsaved = 0;
+        for (doc_id, gt_doc) in gt {
+            if accumulator.has_document(doc_id) {
+                continue;
+            }
+            accumulator.add_document(gt_doc, preds.get(doc_id))?;
+            unsaved += 1;
+            if unsaved >= checkpoint.every.max(1) {
+                accumulator.save_checkpoint(&checkpoint.path)?;
+                unsaved = 0;
+            }
+        }
+        accumulator.save_checkpoint(&checkpoint.path)?;
+        for (doc_id, pred_doc) in preds {
+            if !gt.contains_key(doc_id) {
+                accumulator.add_extra_prediction(pred_doc)?;
+            }
+        }
+        accumulator.finalize()
+    })
+}
+
+/// Hands the documents [`selected_documents`] picks to `score`, recording the cap and the
+/// documents left out by `intersection_only` on the metrics.
+fn evaluate_selected(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    score: impl FnOnce(
+        &BTreeMap<String, Document>,
+        &BTreeMap<String, Document>,
+    ) -> Result<EvaluationMetrics, EvaluationError>,
+) -> Result<EvaluationMetrics, EvaluationError> {
+    let selection = selected_documents(ground_truth, predictions, options);
+    let mut metrics = score(&selection.ground_truth, &selection.predictions)?;
+    metrics.document_cap = selection.document_cap;
+    metrics.excluded_documents = selection.excluded_documents;
+    Ok(metrics)
+}
+
+/// Scores contiguous shards of the ground truth on up to `options.threads` threads and merges
+/// them with [`EvaluationMetrics::merge`], which gives the same metrics as a single pass.
+/// Predictions go with their ground-truth document; those without one go to the first shard.
+fn score_in_parallel(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<EvaluationMetrics, EvaluationError> {
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, usize::from),
+        threads => threads,
+    };
+    if threads <= 1 || ground_truth.len() <= 1 {
+        return score_documents(ground_truth, predictions, options);
+    }
+
+    let shard_size = ground_truth.len().div_ceil(threads);
+    let ids: Vec<&String> = ground_truth.keys().collect();
+    let mut shards: Vec<(BTreeMap<String, Document>, BTreeMap<String, Document>)> = ids
+        .chunks(shard_size)
+        .map(|chunk| {
+            let select = |documents: &BTreeMap<String, Document>| {
+                chunk
+                    .iter()
+                    .filter_map(|doc_id| documents.get(*doc_id))
+                    .map(|document| (document.document_id.clone(), document.clone()))
+                    .collect::<BTreeMap<_, _>>()
+            };
+            (select(ground_truth), select(predictions))
+        })
         .collect();
+    for (doc_id, prediction) in predictions {
+        if !ground_truth.contains_key(doc_id) {
+            shards[0].1.insert(doc_id.clone(), prediction.clone());
+        }
+    }
 
-    let mut missing_field_count = 0_u32;
-    let mut extra_field_count = 0_u32;
-    let mut missing_fields: BTreeMap<String, Vec<String>> = BTreeMap::new();
-    let mut extra_fields: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = shards
+            .iter()
+            .map(|(gt, preds)| scope.spawn(move || score_documents(gt, preds, options)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("scoring thread panicked"))
+            .collect()
+    });
+    let mut results = results.into_iter();
+    let first = results.next().expect("at least one shard")?;
+    results.try_fold(first, |merged, shard| merged.merge(shard?))
+}
+
+/// The documents [`evaluate_predictions_with_options`] scores, with what was left out.
+pub(crate) struct Selection<'a> {
+    pub(crate) ground_truth: Cow<'a, BTreeMap<String, Document>>,
+    pub(crate) predictions: Cow<'a, BTreeMap<String, Document>>,
+    /// Set when `max_documents` applies.
+    pub(crate) document_cap: Option<DocumentCap>,
+    /// Set with `intersection_only`, counted after the cap.
+    pub(crate) excluded_documents: Option<ExcludedDocuments>,
+}
+
+/// Applies `max_documents`, then `intersection_only`: the cap keeps the first ground-truth
+/// documents in id order and the predictions for them, and the intersection keeps the
+/// documents present on both sides. Without either option the documents are borrowed as
+/// they are.
+pub(crate) fn selected_documents<'a>(
+    ground_truth: &'a BTreeMap<String, Document>,
+    predictions: &'a BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Selection<'a> {
+    let mut selection = Selection {
+        ground_truth: Cow::Borrowed(ground_truth),
+        predictions: Cow::Borrowed(predictions),
+        document_cap: None,
+        excluded_documents: None,
+    };
+    if let Some(max_documents) = options.max_documents {
+        let capped: BTreeMap<String, Document> = ground_truth
+            .iter()
+            .take(max_documents)
+            .map(|(doc_id, document)| (doc_id.clone(), document.clone()))
+            .collect();
+        selection.predictions = Cow::Owned(shared_documents(predictions, &capped));
+        selection.ground_truth = Cow::Owned(capped);
+        selection.document_cap = Some(DocumentCap {
+            max_documents: max_documents as u32,
+            ground_truth_documents: ground_truth.len() as u32,
+        });
+    }
+    if options.intersection_only {
+        let shared_ground_truth = shared_documents(&selection.ground_truth, &selection.predictions);
+        let shared_predictions = shared_documents(&selection.predictions, &selection.ground_truth);
+        selection.excluded_documents = Some(ExcludedDocuments {
+            missing_documents: (selection.ground_truth.len() - shared_ground_truth.len()) as u32,
+            extra_documents: (selection.predictions.len() - shared_predictions.len()) as u32,
+        });
+        selection.ground_truth = Cow::Owned(shared_ground_truth);
+        selection.predictions = Cow::Owned(shared_predictions);
+    }
+    selection
+}
 
+/// Documents of `documents` whose id also appears in `other`.
+fn shared_documents(
+    documents: &BTreeMap<String, Document>,
+    other: &BTreeMap<String, Document>,
+) -> BTreeMap<String, Document> {
+    documents
+        .iter()
+        .filter(|(doc_id, _)| other.contains_key(*doc_id))
+        .map(|(doc_id, document)| (doc_id.clone(), document.clone()))
+        .collect()
+}
+
+fn score_documents(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<EvaluationMetrics, EvaluationError> {
+    let mut accumulator = MetricsAccumulator::new(options.clone());
     for (doc_id, gt_doc) in ground_truth {
-        let gt_flat = flatten_fields(&gt_doc.fields, Vec::new())?;
-        total_fields += gt_flat.len() as u32;
-        let Some(pred_doc) = predictions.get(doc_id) else {
-            missing_docs.push(doc_id.clone());
-            missing_field_count += gt_flat.len() as u32;
-            if !gt_flat.is_empty() {
-                missing_fields.insert(doc_id.clone(), gt_flat.keys().cloned().collect());
+        accumulator.add_document(gt_doc, predictions.get(doc_id))?;
+    }
+    for (doc_id, pred_doc) in predictions {
+        if !ground_truth.contains_key(doc_id) {
+            accumulator.add_extra_prediction(pred_doc)?;
+        }
+    }
+    accumulator.finalize()
+}
+
+/// Evaluation state fed one ground-truth document at a time, for online evaluation.
+/// Batch scoring is built on it, so accumulating every document gives the same metrics as
+/// [`evaluate_predictions_with_options`] (without `intersection_only`). Each document id
+/// should be added once.
+///
+/// The state can be saved with [`MetricsAccumulator::save_checkpoint`] and picked up again
+/// with [`MetricsAccumulator::resume`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsAccumulator {
+    #[serde(skip)]
+    options: EvaluationOptions,
+    #[serde(skip)]
+    document_ids: BTreeSet<String>,
+    num_documents: u32,
+    total_fields: u32,
+    coverage_sum: f64,
+    matched_fields: u32,
+    totals: SimilarityTotals,
+    missing_docs: Vec<String>,
+    extra_docs: Vec<String>,
+    missing_field_count: u32,
+    extra_field_count: u32,
+    missing_fields: BTreeMap<String, Vec<String>>,
+    extra_fields: BTreeMap<String, Vec<String>>,
+    numeric_pairs: BTreeMap<String, Vec<(f64, f64)>>,
+    truncated_field_count: u32,
+    #[serde(default)]
+    near_miss_numeric_count: u32,
+    malformed_binary_fields: BTreeMap<String, Vec<String>>,
+    malformed_coordinate_fields: BTreeMap<String, Vec<String>>,
+    normalized_percent_fields: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    mojibake_fields: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    sequence_operations: BTreeMap<String, SequenceOperations>,
+    numeric_outliers: Vec<NumericOutlier>,
+    documents: Vec<DocumentScore>,
+    #[serde(default)]
+    profile: PhaseProfile,
+}
+
+impl MetricsAccumulator {
+    pub fn new(options: EvaluationOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// Scores one ground-truth document against its prediction, if any.
+    pub fn add_document(
+        &mut self,
+        gt_doc: &Document,
+        pred_doc: Option<&Document>,
+    ) -> Result<(), EvaluationError> {
+        let options = &self.options;
+        let doc_id = &gt_doc.document_id;
+        let started = profile::start(options.profile);
+        let gt_flat = ground_truth_fields(&gt_doc.fields, options)?;
+        profile::stop(started, &mut self.profile.flatten_ms);
+        self.document_ids.insert(doc_id.clone());
+        self.num_documents += 1;
+        self.total_fields += gt_flat.len() as u32;
+        let mut doc_totals = SimilarityTotals::default();
+        let Some(pred_doc) = pred_doc else {
+            self.missing_docs.push(doc_id.clone());
+            self.missing_field_count += gt_flat.len() as u32;
+            let missing_paths: Vec<String> = gt_flat.keys().cloned().collect();
+            if !missing_paths.is_empty() {
+                self.missing_fields
+                    .insert(doc_id.clone(), missing_paths.clone());
             }
-            for value in gt_flat.values() {
-                if value.is_number() {
-                    numeric_total += 1;
+            for (path, value) in gt_flat.iter() {
+                let numeric = is_numeric_field(options, path, value, None);
+                if !options.field_buckets.scores(numeric) {
+                    continue;
+                }
+                if numeric {
+                    doc_totals.add_numeric(None, options.field_weight(path));
                 } else {
-                    text_total += 1;
+                    doc_totals.add_text(None, options.text_field_weight(path, value));
                 }
             }
-            continue;
+            self.totals.merge(&doc_totals);
+            self.documents.push(document_score(
+                options,
+                doc_id,
+                false,
+                gt_flat.len() as u32,
+                0,
+                &doc_totals,
+                missing_paths,
+                Vec::new(),
+            )?);
+            return Ok(());
         };
 
-        docs_with_predictions += 1;
-        let pred_flat = flatten_fields(&pred_doc.fields, Vec::new())?;
+        let started = profile::start(options.profile);
+        let (mut pred_flat, remapped_fields, aliased_fields) =
+            predicted_fields(&pred_doc.fields, options)?;
+        drop_ignored_fields(&mut pred_flat, &ignored_fields(&gt_doc.fields, options)?);
+        profile::stop(started, &mut self.profile.flatten_ms);
+
+        let started = profile::start(options.profile);
         let gt_paths: BTreeSet<_> = gt_flat.keys().cloned().collect();
         let pred_paths: BTreeSet<_> = pred_flat.keys().cloned().collect();
         let matched: Vec<_> = gt_paths.intersection(&pred_paths).collect();
-        matched_fields += matched.len() as u32;
+        self.matched_fields += matched.len() as u32;
+        self.coverage_sum += document_coverage(
+            options.coverage_mode,
+            matched.len() as u32,
+            gt_flat.len() as u32,
+        );
 
         let missing_paths: Vec<String> = gt_paths.difference(&pred_paths).cloned().collect();
         if !missing_paths.is_empty() {
-            missing_field_count += missing_paths.len() as u32;
-            missing_fields.insert(doc_id.clone(), missing_paths);
+            self.missing_field_count += missing_paths.len() as u32;
+            self.missing_fields
+                .insert(doc_id.clone(), missing_paths.clone());
         }
 
         let extra_paths: Vec<String> = pred_paths.difference(&gt_paths).cloned().collect();
         if !extra_paths.is_empty() {
-            extra_field_count += extra_paths.len() as u32;
-            extra_fields.insert(doc_id.clone(), extra_paths);
+            self.extra_field_count += extra_paths.len() as u32;
+            self.extra_fields
+                .insert(doc_id.clone(), extra_paths.clone());
+        }
+        profile::stop(started, &mut self.profile.set_diff_ms);
+
+        let mojibake_paths: Vec<String> = pred_flat
+            .iter()
+            .filter(|(_, value)| value.as_str().is_some_and(looks_mojibake))
+            .map(|(path, _)| path.clone())
+            .collect();
+        if !mojibake_paths.is_empty() {
+            self.mojibake_fields.insert(doc_id.clone(), mojibake_paths);
         }
 
+        let mut field_scores = Vec::with_capacity(gt_flat.len());
+        let zero = Value::from(0);
         for (path, expected) in gt_flat.iter() {
-            let predicted = pred_flat.get(path);
-            if expected.is_number() {
-                numeric_total += 1;
-                if let Some(score) = numeric_similarity(expected, predicted) {
-                    numeric_score += score;
+            let (expected, predicted) =
+                null_numbers_as_zero(options, path, expected, pred_flat.get(path), &zero);
+            let numeric = is_numeric_field(options, path, expected, predicted);
+            // Fields of a skipped bucket are still scored and reported per field; they are
+            // only left out of the similarity averages.
+            let averaged = options.field_buckets.scores(numeric);
+            let started = profile::start(options.profile);
+            if numeric {
+                if has_malformed_coordinate(options, path, expected, predicted) {
+                    self.malformed_coordinate_fields
+                        .entry(doc_id.clone())
+                        .or_default()
+                        .push(path.clone());
+                }
+                if reads_percent(options, path, expected, predicted) {
+                    self.normalized_percent_fields
+                        .entry(doc_id.clone())
+                        .or_default()
+                        .push(path.clone());
+                }
+                let similarity = numeric_similarity(options, path, expected, predicted);
+                if options
+                    .near_miss_band
+                    .zip(similarity)
+                    .is_some_and(|(band, score)| (band..1.0).contains(&score))
+                {
+                    self.near_miss_numeric_count += 1;
+                }
+                let score = options
+                    .decided_score(doc_id, path)
+                    .or_else(|| similarity.or_else(|| missing_credit(options, path, predicted)));
+                if averaged {
+                    doc_totals.add_numeric(score, options.field_weight(path));
+                }
+                field_scores.push(FieldScore {
+                    path: path.clone(),
+                    kind: FieldKind::Numeric,
+                    score: round(score.unwrap_or(0.0)),
+                    tolerance: options.numeric_tolerance_for(path),
+                    text_metric_scores: BTreeMap::new(),
+                    best_text_metric: None,
+                    expected: raw_value(options, Some(expected)),
+                    predicted: raw_value(options, predicted),
+                });
+                if let (Some(expected), Some(predicted)) = (
+                    field_number(options, path, expected),
+                    predicted.and_then(|value| field_number(options, path, value)),
+                ) {
+                    self.numeric_pairs
+                        .entry(path.clone())
+                        .or_default()
+                        .push((expected, predicted));
+                    if let Some(factor) = options
+                        .outlier_factor
+                        .and_then(|min_factor| outlier_factor(expected, predicted, min_factor))
+                    {
+                        self.numeric_outliers.push(NumericOutlier {
+                            document_id: doc_id.clone(),
+                            field: path.clone(),
+                            expected,
+                            predicted,
+                            factor: round(factor),
+                        });
+                    }
                 }
+                profile::stop(started, &mut self.profile.numeric_ms);
             } else {
-                text_total += 1;
-                if let Some(score) = text_similarity(expected, predicted) {
-                    text_score += score;
+                if exceeds_max_field_length(options, path, expected, predicted) {
+                    self.truncated_field_count += 1;
                 }
+                if has_malformed_binary(options, path, expected, predicted) {
+                    self.malformed_binary_fields
+                        .entry(doc_id.clone())
+                        .or_default()
+                        .push(path.clone());
+                }
+                let score = options.decided_score(doc_id, path).or_else(|| {
+                    text_similarity(options, path, expected, predicted)
+                        .or_else(|| missing_credit(options, path, predicted))
+                });
+                if let Some(alignment) = options
+                    .is_sequence(path)
+                    .then(|| sequence_alignment(options, path, expected, predicted?))
+                    .flatten()
+                {
+                    self.sequence_operations
+                        .entry(field_pattern(path))
+                        .or_default()
+                        .add(&alignment.operations);
+                }
+                if averaged {
+                    doc_totals.add_text(score, options.text_field_weight(path, expected));
+                }
+                let metric_scores = compared_text_metrics(options, path, expected, predicted);
+                field_scores.push(FieldScore {
+                    path: path.clone(),
+                    kind: FieldKind::Text,
+                    score: round(score.unwrap_or(0.0)),
+                    tolerance: None,
+                    best_text_metric: best_text_metric(&metric_scores),
+                    text_metric_scores: metric_scores
+                        .into_iter()
+                        .map(|(metric, score)| (metric.name().to_string(), round(score)))
+                        .collect(),
+                    expected: raw_value(options, Some(expected)),
+                    predicted: raw_value(options, predicted),
+                });
+                profile::stop(started, &mut self.profile.text_ms);
             }
         }
+        self.totals.merge(&doc_totals);
+        let mut document = document_score(
+            options,
+            doc_id,
+            true,
+            gt_flat.len() as u32,
+            matched.len() as u32,
+            &doc_totals,
+            missing_paths,
+            extra_paths,
+        )?;
+        document.field_scores = field_scores;
+        document.remapped_fields = remapped_fields;
+        document.aliased_fields = aliased_fields;
+        self.documents.push(document);
+        Ok(())
+    }
+
+    /// Whether a ground-truth document with this id has been added.
+    pub fn has_document(&self, document_id: &str) -> bool {
+        self.document_ids.contains(document_id)
+    }
+
+    /// Writes the accumulated state to `path` through a temporary file that is renamed into
+    /// place, so an interrupted write never leaves a truncated checkpoint behind.
+    pub fn save_checkpoint(&self, path: &Path) -> Result<(), EvaluationError> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_string(self)?)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
     }
 
-    for doc_id in extra_docs.iter() {
-        if let Some(pred_doc) = predictions.get(doc_id) {
-            let flat = flatten_fields(&pred_doc.fields, Vec::new())?;
-            if !flat.is_empty() {
-                extra_field_count += flat.len() as u32;
-                extra_fields.insert(doc_id.clone(), flat.keys().cloned().collect());
+    /// Continues from a state written by [`MetricsAccumulator::save_checkpoint`]. `options`
+    /// must be the ones the checkpointed documents were scored with.
+    pub fn resume(path: &Path, options: EvaluationOptions) -> Result<Self, EvaluationError> {
+        let payload = read_payload(path)?;
+        let mut accumulator: Self = serde_json::from_str(&payload)
+            .map_err(|err| EvaluationError::invalid_json(err, &payload))?;
+        for document in &mut accumulator.documents {
+            for field in &mut document.field_scores {
+                if field.kind == FieldKind::Numeric {
+                    field.tolerance = options.numeric_tolerance_for(&field.path);
+                }
             }
         }
+        accumulator.document_ids = accumulator
+            .documents
+            .iter()
+            .map(|document| document.document_id.clone())
+            .collect();
+        accumulator.options = options;
+        Ok(accumulator)
     }
 
-    let numeric_similarity = if numeric_total > 0 {
-        numeric_score / f64::from(numeric_total)
-    } else {
-        1.0
-    };
-    let text_similarity = if text_total > 0 {
-        text_score / f64::from(text_total)
+    /// Records a prediction whose document has no ground truth.
+    pub fn add_extra_prediction(&mut self, pred_doc: &Document) -> Result<(), EvaluationError> {
+        let flat = flatten_fields(&pred_doc.fields, Vec::new())?;
+        self.extra_docs.push(pred_doc.document_id.clone());
+        if !flat.is_empty() {
+            self.extra_field_count += flat.len() as u32;
+            self.extra_fields
+                .insert(pred_doc.document_id.clone(), flat.keys().cloned().collect());
+        }
+        Ok(())
+    }
+
+    /// Adds the contributions of the documents `other` has accumulated, in the order they
+    /// are summed when the documents are added one by one after those of `self`.
+    pub(crate) fn absorb(&mut self, other: &MetricsAccumulator) {
+        self.document_ids.extend(other.document_ids.iter().cloned());
+        self.num_documents += other.num_documents;
+        self.total_fields += other.total_fields;
+        self.coverage_sum += other.coverage_sum;
+        self.matched_fields += other.matched_fields;
+        self.totals.merge(&other.totals);
+        self.missing_docs.extend(other.missing_docs.iter().cloned());
+        self.extra_docs.extend(other.extra_docs.iter().cloned());
+        self.missing_field_count += other.missing_field_count;
+        self.extra_field_count += other.extra_field_count;
+        self.missing_fields.extend(other.missing_fields.clone());
+        self.extra_fields.extend(other.extra_fields.clone());
+        for (field, pairs) in &other.numeric_pairs {
+            self.numeric_pairs
+                .entry(field.clone())
+                .or_default()
+                .extend(pairs);
+        }
+        self.truncated_field_count += other.truncated_field_count;
+        self.near_miss_numeric_count += other.near_miss_numeric_count;
+        self.malformed_binary_fields
+            .extend(other.malformed_binary_fields.clone());
+        self.malformed_coordinate_fields
+            .extend(other.malformed_coordinate_fields.clone());
+        self.normalized_percent_fields
+            .extend(other.normalized_percent_fields.clone());
+        self.mojibake_fields.extend(other.mojibake_fields.clone());
+        for (field, operations) in &other.sequence_operations {
+            self.sequence_operations
+                .entry(field.clone())
+                .or_default()
+                .add(operations);
+        }
+        self.numeric_outliers
+            .extend(other.numeric_outliers.iter().cloned());
+        self.documents.extend(other.documents.iter().cloned());
+        self.profile = self.profile.merge(other.profile);
+    }
+
+    /// Metrics over the documents added so far, leaving the accumulator usable.
+    pub fn snapshot(&self) -> Result<EvaluationMetrics, EvaluationError> {
+        self.clone().finalize()
+    }
+
+    /// Aggregates the documents added so far. Document lists are ordered by id, whatever
+    /// order the documents were added in.
+    pub fn finalize(mut self) -> Result<EvaluationMetrics, EvaluationError> {
+        if self.num_documents == 0 {
+            return Err(EvaluationError::EmptyInput);
+        }
+        let options = &self.options;
+        self.missing_docs.sort();
+        self.extra_docs.sort();
+        self.documents
+            .sort_by(|a, b| a.document_id.cmp(&b.document_id));
+
+        let structural_completeness = ratio_or_one(self.matched_fields, self.total_fields);
+        let coverage = self.coverage_sum / f64::from(self.num_documents);
+        let overall_score = combine_components(
+            options.effective_component_weighting(),
+            options.score_formula.as_ref(),
+            coverage,
+            structural_completeness,
+            &self.totals,
+        )?;
+
+        let mut metrics = EvaluationMetrics::new(
+            self.num_documents,
+            self.total_fields,
+            coverage,
+            self.totals.numeric_similarity(),
+            self.totals.text_similarity(),
+            structural_completeness,
+            overall_score,
+            self.missing_docs,
+            self.extra_docs,
+            self.missing_field_count,
+            self.extra_field_count,
+            self.missing_fields,
+            self.extra_fields,
+        );
+        metrics.systematic_biases = detect_systematic_biases(&self.numeric_pairs);
+        sort_outliers(&mut self.numeric_outliers);
+        metrics.numeric_outliers = self.numeric_outliers;
+        metrics.documents = self.documents;
+        metrics.field_minimums =
+            check_field_minimums(&metrics.documents, &options.critical_fields());
+        metrics.truncated_field_count = self.truncated_field_count;
+        metrics.near_miss_numeric_count =
+            options.near_miss_band.map(|_| self.near_miss_numeric_count);
+        metrics.profile = options.profile.then(|| self.profile.rounded());
+        metrics.malformed_binary_fields = self.malformed_binary_fields;
+        metrics.malformed_coordinate_fields = self.malformed_coordinate_fields;
+        metrics.normalized_percent_fields = self.normalized_percent_fields;
+        metrics.mojibake_fields = self.mojibake_fields;
+        metrics.sequence_operations = self.sequence_operations;
+        metrics.text_length_weighting = options.text_length_weighting;
+        metrics.evaluated_buckets = options.field_buckets;
+        metrics.totals = AggregateTotals {
+            coverage_sum: self.coverage_sum,
+            matched_fields: self.matched_fields,
+            similarity: self.totals,
+            numeric_pairs: self.numeric_pairs,
+            component_weighting: options.effective_component_weighting(),
+            score_formula: options.score_formula.clone(),
+        };
+        Ok(metrics)
+    }
+}
+
+/// Running sums of per-field similarity scores, split by ground-truth value type. Each field
+/// contributes with its [`EvaluationOptions::field_weight`] (text fields with their
+/// [`EvaluationOptions::text_field_weight`]).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct SimilarityTotals {
+    numeric_weight: f64,
+    numeric_score: f64,
+    text_weight: f64,
+    text_score: f64,
+}
+
+impl SimilarityTotals {
+    fn add_numeric(&mut self, score: Option<f64>, weight: f64) {
+        self.numeric_weight += weight;
+        self.numeric_score += score.unwrap_or(0.0) * weight;
+    }
+
+    fn add_text(&mut self, score: Option<f64>, weight: f64) {
+        self.text_weight += weight;
+        self.text_score += score.unwrap_or(0.0) * weight;
+    }
+
+    pub(crate) fn merge(&mut self, other: &SimilarityTotals) {
+        self.numeric_weight += other.numeric_weight;
+        self.numeric_score += other.numeric_score;
+        self.text_weight += other.text_weight;
+        self.text_score += other.text_score;
+    }
+
+    pub(crate) fn numeric_similarity(&self) -> f64 {
+        if self.numeric_weight > 0.0 {
+            self.numeric_score / self.numeric_weight
+        } else {
+            1.0
+        }
+    }
+
+    pub(crate) fn text_similarity(&self) -> f64 {
+        if self.text_weight > 0.0 {
+            self.text_score / self.text_weight
+        } else {
+            1.0
+        }
+    }
+}
+
+pub(crate) fn ratio_or_one(numerator: u32, denominator: u32) -> f64 {
+    if denominator > 0 {
+        f64::from(numerator) / f64::from(denominator)
     } else {
         1.0
-    };
-    let structural_completeness = if total_fields > 0 {
-        f64::from(matched_fields) / f64::from(total_fields)
+    }
+}
+
+/// Coverage contributed by a document that has a prediction.
+fn document_coverage(mode: CoverageMode, matched_fields: u32, num_fields: u32) -> f64 {
+    match mode {
+        CoverageMode::Binary => 1.0,
+        CoverageMode::Weighted => ratio_or_one(matched_fields, num_fields),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn document_score(
+    options: &EvaluationOptions,
+    document_id: &str,
+    has_prediction: bool,
+    num_fields: u32,
+    matched_fields: u32,
+    totals: &SimilarityTotals,
+    missing_fields: Vec<String>,
+    extra_fields: Vec<String>,
+) -> Result<DocumentScore, EvaluationError> {
+    let coverage = if has_prediction {
+        document_coverage(options.coverage_mode, matched_fields, num_fields)
     } else {
-        1.0
-    };
-    let coverage = if ground_truth.is_empty() {
         0.0
-    } else {
-        f64::from(docs_with_predictions) / f64::from(ground_truth.len() as u32)
     };
-
-    let overall_score =
-        (coverage + structural_completeness + numeric_similarity + text_similarity) / 4.0;
-
-    Ok(EvaluationMetrics::new(
-        ground_truth.len() as u32,
-        total_fields,
-        coverage,
-        numeric_similarity,
-        text_similarity,
-        structural_completeness,
-        overall_score,
-        missing_docs,
-        extra_docs,
-        missing_field_count,
-        extra_field_count,
+    let structural_completeness = ratio_or_one(matched_fields, num_fields);
+    Ok(DocumentScore {
+        document_id: document_id.to_string(),
+        has_prediction,
+        num_fields,
+        matched_fields,
+        numeric_field_similarity: round(totals.numeric_similarity()),
+        text_field_similarity: round(totals.text_similarity()),
+        structural_completeness: round(structural_completeness),
+        overall_score: round(combine_components(
+            options.effective_component_weighting(),
+            options.score_formula.as_ref(),
+            coverage,
+            structural_completeness,
+            totals,
+        )?),
         missing_fields,
         extra_fields,
+        remapped_fields: BTreeMap::new(),
+        aliased_fields: BTreeMap::new(),
+        field_scores: Vec::new(),
+    })
+}
+
+/// Applies the configured score formula, falling back to [`overall_score`]. Fails when the
+/// formula divides by zero for these components.
+pub(crate) fn combine_components(
+    component_weighting: ComponentWeighting,
+    score_formula: Option<&ScoreFormula>,
+    coverage: f64,
+    structural_completeness: f64,
+    totals: &SimilarityTotals,
+) -> Result<f64, EvaluationError> {
+    match score_formula {
+        Some(formula) => formula.evaluate(&ComponentValues {
+            coverage,
+            structural: structural_completeness,
+            numeric: totals.numeric_similarity(),
+            text: totals.text_similarity(),
+        }),
+        None => Ok(overall_score(
+            component_weighting,
+            coverage,
+            structural_completeness,
+            (totals.numeric_similarity(), totals.numeric_weight),
+            (totals.text_similarity(), totals.text_weight),
+        )),
+    }
+}
+
+/// Weights of coverage, structural completeness, numeric and text similarity under
+/// `weighting`: the coefficients of the weighted sum [`overall_score`] computes.
+pub(crate) fn component_weights(
+    weighting: ComponentWeighting,
+    totals: &SimilarityTotals,
+) -> [f64; 4] {
+    let field_weight = totals.numeric_weight + totals.text_weight;
+    match weighting {
+        ComponentWeighting::ByCount if field_weight > 0.0 => [
+            0.25,
+            0.25,
+            totals.numeric_weight / field_weight / 2.0,
+            totals.text_weight / field_weight / 2.0,
+        ],
+        _ => [0.25; 4],
+    }
+}
+
+/// Combines the four component metrics into the overall score.
+///
+/// With [`ComponentWeighting::Equal`] this is the plain mean of the four components. With
+/// [`ComponentWeighting::ByCount`] coverage and structural completeness keep their quarter
+/// each, while the remaining half is the field-count weighted mean of the similarities:
+///
+/// `overall = (coverage + structural) / 4 + (n_num * numeric + n_text * text) / (n_num + n_text) / 2`
+///
+/// Under `--depth-decay` the field counts are the summed field weights.
+fn overall_score(
+    weighting: ComponentWeighting,
+    coverage: f64,
+    structural_completeness: f64,
+    (numeric_similarity, numeric_weight): (f64, f64),
+    (text_similarity, text_weight): (f64, f64),
+) -> f64 {
+    let field_weight = numeric_weight + text_weight;
+    match weighting {
+        ComponentWeighting::ByCount if field_weight > 0.0 => {
+            let similarity = (numeric_similarity * numeric_weight + text_similarity * text_weight)
+                / field_weight;
+            (coverage + structural_completeness) / 4.0 + similarity / 2.0
+        }
+        _ => (coverage + structural_completeness + numeric_similarity + text_similarity) / 4.0,
+    }
+}
+
+pub(crate) fn flatten_fields(
+    value: &Value,
+    path: Vec<String>,
+) -> Result<BTreeMap<String, Value>, EvaluationError> {
+    flatten_until(value, path, &|_| false)
+}
+
+/// Replaces JSON-encoded strings with the value they encode: in `x-json-string` fields, and
+/// with `parse_json_strings` any string that holds a JSON object or array. Strings that do
+/// not parse are kept.
+fn decode_json_strings(value: &mut Value, path: &mut Vec<String>, options: &EvaluationOptions) {
+    match value {
+        Value::String(text) if !path.is_empty() => {
+            let flagged = options.is_json_string(&path.join("."));
+            if !(flagged || options.parse_json_strings) {
+                return;
+            }
+            let Ok(decoded) = serde_json::from_str::<Value>(text) else {
+                return;
+            };
+            if flagged || decoded.is_object() || decoded.is_array() {
+                *value = decoded;
+                decode_json_strings(value, path, options);
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                path.push(key.clone());
+                decode_json_strings(child, path, options);
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter_mut().enumerate() {
+                path.push(idx.to_string());
+                decode_json_strings(item, path, options);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes leading and trailing `null` elements from every array, at any depth. Nulls
+/// between other elements are kept, as they hold a position.
+fn trim_null_array_elements(value: &mut Value) {
+    match value {
+        Value::Object(map) => map.values_mut().for_each(trim_null_array_elements),
+        Value::Array(items) => {
+            while items.last().is_some_and(Value::is_null) {
+                items.pop();
+            }
+            let leading = items.iter().take_while(|item| item.is_null()).count();
+            items.drain(..leading);
+            items.iter_mut().for_each(trim_null_array_elements);
+        }
+        _ => {}
+    }
+}
+
+/// Flattens fields for scoring: like [`flatten_fields`], but JSON-encoded strings are decoded
+/// first (see [`decode_json_strings`]), null array padding is trimmed with
+/// `trim_null_array_elements`, aliased keys are renamed (see [`resolve_aliases`]) and
+/// `x-coordinate` and `x-money` objects and `x-set` arrays stay whole so they are scored as one
+/// field.
+fn flatten_scored_fields(
+    value: &Value,
+    options: &EvaluationOptions,
+) -> Result<BTreeMap<String, Value>, EvaluationError> {
+    Ok(flatten_aliased_fields(value, options)?.0)
+}
+
+/// [`flatten_scored_fields`], also returning each aliased key's path with the path it had
+/// as written.
+fn flatten_aliased_fields(
+    value: &Value,
+    options: &EvaluationOptions,
+) -> Result<(FlatFields, BTreeMap<String, String>), EvaluationError> {
+    let is_leaf = |path: &str| is_scored_leaf(options, path);
+    let aliases = options.key_aliases();
+    if !options.decodes_json_strings() && !options.trim_null_array_elements && aliases.is_empty() {
+        return Ok((flatten_until(value, Vec::new(), &is_leaf)?, BTreeMap::new()));
+    }
+    let mut prepared = value.clone();
+    if options.decodes_json_strings() {
+        decode_json_strings(&mut prepared, &mut Vec::new(), options);
+    }
+    if options.trim_null_array_elements {
+        trim_null_array_elements(&mut prepared);
+    }
+    let mut aliased = BTreeMap::new();
+    if !aliases.is_empty() {
+        resolve_aliases(
+            &mut prepared,
+            &aliases,
+            &mut AliasedPath::default(),
+            &mut aliased,
+        );
+    }
+    Ok((flatten_until(&prepared, Vec::new(), &is_leaf)?, aliased))
+}
+
+/// Whether an object or array at `path` is scored as one field: `x-coordinate` and `x-money`
+/// objects, `x-set` arrays and sequence arrays.
+fn is_scored_leaf(options: &EvaluationOptions, path: &str) -> bool {
+    options.is_coordinate(path)
+        || options.is_money(path)
+        || options.set_mode_for(path).is_some()
+        || options.is_sequence(path)
+}
+
+/// A key path both as scored and as written, while [`resolve_aliases`] walks a value.
+#[derive(Default)]
+struct AliasedPath {
+    scored: Vec<String>,
+    written: Vec<String>,
+}
+
+/// Renames object keys found in `aliases` to their canonical name, at any nesting level, and
+/// records each renamed key's path. An alias stays as written when its object already has
+/// the canonical key, so the conflict shows up as an extra field.
+fn resolve_aliases(
+    value: &mut Value,
+    aliases: &BTreeMap<String, String>,
+    path: &mut AliasedPath,
+    aliased: &mut BTreeMap<String, String>,
+) {
+    match value {
+        Value::Object(map) => {
+            let keys: Vec<String> = map.keys().cloned().collect();
+            for key in keys {
+                let mut name = key.clone();
+                if let Some(canonical) = aliases
+                    .get(&key)
+                    .filter(|canonical| !map.contains_key(canonical.as_str()))
+                {
+                    let entry = map.remove(&key).expect("key present");
+                    map.insert(canonical.clone(), entry);
+                    name = canonical.clone();
+                }
+                path.scored.push(name.clone());
+                path.written.push(key.clone());
+                if name != key {
+                    aliased.insert(path.scored.join("."), path.written.join("."));
+                }
+                let entry = map.get_mut(&name).expect("key present");
+                resolve_aliases(entry, aliases, path, aliased);
+                path.scored.pop();
+                path.written.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter_mut().enumerate() {
+                path.scored.push(idx.to_string());
+                path.written.push(idx.to_string());
+                resolve_aliases(item, aliases, path, aliased);
+                path.scored.pop();
+                path.written.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Field values keyed by flattened path.
+type FlatFields = BTreeMap<String, Value>;
+
+/// Scored paths keyed to the path they were read from.
+type MovedPaths = BTreeMap<String, String>;
+
+/// Flattened ground-truth fields as scored: [`flatten_scored_fields`] without the fields
+/// holding the `--ignore-value` sentinel, with each field's `x-transform` pipeline applied.
+pub(crate) fn ground_truth_fields(
+    value: &Value,
+    options: &EvaluationOptions,
+) -> Result<FlatFields, EvaluationError> {
+    let mut fields = flatten_scored_fields(value, options)?;
+    if let Some(sentinel) = &options.ignore_value {
+        fields.retain(|_, value| !is_sentinel(value, sentinel));
+    }
+    Ok(label_codes(transform_fields(fields, options), options))
+}
+
+/// Paths of the ground-truth fields that hold the `--ignore-value` sentinel.
+fn ignored_fields(
+    value: &Value,
+    options: &EvaluationOptions,
+) -> Result<Vec<String>, EvaluationError> {
+    let Some(sentinel) = &options.ignore_value else {
+        return Ok(Vec::new());
+    };
+    Ok(flatten_scored_fields(value, options)?
+        .into_iter()
+        .filter(|(_, value)| is_sentinel(value, sentinel))
+        .map(|(path, _)| path)
+        .collect())
+}
+
+fn is_sentinel(value: &Value, sentinel: &str) -> bool {
+    value.as_str() == Some(sentinel)
+}
+
+/// Drops predicted fields at, or nested below, an ignored ground-truth path, so they do not
+/// count as extra fields.
+fn drop_ignored_fields(fields: &mut FlatFields, ignored: &[String]) {
+    if ignored.is_empty() {
+        return;
+    }
+    fields.retain(|path, _| {
+        !ignored.iter().any(|ignored| {
+            path.strip_prefix(ignored.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    });
+}
+
+/// Flattened predicted fields as scored: moved to their `--remap` paths, then transformed
+/// with the `x-transform` pipeline of the ground-truth path they land on. Also returns the
+/// remapped paths, as [`remap_predicted_fields`] does, and the aliased paths, as
+/// [`flatten_aliased_fields`] does.
+pub(crate) fn predicted_fields(
+    value: &Value,
+    options: &EvaluationOptions,
+) -> Result<(FlatFields, MovedPaths, MovedPaths), EvaluationError> {
+    let (fields, aliased) = flatten_aliased_fields(value, options)?;
+    let (fields, remapped) = remap_predicted_fields(fields, options);
+    Ok((
+        label_codes(transform_fields(fields, options), options),
+        remapped,
+        aliased,
     ))
 }
 
-fn flatten_fields(
+/// Replaces values found in their field's `--code-table` with the code's label, so a code
+/// and its label compare equal. Other values are left for the field's usual metric.
+fn label_codes(mut fields: FlatFields, options: &EvaluationOptions) -> FlatFields {
+    if options.code_tables.is_empty() {
+        return fields;
+    }
+    for (path, value) in fields.iter_mut() {
+        if let Some(label) = options
+            .code_table_for(path)
+            .and_then(|table| table.label(value))
+        {
+            *value = Value::String(label.to_string());
+        }
+    }
+    fields
+}
+
+fn transform_fields(mut fields: FlatFields, options: &EvaluationOptions) -> FlatFields {
+    if options.annotations.is_empty() {
+        return fields;
+    }
+    for (path, value) in fields.iter_mut() {
+        let pipeline = options.transforms_for(path);
+        if !pipeline.is_empty() {
+            *value = apply_pipeline(pipeline, value.take(), options.locale);
+        }
+    }
+    fields
+}
+
+/// Moves flattened predicted fields to their `--remap` ground-truth paths. A remapped value
+/// replaces one the prediction already had at that path. Also returns each remapped
+/// ground-truth path with the predicted path it came from.
+fn remap_predicted_fields(
+    flat: BTreeMap<String, Value>,
+    options: &EvaluationOptions,
+) -> (BTreeMap<String, Value>, BTreeMap<String, String>) {
+    if options.remaps.is_empty() {
+        return (flat, BTreeMap::new());
+    }
+    let mut fields = BTreeMap::new();
+    let mut moved = Vec::new();
+    for (path, value) in flat {
+        match options.remapped_path(&path) {
+            Some(target) => moved.push((target, path, value)),
+            None => {
+                fields.insert(path, value);
+            }
+        }
+    }
+    let mut remapped = BTreeMap::new();
+    for (target, path, value) in moved {
+        fields.insert(target.clone(), value);
+        remapped.insert(target, path);
+    }
+    (fields, remapped)
+}
+
+fn flatten_until(
     value: &Value,
     path: Vec<String>,
+    is_leaf: &dyn Fn(&str) -> bool,
 ) -> Result<BTreeMap<String, Value>, EvaluationError> {
     let mut flattened = BTreeMap::new();
     match value {
+        Value::Object(_) | Value::Array(_) if !path.is_empty() && is_leaf(&path.join(".")) => {
+            flattened.insert(path.join("."), value.clone());
+        }
         Value::Object(map) => {
             for key in sorted_keys(map) {
                 let mut new_path = path.clone();
                 new_path.push(key.clone());
-                flattened.extend(flatten_fields(
+                flattened.extend(flatten_until(
                     map.get(&key).expect("key present"),
                     new_path,
+                    is_leaf,
                 )?);
             }
         }
@@ -219,7 +1473,7 @@ fn flatten_fields(
             for (idx, item) in items.iter().enumerate() {
                 let mut new_path = path.clone();
                 new_path.push(idx.to_string());
-                flattened.extend(flatten_fields(item, new_path)?);
+                flattened.extend(flatten_until(item, new_path, is_leaf)?);
             }
         }
         _ => {
@@ -238,22 +1492,816 @@ fn sorted_keys(map: &Map<String, Value>) -> Vec<String> {
     keys
 }
 
-fn numeric_similarity(expected: &Value, predicted: Option<&Value>) -> Option<f64> {
-    let expected_value = expected.as_f64()?;
-    let predicted_value = predicted?.as_f64()?;
+/// Scores a single field with the metric matching its ground-truth type, treating an
+/// absent or mistyped prediction as a complete miss (less any optional-field credit).
+pub(crate) fn field_score(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> f64 {
+    let zero = Value::from(0);
+    let (expected, predicted) = null_numbers_as_zero(options, path, expected, predicted, &zero);
+    let score = if is_numeric_field(options, path, expected, predicted) {
+        numeric_similarity(options, path, expected, predicted)
+    } else {
+        text_similarity(options, path, expected, predicted)
+    };
+    score
+        .or_else(|| missing_credit(options, path, predicted))
+        .unwrap_or(0.0)
+}
+
+/// How [`field_score`] compares a field with this ground-truth value: `coordinate`, `money`,
+/// `duration`, `unit`, `integer`, `percent` or `number` for numeric fields, `sequence` for sequence fields, `set`
+/// or `multiset` for set fields, `number-sequence` for `x-number-sequence` fields, and the
+/// name of the text metric otherwise.
+pub(crate) fn field_comparison(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+) -> &'static str {
+    if !is_numeric_field(options, path, expected, None) {
+        if options.is_sequence(path) {
+            return "sequence";
+        }
+        return match options.set_mode_for(path) {
+            Some(SetMode::Set) => "set",
+            Some(SetMode::Multiset) => "multiset",
+            None if options.is_number_sequence(path) => "number-sequence",
+            None => options.text_metric_for(path).name(),
+        };
+    }
+    if options.is_coordinate(path) {
+        "coordinate"
+    } else if options.is_money(path) {
+        "money"
+    } else if options.is_duration(path) {
+        "duration"
+    } else if options.unit_spec_for(path).is_some() {
+        "unit"
+    } else if options.strict_numbers && exact_integer(expected).is_some() {
+        "integer"
+    } else if options.is_percent(path) && percent_fraction(options, expected).is_some() {
+        "percent"
+    } else {
+        "number"
+    }
+}
+
+/// A field value as compared, for `--include-raw-values`: strings are cut to
+/// `--max-field-length` characters when a cap is set.
+fn raw_value(options: &EvaluationOptions, value: Option<&Value>) -> Option<Value> {
+    if !options.include_raw_values {
+        return None;
+    }
+    value.map(|value| match value {
+        Value::String(text) => {
+            Value::String(truncate_chars(text, options.max_field_length).to_string())
+        }
+        _ => value.clone(),
+    })
+}
+
+/// Partial credit for an optional field that the prediction leaves out entirely.
+fn missing_credit(
+    options: &EvaluationOptions,
+    path: &str,
+    predicted: Option<&Value>,
+) -> Option<f64> {
+    match predicted {
+        Some(_) => None,
+        None => options.missing_field_credit(path),
+    }
+}
+
+/// Reads a predicted value as a number: JSON numbers directly, and strings through the
+/// configured locale, if any.
+fn numeric_value(options: &EvaluationOptions, value: &Value) -> Option<f64> {
+    match value {
+        Value::String(text) => {
+            parse_scientific(text).or_else(|| parse_localized(text, options.locale?))
+        }
+        _ => value.as_f64(),
+    }
+}
+
+/// Whether a ground-truth value is scored as a number: JSON numbers, `x-coordinate` and
+/// `x-money` fields, percentage strings in `x-percent` fields, durations in `x-duration`
+/// fields, quantities in `x-unit` fields, and scientific-notation strings in fields the
+/// template types as `number`/`integer` or whose `predicted` value is a JSON number. Other
+/// strings such as the code `"3E4"` stay text.
+fn is_numeric_field(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> bool {
+    expected.is_number()
+        || (options.is_numeric_typed(path) || predicted.is_some_and(Value::is_number))
+            && expected.as_str().and_then(parse_scientific).is_some()
+        || options.is_coordinate(path)
+        || options.is_money(path)
+        || options.is_percent(path) && percent_fraction(options, expected).is_some()
+        || options.is_duration(path) && parse_duration(expected).is_some()
+        || options.unit_spec_for(path).is_some()
+            && Quantity::from_json(expected, options.locale).is_some()
+}
+
+/// Under `--null-numeric-as-zero`, reads a `null` as `zero` when the other side of the field
+/// is numeric, so `null` and `0` score as equal amounts.
+fn null_numbers_as_zero<'a>(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &'a Value,
+    predicted: Option<&'a Value>,
+    zero: &'a Value,
+) -> (&'a Value, Option<&'a Value>) {
+    if !options.null_numeric_as_zero {
+        return (expected, predicted);
+    }
+    match predicted {
+        Some(Value::Null) if is_numeric_field(options, path, expected, None) => {
+            (expected, Some(zero))
+        }
+        Some(predicted) if expected.is_null() && predicted.is_number() => (zero, Some(predicted)),
+        _ => (expected, predicted),
+    }
+}
+
+/// A percentage string (`"20%"`) read as a fraction (`0.2`).
+fn percent_fraction(options: &EvaluationOptions, value: &Value) -> Option<f64> {
+    parse_percent(value.as_str()?, options.locale)
+}
+
+/// Reads a value of a numeric field, with percentage strings as fractions in `x-percent`
+/// fields, quantities in their base unit in `x-unit` fields, amounts in the `--fx-rates`
+/// base in `x-money` fields and seconds in `x-duration` fields.
+fn field_number(options: &EvaluationOptions, path: &str, value: &Value) -> Option<f64> {
+    if options.is_money(path) {
+        return base_amount(value, options.fx_rates.as_ref());
+    }
+    if options.is_duration(path) {
+        return parse_duration(value);
+    }
+    if let Some(spec) = options.unit_spec_for(path) {
+        return Quantity::from_json(value, options.locale)
+            .map(|quantity| spec.base_amount(&quantity));
+    }
+    options
+        .is_percent(path)
+        .then(|| percent_fraction(options, value))
+        .flatten()
+        .or_else(|| numeric_value(options, value))
+}
+
+/// Whether scoring an `x-percent` field reads either side from a percentage string.
+fn reads_percent(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> bool {
+    options.is_percent(path)
+        && [Some(expected), predicted]
+            .into_iter()
+            .flatten()
+            .any(|value| percent_fraction(options, value).is_some())
+}
+
+fn numeric_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> Option<f64> {
+    let predicted = predicted?;
+    if options.is_coordinate(path) {
+        return Some(
+            coordinate_similarity(expected, predicted, options.geo_tolerance_km).unwrap_or(0.0),
+        );
+    }
+    if options.is_money(path) {
+        return Some(
+            money_similarity(
+                expected,
+                predicted,
+                options.fx_rates.as_ref(),
+                |expected, predicted| number_similarity(options, path, expected, predicted),
+            )
+            .unwrap_or(0.0),
+        );
+    }
+    if options.is_duration(path) {
+        // A prediction that is no duration is still compared with the expected text.
+        return match (parse_duration(expected), parse_duration(predicted)) {
+            (Some(expected), Some(predicted)) => {
+                Some(number_similarity(options, path, expected, predicted))
+            }
+            _ => text_similarity(options, path, expected, Some(predicted)).or(Some(0.0)),
+        };
+    }
+    if let Some(spec) = options.unit_spec_for(path) {
+        let expected = Quantity::from_json(expected, options.locale)?;
+        let predicted = Quantity::from_json(predicted, options.locale)?;
+        let number_score = number_similarity(
+            options,
+            path,
+            spec.base_amount(&expected),
+            spec.base_amount(&predicted),
+        );
+        return Some(quantity_score(
+            number_score,
+            spec.unit_similarity(&expected, &predicted),
+        ));
+    }
+    if options.strict_numbers {
+        if let (Some(expected), Some(predicted)) =
+            (exact_integer(expected), exact_integer(predicted))
+        {
+            return Some(integer_similarity(options, path, expected, predicted));
+        }
+    }
+    let expected_value = field_number(options, path, expected)?;
+    let predicted_value = field_number(options, path, predicted)?;
+    Some(number_similarity(
+        options,
+        path,
+        expected_value,
+        predicted_value,
+    ))
+}
+
+/// 1.0 within the field's tolerance, otherwise one minus the difference relative to the
+/// larger magnitude (at least 1.0), scaled by the field's asymmetry slope for that side.
+fn number_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected_value: f64,
+    predicted_value: f64,
+) -> f64 {
+    if options
+        .numeric_tolerance_for(path)
+        .is_some_and(|tolerance| tolerance.accepts(expected_value, predicted_value))
+    {
+        return 1.0;
+    }
     let scale = expected_value.abs().max(predicted_value.abs()).max(1.0);
-    let diff = (expected_value - predicted_value).abs() / scale;
-    Some((1.0 - diff.min(1.0)).max(0.0))
+    let slope = options
+        .numeric_asymmetry_for(path)
+        .map_or(1.0, |asymmetry| {
+            asymmetry.slope(expected_value, predicted_value)
+        });
+    let diff = slope * (expected_value - predicted_value).abs() / scale;
+    (1.0 - diff.min(1.0)).max(0.0)
+}
+
+/// A JSON integer widened losslessly, so 19-digit ids keep every digit.
+fn exact_integer(value: &Value) -> Option<i128> {
+    value
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| value.as_u64().map(i128::from))
+}
+
+/// `--strict-numbers` scoring of two integers: 1.0 when they are equal or within the field's
+/// tolerance, 0.0 otherwise. Distinct integers never score 1.0 through float rounding.
+fn integer_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: i128,
+    predicted: i128,
+) -> f64 {
+    let difference = (expected - predicted).unsigned_abs();
+    let within_tolerance = options
+        .numeric_tolerance_for(path)
+        .is_some_and(|tolerance| tolerance.accepts_difference(expected as f64, difference as f64));
+    if difference == 0 || within_tolerance {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn text_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> Option<f64> {
+    if let Some(mode) = options.set_mode_for(path) {
+        return Some(set_similarity(expected, predicted?, mode).unwrap_or(0.0));
+    }
+    if options.is_sequence(path) {
+        return Some(
+            sequence_alignment(options, path, expected, predicted?)
+                .map_or(0.0, |alignment| alignment.score),
+        );
+    }
+    let (expected_str, predicted_str) =
+        comparable_text(options, path, expected, predicted?.as_str()?);
+    if options.is_number_sequence(path) {
+        if let Some(score) =
+            number_sequence_similarity(options, path, &expected_str, &predicted_str)
+        {
+            return Some(score);
+        }
+    }
+    if options
+        .synonyms_for(path)
+        .is_some_and(|synonyms| synonyms.same_term(&expected_str, &predicted_str))
+    {
+        return Some(1.0);
+    }
+    if options.embedded_numbers {
+        if let Some(score) =
+            embedded_number_similarity(options, path, &expected_str, &predicted_str)
+        {
+            return Some(score);
+        }
+    }
+    Some(metric_similarity(
+        options,
+        path,
+        options.text_metric_for(path),
+        &expected_str,
+        &predicted_str,
+    ))
+}
+
+/// Share of an `--embedded-numbers` score that comes from the numbers; the rest comes from
+/// the text around them.
+const EMBEDDED_NUMBER_WEIGHT: f64 = 0.5;
+
+/// Scores a gestalt text field whose expected and predicted values both contain numbers,
+/// under `--embedded-numbers`: the numbers are compared position by position as in
+/// [`number_sequence_similarity`], the text around them (numbers replaced by a placeholder)
+/// with the gestalt metric, and the two scores are combined by [`EMBEDDED_NUMBER_WEIGHT`].
+/// `"12.5%"` against `"12.53%"` then loses only the small relative error of the number.
+/// `None` when either side has no number or the field uses another text metric.
+fn embedded_number_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &str,
+    predicted: &str,
+) -> Option<f64> {
+    if options.text_metric_for(path) != TextMetric::Gestalt {
+        return None;
+    }
+    let (expected_numbers, expected_text) = split_numbers(expected, options.locale);
+    let (predicted_numbers, predicted_text) = split_numbers(predicted, options.locale);
+    if expected_numbers.is_empty() || predicted_numbers.is_empty() {
+        return None;
+    }
+    let positions = expected_numbers.len().max(predicted_numbers.len());
+    let numbers: f64 = expected_numbers
+        .iter()
+        .zip(&predicted_numbers)
+        .map(|(&expected, &predicted)| number_similarity(options, path, expected, predicted))
+        .sum::<f64>()
+        / positions as f64;
+    let text = metric_similarity(
+        options,
+        path,
+        TextMetric::Gestalt,
+        &expected_text,
+        &predicted_text,
+    );
+    Some(EMBEDDED_NUMBER_WEIGHT * numbers + (1.0 - EMBEDDED_NUMBER_WEIGHT) * text)
+}
+
+/// Scores an `x-number-sequence` field by the numbers written on each side, compared
+/// position by position with [`number_similarity`]; numbers only one side has score 0.0.
+/// `None` when neither side has a number, so the text metric applies.
+fn number_sequence_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &str,
+    predicted: &str,
+) -> Option<f64> {
+    let expected = extract_numbers(expected, options.locale);
+    let predicted = extract_numbers(predicted, options.locale);
+    let positions = expected.len().max(predicted.len());
+    if positions == 0 {
+        return None;
+    }
+    let total: f64 = expected
+        .iter()
+        .zip(&predicted)
+        .map(|(&expected, &predicted)| number_similarity(options, path, expected, predicted))
+        .sum();
+    Some(total / positions as f64)
+}
+
+/// Scores two comparable texts with `metric`, truncated to `--max-field-length` first.
+fn metric_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    metric: TextMetric,
+    expected: &str,
+    predicted: &str,
+) -> f64 {
+    let max_length = text_length_cap(options, metric);
+    let expected = truncate_chars(expected, max_length);
+    let predicted = truncate_chars(predicted, max_length);
+    match metric {
+        TextMetric::Gestalt => ratcliff_obershelp(expected, predicted),
+        TextMetric::HierarchicalPath => {
+            hierarchical_path_similarity(expected, predicted, options.path_separator_for(path))
+        }
+        TextMetric::Timestamp => {
+            timestamp_similarity(expected, predicted, options.timestamp_tolerance)
+                .unwrap_or_else(|| ratcliff_obershelp(expected, predicted))
+        }
+        TextMetric::Binary => binary_similarity(expected, predicted).unwrap_or(0.0),
+        TextMetric::Address => address_similarity(expected, predicted),
+    }
+}
+
+/// Scores of every `--compare-text-metrics` metric on a text field, in the order given.
+/// Empty with fewer than two metrics, for set fields and when the prediction is not text.
+fn compared_text_metrics(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> Vec<(TextMetric, f64)> {
+    if options.compare_text_metrics.len() < 2
+        || options.set_mode_for(path).is_some()
+        || options.is_sequence(path)
+    {
+        return Vec::new();
+    }
+    let Some(predicted) = predicted.and_then(Value::as_str) else {
+        return Vec::new();
+    };
+    let (expected, predicted) = comparable_text(options, path, expected, predicted);
+    options
+        .compare_text_metrics
+        .iter()
+        .map(|&metric| {
+            let score = metric_similarity(options, path, metric, &expected, &predicted);
+            (metric, score)
+        })
+        .collect()
+}
+
+/// The metric with the highest score; the earliest one wins ties.
+fn best_text_metric(scores: &[(TextMetric, f64)]) -> Option<String> {
+    scores
+        .iter()
+        .fold(
+            None,
+            |best: Option<&(TextMetric, f64)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            },
+        )
+        .map(|(metric, _)| metric.name().to_string())
+}
+
+/// The expected and predicted text of a field as compared: non-string expected values as
+/// normalized JSON, both sides of identifier fields normalized and stop words dropped from
+/// `x-stop-words` fields.
+fn comparable_text(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: &str,
+) -> (String, String) {
+    let expected = match expected.as_str() {
+        Some(text) => text.to_string(),
+        None => normalized_json(expected),
+    };
+    let (expected, predicted) = match options.identifier_format_for(path) {
+        Some(format) => (format.normalize(&expected), format.normalize(predicted)),
+        None => (expected, predicted.to_string()),
+    };
+    match options.stop_words_for(path) {
+        Some(stop_words) => (stop_words.remove(&expected), stop_words.remove(&predicted)),
+        None => (expected, predicted),
+    }
 }
 
-fn text_similarity(expected: &Value, predicted: Option<&Value>) -> Option<f64> {
-    let predicted_str = predicted?.as_str()?;
-    let expected_str = if expected.is_string() {
-        expected.as_str().unwrap().to_string()
+/// `--max-field-length`, which binary values are exempt from.
+fn text_length_cap(options: &EvaluationOptions, metric: TextMetric) -> Option<usize> {
+    options
+        .max_field_length
+        .filter(|_| metric != TextMetric::Binary)
+}
+
+/// A run of characters that gestalt matching paired between the expected and predicted
+/// text. Positions count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatchingBlock {
+    pub expected_start: usize,
+    pub predicted_start: usize,
+    pub length: usize,
+    pub matched_text: String,
+}
+
+/// How one text field was scored, for `--explain-text`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TextExplanation {
+    pub document_id: String,
+    pub path: String,
+    /// Texts as compared, after identifier normalization and truncation.
+    pub expected: String,
+    pub predicted: Option<String>,
+    /// The field's score with the configured metric, as used in the metrics.
+    pub score: f64,
+    /// `2 * matched characters / total characters` over the blocks below.
+    pub gestalt_score: f64,
+    pub matching_blocks: Vec<MatchingBlock>,
+}
+
+/// Explains the score of the text field at `path` (a flattened path such as
+/// `customer.name` or `items.0.description`) in document `doc_id`.
+pub fn explain_text_field(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    doc_id: &str,
+    path: &str,
+) -> Result<TextExplanation, EvaluationError> {
+    let unknown = || EvaluationError::UnknownField(doc_id.to_string(), path.to_string());
+    let gt_doc = ground_truth.get(doc_id).ok_or_else(unknown)?;
+    let gt_flat = ground_truth_fields(&gt_doc.fields, options)?;
+    let expected = gt_flat.get(path).ok_or_else(unknown)?;
+    let pred_flat = match predictions.get(doc_id) {
+        Some(pred_doc) => predicted_fields(&pred_doc.fields, options)?.0,
+        None => BTreeMap::new(),
+    };
+    let predicted = pred_flat.get(path);
+
+    let max_length = text_length_cap(options, options.text_metric_for(path));
+    let (expected_text, predicted_text) = match predicted.and_then(Value::as_str) {
+        Some(predicted) => {
+            let (expected, predicted) = comparable_text(options, path, expected, predicted);
+            (expected, Some(predicted))
+        }
+        None => (comparable_text(options, path, expected, "").0, None),
+    };
+    let expected_text = truncate_chars(&expected_text, max_length).to_string();
+    let predicted_text = predicted_text.map(|text| truncate_chars(&text, max_length).to_string());
+    let matching_blocks = matching_blocks(&expected_text, predicted_text.as_deref().unwrap_or(""));
+    let gestalt_score = match &predicted_text {
+        Some(text) => round(ratcliff_obershelp(&expected_text, text)),
+        None => 0.0,
+    };
+    Ok(TextExplanation {
+        document_id: doc_id.to_string(),
+        path: path.to_string(),
+        expected: expected_text,
+        predicted: predicted_text,
+        score: round(field_score(options, path, expected, predicted)),
+        gestalt_score,
+        matching_blocks,
+    })
+}
+
+/// Whether a binary field has a value on either side that is not valid base64.
+fn has_malformed_binary(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> bool {
+    options.text_metric_for(path) == TextMetric::Binary
+        && [Some(expected), predicted]
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .any(|text| decode_base64(text).is_none())
+}
+
+/// Whether a coordinate field has a value on either side that is not a valid coordinate.
+fn has_malformed_coordinate(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> bool {
+    options.is_coordinate(path)
+        && [Some(expected), predicted]
+            .into_iter()
+            .flatten()
+            .any(|value| Coordinate::from_json(value).is_none())
+}
+
+/// Cuts `text` to at most `max_chars` characters so the quadratic matchers stay bounded.
+pub(crate) fn truncate_chars(text: &str, max_chars: Option<usize>) -> &str {
+    match max_chars.and_then(|max| text.char_indices().nth(max)) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Whether either side of a text comparison is longer than `--max-field-length`.
+fn exceeds_max_field_length(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: Option<&Value>,
+) -> bool {
+    let Some(max) = options.max_field_length else {
+        return false;
+    };
+    if options.text_metric_for(path) == TextMetric::Binary {
+        return false;
+    }
+    [Some(expected), predicted]
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .any(|text| text.chars().nth(max).is_some())
+}
+
+/// Scores two timestamps by the distance between the instants they denote, so values in
+/// different time zones compare equal. Returns `None` when either side does not parse.
+fn timestamp_similarity(expected: &str, predicted: &str, tolerance: Duration) -> Option<f64> {
+    let distance = (parse_timestamp_millis(expected)? - parse_timestamp_millis(predicted)?).abs();
+    let tolerance = tolerance.as_millis() as f64;
+    if tolerance == 0.0 {
+        return Some(if distance == 0 { 1.0 } else { 0.0 });
+    }
+    Some((1.0 - distance as f64 / tolerance).max(0.0))
+}
+
+/// Parses RFC 3339 timestamps; naive `YYYY-MM-DDTHH:MM:SS` values are taken as UTC.
+pub(crate) fn parse_timestamp_millis(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.timestamp_millis());
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc().timestamp_millis())
+}
+
+/// Aligns two array values element by element (see [`align_sequences`]). Returns `None` when
+/// either side is not an array.
+fn sequence_alignment(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: &Value,
+) -> Option<SequenceAlignment> {
+    let (expected, predicted) = (expected.as_array()?, predicted.as_array()?);
+    Some(align_sequences(expected.len(), predicted.len(), |i, j| {
+        element_similarity(options, &format!("{path}.{i}"), &expected[i], &predicted[j])
+    }))
+}
+
+/// Mean score of the leaves of two sequence elements, both read at the expected element's
+/// `path` so its annotations apply; leaves only one side has score 0.
+fn element_similarity(
+    options: &EvaluationOptions,
+    path: &str,
+    expected: &Value,
+    predicted: &Value,
+) -> f64 {
+    let is_leaf = |path: &str| is_scored_leaf(options, path);
+    let prefix: Vec<String> = path.split('.').map(str::to_string).collect();
+    let (Ok(expected), Ok(predicted)) = (
+        flatten_until(expected, prefix.clone(), &is_leaf),
+        flatten_until(predicted, prefix, &is_leaf),
+    ) else {
+        return 0.0;
+    };
+    let leaves = expected
+        .keys()
+        .chain(
+            predicted
+                .keys()
+                .filter(|leaf| !expected.contains_key(*leaf)),
+        )
+        .count();
+    if leaves == 0 {
+        return 1.0;
+    }
+    let total: f64 = expected
+        .iter()
+        .map(|(leaf, value)| field_score(options, leaf, value, predicted.get(leaf)))
+        .sum();
+    total / leaves as f64
+}
+
+/// Scores category paths such as `A/B/C` by the fraction of leading segments that match,
+/// relative to the deeper of the two paths.
+fn hierarchical_path_similarity(expected: &str, predicted: &str, separator: &str) -> f64 {
+    let segments = |value: &str| -> Vec<String> {
+        value
+            .split(separator)
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let expected_segments = segments(expected);
+    let predicted_segments = segments(predicted);
+    let depth = expected_segments.len().max(predicted_segments.len());
+    if depth == 0 {
+        return 1.0;
+    }
+    let shared = expected_segments
+        .iter()
+        .zip(&predicted_segments)
+        .take_while(|(a, b)| a == b)
+        .count();
+    shared as f64 / depth as f64
+}
+
+/// Lines of the shorter side for which [`address_similarity`] searches every pairing; longer
+/// addresses are paired greedily, best-scoring pair first.
+const MAX_EXHAUSTIVE_ADDRESS_LINES: usize = 10;
+
+/// Scores multi-line addresses independently of line order: lines are paired one to one so
+/// that the sum of their gestalt scores is highest, and the sum is divided by the line count
+/// of the longer address, so unpaired lines score 0.0. Blank lines are ignored.
+fn address_similarity(expected: &str, predicted: &str) -> f64 {
+    let lines = |value: &str| -> Vec<String> {
+        value
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (mut longer, mut shorter) = (lines(expected), lines(predicted));
+    if shorter.len() > longer.len() {
+        std::mem::swap(&mut longer, &mut shorter);
+    }
+    if longer.is_empty() {
+        return 1.0;
+    }
+    let scores: Vec<Vec<f64>> = longer
+        .iter()
+        .map(|line| {
+            shorter
+                .iter()
+                .map(|other| ratcliff_obershelp(line, other))
+                .collect()
+        })
+        .collect();
+    let total = if shorter.len() <= MAX_EXHAUSTIVE_ADDRESS_LINES {
+        best_pairing_score(&scores, shorter.len())
     } else {
-        normalized_json(expected)
+        greedy_pairing_score(&scores)
     };
-    Some(ratcliff_obershelp(&expected_str, predicted_str))
+    total / longer.len() as f64
+}
+
+/// Highest sum of scores over one-to-one pairings of the rows of `scores` (the longer side)
+/// with its `columns`, by dynamic programming over the sets of paired columns.
+fn best_pairing_score(scores: &[Vec<f64>], columns: usize) -> f64 {
+    let mut best = vec![f64::NEG_INFINITY; 1 << columns];
+    best[0] = 0.0;
+    for row in scores {
+        let mut next = best.clone();
+        for (paired, &total) in best.iter().enumerate() {
+            if total == f64::NEG_INFINITY {
+                continue;
+            }
+            for (column, score) in row.iter().enumerate() {
+                if paired & (1 << column) == 0 {
+                    let slot = &mut next[paired | (1 << column)];
+                    *slot = slot.max(total + score);
+                }
+            }
+        }
+        best = next;
+    }
+    best.into_iter().fold(0.0, f64::max)
+}
+
+/// Sum of scores pairing the best-scoring remaining row and column first.
+fn greedy_pairing_score(scores: &[Vec<f64>]) -> f64 {
+    let mut pairs: Vec<(f64, usize, usize)> = scores
+        .iter()
+        .enumerate()
+        .flat_map(|(row, columns)| {
+            columns
+                .iter()
+                .enumerate()
+                .map(move |(column, &score)| (score, row, column))
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut rows = BTreeSet::new();
+    let mut columns = BTreeSet::new();
+    pairs
+        .into_iter()
+        .filter(|&(_, row, column)| {
+            !rows.contains(&row) && !columns.contains(&column) && {
+                rows.insert(row);
+                columns.insert(column);
+                true
+            }
+        })
+        .map(|(score, _, _)| score)
+        .sum()
 }
 
 fn normalized_json(value: &Value) -> String {
@@ -287,15 +2335,44 @@ fn ratcliff_obershelp(a: &str, b: &str) -> f64 {
 }
 
 fn gestalt_match(a: &[char], b: &[char]) -> usize {
+    let mut blocks = Vec::new();
+    collect_blocks(a, b, (0, 0), &mut blocks);
+    blocks.iter().map(|block| block.length).sum()
+}
+
+/// The blocks gestalt matching pairs between `expected` and `predicted`, in text order: the
+/// longest common substring, then recursively the longest ones left of and right of it.
+pub fn matching_blocks(expected: &str, predicted: &str) -> Vec<MatchingBlock> {
+    let expected: Vec<char> = expected.chars().collect();
+    let predicted: Vec<char> = predicted.chars().collect();
+    let mut blocks = Vec::new();
+    collect_blocks(&expected, &predicted, (0, 0), &mut blocks);
+    blocks
+}
+
+fn collect_blocks(
+    a: &[char],
+    b: &[char],
+    (offset_a, offset_b): (usize, usize),
+    blocks: &mut Vec<MatchingBlock>,
+) {
     if a.is_empty() || b.is_empty() {
-        return 0;
+        return;
     }
     if let Some((start_a, start_b, length)) = longest_common_substring(a, b) {
-        let prefix = gestalt_match(&a[..start_a], &b[..start_b]);
-        let suffix = gestalt_match(&a[start_a + length..], &b[start_b + length..]);
-        length + prefix + suffix
-    } else {
-        0
+        collect_blocks(&a[..start_a], &b[..start_b], (offset_a, offset_b), blocks);
+        blocks.push(MatchingBlock {
+            expected_start: offset_a + start_a,
+            predicted_start: offset_b + start_b,
+            length,
+            matched_text: a[start_a..start_a + length].iter().collect(),
+        });
+        collect_blocks(
+            &a[start_a + length..],
+            &b[start_b + length..],
+            (offset_a + start_a + length, offset_b + start_b + length),
+            blocks,
+        );
     }
 }
 
@@ -323,6 +2400,89 @@ pub fn save_metrics(path: &Path, metrics: &EvaluationMetrics) -> Result<(), Eval
     Ok(())
 }
 
+/// One line of the `--append` run log.
+#[derive(Serialize)]
+struct RunLogEntry<'a> {
+    timestamp: String,
+    metrics: &'a EvaluationMetrics,
+}
+
+/// Appends `metrics` with the current UTC time as one JSON line to `path`, creating the file
+/// if needed. The line is written with a single `write_all` on a file opened in append mode,
+/// so concurrent runs never interleave within a line.
+pub fn append_metrics_log(
+    path: &Path,
+    metrics: &EvaluationMetrics,
+    redact: bool,
+) -> Result<(), EvaluationError> {
+    let entry = RunLogEntry {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        metrics,
+    };
+    let line = to_output_line(&entry, redact)? + "\n";
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+const AGGREGATE_FILE_STEM: &str = "aggregate";
+
+/// Writes one `<document_id>.json` file per scored document plus `aggregate.json` into `dir`,
+/// creating the directory if needed. With `redact`, document values are masked as in
+/// [`crate::redact::redact_values`].
+pub fn save_document_scores(
+    dir: &Path,
+    metrics: &EvaluationMetrics,
+    redact: bool,
+) -> Result<(), EvaluationError> {
+    fs::create_dir_all(dir)?;
+    let mut used = BTreeSet::from([AGGREGATE_FILE_STEM.to_string()]);
+    for document in &metrics.documents {
+        let base = sanitize_file_stem(&document.document_id);
+        let mut stem = base.clone();
+        let mut suffix = 2;
+        while !used.insert(stem.to_lowercase()) {
+            stem = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        write_output(&dir.join(format!("{stem}.json")), document, redact)?;
+    }
+    write_output(
+        &dir.join(format!("{AGGREGATE_FILE_STEM}.json")),
+        metrics,
+        redact,
+    )
+}
+
+fn write_output<T: Serialize>(path: &Path, value: &T, redact: bool) -> Result<(), EvaluationError> {
+    let payload = to_output_json(value, redact)?;
+    fs::write(path, payload + "\n")?;
+    Ok(())
+}
+
+/// Maps a document id onto a portable file stem: anything outside `[A-Za-z0-9._-]` becomes
+/// `_`, and ids that would be empty or hidden are prefixed with `_`.
+fn sanitize_file_stem(document_id: &str) -> String {
+    let stem: String = document_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() || stem.starts_with('.') {
+        format!("_{stem}")
+    } else {
+        stem
+    }
+}
+
 pub fn parse_path(value: &str) -> PathBuf {
     PathBuf::from(value)
 }
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{combine_components, component_weights, ratio_or_one};
+use crate::formula::Component;
+use crate::metrics::{round, EvaluationMetrics};
+use crate::options::ComponentWeighting;
+
+/// How `overall_score` follows from the component metrics, for `--explain-score`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreExplanation {
+    /// Unrounded `document_coverage`, `structural_completeness`, `numeric_field_similarity`
+    /// and `text_field_similarity`, by name.
+    pub components: [(&'static str, f64); 4],
+    pub rule: CombinationRule,
+    /// The overall score before rounding.
+    pub overall_score: f64,
+}
+
+/// The rule that combined the components.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombinationRule {
+    /// A weighted sum, with one weight per component in [`ScoreExplanation::components`]
+    /// order.
+    Weighted {
+        weighting: ComponentWeighting,
+        weights: [f64; 4],
+    },
+    /// A `--score-formula` expression.
+    Formula(String),
+}
+
+impl EvaluationMetrics {
+    /// The arithmetic behind `overall_score`, recomputed from the unrounded totals.
+    pub fn explain_score(&self) -> Result<ScoreExplanation, EvaluationError> {
+        let totals = &self.totals;
+        let coverage = if self.num_documents == 0 {
+            0.0
+        } else {
+            totals.coverage_sum / f64::from(self.num_documents)
+        };
+        let similarity = &totals.similarity;
+        let components = [
+            ("document_coverage", coverage),
+            (
+                "structural_completeness",
+                ratio_or_one(totals.matched_fields, self.num_fields),
+            ),
+            ("numeric_field_similarity", similarity.numeric_similarity()),
+            ("text_field_similarity", similarity.text_similarity()),
+        ];
+        let rule = match &totals.score_formula {
+            Some(formula) => CombinationRule::Formula(formula.to_string()),
+            None => CombinationRule::Weighted {
+                weighting: totals.component_weighting,
+                weights: component_weights(totals.component_weighting, similarity),
+            },
+        };
+        Ok(ScoreExplanation {
+            components,
+            rule,
+            overall_score: combine_components(
+                totals.component_weighting,
+                totals.score_formula.as_ref(),
+                components[0].1,
+                components[1].1,
+                similarity,
+            )?,
+        })
+    }
+}
+
+impl fmt::Display for ScoreExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rule {
+            CombinationRule::Weighted { weighting, weights } => {
+                let name = match weighting {
+                    ComponentWeighting::Equal => "equal",
+                    ComponentWeighting::ByCount => "by-count",
+                };
+                writeln!(
+                    f,
+                    "overall_score = weighted sum of components ({name} weighting)"
+                )?;
+                for ((component, value), weight) in self.components.iter().zip(weights) {
+                    writeln!(
+                        f,
+                        "  {component:<26} {value:.6} * {weight:.6} = {:.6}",
+                        value * weight
+                    )?;
+                }
+            }
+            CombinationRule::Formula(formula) => {
+                writeln!(f, "overall_score = {formula}")?;
+                for (variable, (component, value)) in Component::NAMES.iter().zip(&self.components)
+                {
+                    writeln!(f, "  {variable:<10} = {value:.6} ({component})")?;
+                }
+            }
+        }
+        writeln!(
+            f,
+            "  = {:.6}, reported as {}",
+            self.overall_score,
+            round(self.overall_score)
+        )
+    }
+}
@@ -0,0 +1,252 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::annotations::field_pattern;
+use crate::error::EvaluationError;
+use crate::evaluator::{
+    field_comparison, flatten_fields, ground_truth_fields, parse_timestamp_millis, Document,
+};
+use crate::metrics::round;
+use crate::numbers::{parse_localized, parse_scientific, NumberLocale};
+use crate::options::EvaluationOptions;
+use crate::transforms::strip_currency;
+
+/// Values per field pattern that `--infer-types` classifies.
+pub const INFERENCE_SAMPLE_SIZE: usize = 100;
+
+/// Date layouts recognised by `--infer-types`, besides RFC 3339 timestamps.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%B %d, %Y",
+    "%d %B %Y",
+    "%b %d, %Y",
+    "%d %b %Y",
+];
+
+/// A leaf field path observed in the ground truth.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldPathSummary {
+    pub path: String,
+    /// JSON types seen for this path (`string`, `number`, `boolean`, `null`).
+    pub types: BTreeSet<&'static str>,
+    /// Number of documents that contain the path.
+    pub documents: u32,
+}
+
+/// Returns the sorted union of leaf paths across all documents with their observed types.
+pub fn list_field_paths(
+    documents: &BTreeMap<String, Document>,
+) -> Result<Vec<FieldPathSummary>, EvaluationError> {
+    let mut summaries: BTreeMap<String, FieldPathSummary> = BTreeMap::new();
+    for document in documents.values() {
+        for (path, value) in flatten_fields(&document.fields, Vec::new())? {
+            let summary = summaries
+                .entry(path.clone())
+                .or_insert_with(|| FieldPathSummary {
+                    path,
+                    types: BTreeSet::new(),
+                    documents: 0,
+                });
+            summary.types.insert(json_type_name(&value));
+            summary.documents += 1;
+        }
+    }
+    Ok(summaries.into_values().collect())
+}
+
+/// How a field path of the ground truth is compared, once template annotations, value
+/// detection and CLI options are resolved.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldTypeSummary {
+    pub path: String,
+    /// Comparison types resolved for the path (`number`, `percent`, `unit`, `coordinate`,
+    /// `integer`, `set`, `multiset`, `sequence`, or a text metric such as `gestalt`). More than one means
+    /// documents hold values that are compared differently.
+    pub comparisons: BTreeSet<&'static str>,
+    /// Number of documents that contain the path.
+    pub documents: u32,
+}
+
+/// Returns every scored field path of the ground truth, as flattened for scoring, with the
+/// comparison types resolved for it.
+pub fn resolve_field_types(
+    documents: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> Result<Vec<FieldTypeSummary>, EvaluationError> {
+    let mut summaries: BTreeMap<String, FieldTypeSummary> = BTreeMap::new();
+    for document in documents.values() {
+        for (path, value) in ground_truth_fields(&document.fields, options)? {
+            let comparison = field_comparison(options, &path, &value);
+            let summary = summaries
+                .entry(path.clone())
+                .or_insert_with(|| FieldTypeSummary {
+                    path,
+                    comparisons: BTreeSet::new(),
+                    documents: 0,
+                });
+            summary.comparisons.insert(comparison);
+            summary.documents += 1;
+        }
+    }
+    Ok(summaries.into_values().collect())
+}
+
+/// A guessed type for a field pattern of the ground truth, with a template annotation to
+/// start from.
+#[derive(Debug, Clone, Serialize)]
+pub struct InferredFieldType {
+    /// Field pattern, with array indices as `*`.
+    pub path: String,
+    /// `number`, `date`, `currency`, `boolean` or `text`; `unknown` when every sampled value
+    /// is null.
+    pub inferred_type: &'static str,
+    /// Share of the sampled non-null values that look like `inferred_type`.
+    pub confidence: f64,
+    /// Non-null values classified, at most [`INFERENCE_SAMPLE_SIZE`].
+    pub sampled: u32,
+    /// How many sampled values looked like each type.
+    pub types: BTreeMap<&'static str, u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_annotation: Option<Value>,
+}
+
+/// Guesses the type of every field pattern from its first `sample_size` non-null
+/// ground-truth values (in document id order). Each value is classified on its own and the
+/// most common type wins, ties going to the more specific type.
+pub fn infer_field_types(
+    documents: &BTreeMap<String, Document>,
+    sample_size: usize,
+) -> Result<Vec<InferredFieldType>, EvaluationError> {
+    let mut samples: BTreeMap<String, Vec<ValueType>> = BTreeMap::new();
+    for document in documents.values() {
+        for (path, value) in flatten_fields(&document.fields, Vec::new())? {
+            let sample = samples.entry(field_pattern(&path)).or_default();
+            if sample.len() < sample_size {
+                sample.extend(classify_value(&value));
+            }
+        }
+    }
+    Ok(samples
+        .into_iter()
+        .map(|(path, sample)| infer_from_sample(path, &sample))
+        .collect())
+}
+
+/// The type of a single value; `ParsedNumber` is a number written as a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Number,
+    ParsedNumber,
+    Date,
+    Currency,
+    Boolean,
+    Text,
+}
+
+impl ValueType {
+    fn name(self) -> &'static str {
+        match self {
+            ValueType::Number | ValueType::ParsedNumber => "number",
+            ValueType::Date => "date",
+            ValueType::Currency => "currency",
+            ValueType::Boolean => "boolean",
+            ValueType::Text => "text",
+        }
+    }
+}
+
+fn classify_value(value: &Value) -> Option<ValueType> {
+    let text = match value {
+        Value::Null => return None,
+        Value::Bool(_) => return Some(ValueType::Boolean),
+        Value::Number(_) => return Some(ValueType::Number),
+        Value::String(text) => text.trim(),
+        _ => return Some(ValueType::Text),
+    };
+    let is_number =
+        |text: &str| parse_scientific(text).or_else(|| parse_localized(text, NumberLocale::En));
+    Some(if text.is_empty() {
+        ValueType::Text
+    } else if is_number(text).is_some() {
+        ValueType::ParsedNumber
+    } else if is_date(text) {
+        ValueType::Date
+    } else if strip_currency(text) != text && is_number(&strip_currency(text)).is_some() {
+        ValueType::Currency
+    } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+        ValueType::Boolean
+    } else {
+        ValueType::Text
+    })
+}
+
+fn is_date(text: &str) -> bool {
+    parse_timestamp_millis(text).is_some()
+        || DATE_FORMATS
+            .iter()
+            .any(|format| NaiveDate::parse_from_str(text, format).is_ok())
+}
+
+fn infer_from_sample(path: String, sample: &[ValueType]) -> InferredFieldType {
+    let mut types: BTreeMap<&'static str, u32> = BTreeMap::new();
+    for value_type in sample {
+        *types.entry(value_type.name()).or_default() += 1;
+    }
+    let winner = ["number", "date", "currency", "boolean", "text"]
+        .into_iter()
+        .filter_map(|name| types.get(name).map(|&count| (name, count)))
+        .fold(
+            None,
+            |best: Option<(&str, u32)>, (name, count)| match best {
+                Some((_, best_count)) if best_count >= count => best,
+                _ => Some((name, count)),
+            },
+        );
+    let Some((inferred_type, count)) = winner else {
+        return InferredFieldType {
+            path,
+            inferred_type: "unknown",
+            confidence: 0.0,
+            sampled: 0,
+            types,
+            suggested_annotation: None,
+        };
+    };
+    let suggested_annotation = match inferred_type {
+        "number" if sample.contains(&ValueType::ParsedNumber) => {
+            json!({"type": "string", "x-transform": ["trim", "parse-number"]})
+        }
+        "number" => json!({"type": "number"}),
+        "date" => json!({"type": "string", "format": "date"}),
+        "currency" => {
+            json!({"type": "string", "x-transform": ["trim", "strip-currency", "parse-number"]})
+        }
+        "boolean" => json!({"type": "boolean"}),
+        _ => json!({"type": "string"}),
+    };
+    InferredFieldType {
+        path,
+        inferred_type,
+        confidence: round(f64::from(count) / sample.len() as f64),
+        sampled: sample.len() as u32,
+        types,
+        suggested_annotation: Some(suggested_annotation),
+    }
+}
+
+/// Name of the JSON type of a leaf value.
+pub fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::EvaluationError;
+
+/// Component metrics that may be referenced from a score formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Coverage,
+    Structural,
+    Numeric,
+    Text,
+}
+
+impl Component {
+    pub const NAMES: [&'static str; 4] = ["coverage", "structural", "numeric", "text"];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "coverage" => Some(Self::Coverage),
+            "structural" => Some(Self::Structural),
+            "numeric" => Some(Self::Numeric),
+            "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Values bound to the formula variables when it is evaluated.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentValues {
+    pub coverage: f64,
+    pub structural: f64,
+    pub numeric: f64,
+    pub text: f64,
+}
+
+impl ComponentValues {
+    fn get(&self, component: Component) -> f64 {
+        match component {
+            Component::Coverage => self.coverage,
+            Component::Structural => self.structural,
+            Component::Numeric => self.numeric,
+            Component::Text => self.text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Variable(Component),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// An arithmetic expression over the component metrics that replaces the default
+/// overall-score average, e.g. `0.5*text + 0.3*numeric + 0.2*coverage`.
+///
+/// Supports number literals, the variables listed in [`Component::NAMES`], `+ - * /`,
+/// unary minus, and parentheses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreFormula {
+    source: String,
+    expr: Expr,
+}
+
+impl ScoreFormula {
+    pub fn parse(source: &str) -> Result<Self, EvaluationError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(EvaluationError::InvalidScoreFormula(format!(
+                "unexpected {token} in '{source}'"
+            )));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Evaluates the expression with `values` bound to its variables. Dividing by zero is an
+    /// error rather than a NaN or infinite score.
+    pub fn evaluate(&self, values: &ComponentValues) -> Result<f64, EvaluationError> {
+        match eval(&self.expr, values) {
+            Some(score) if score.is_finite() => Ok(score),
+            Some(_) => Err(EvaluationError::InvalidScoreFormula(format!(
+                "'{}' does not evaluate to a finite number",
+                self.source
+            ))),
+            None => Err(EvaluationError::InvalidScoreFormula(format!(
+                "'{}' divides by zero (coverage={}, structural={}, numeric={}, text={})",
+                self.source, values.coverage, values.structural, values.numeric, values.text
+            ))),
+        }
+    }
+}
+
+impl FromStr for ScoreFormula {
+    type Err = EvaluationError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for ScoreFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// `None` when the expression divides by zero.
+fn eval(expr: &Expr, values: &ComponentValues) -> Option<f64> {
+    let value = match expr {
+        Expr::Number(value) => *value,
+        Expr::Variable(component) => values.get(*component),
+        Expr::Negate(inner) => -eval(inner, values)?,
+        Expr::Binary(lhs, op, rhs) => {
+            let (lhs, rhs) = (eval(lhs, values)?, eval(rhs, values)?);
+            match op {
+                Operator::Add => lhs + rhs,
+                Operator::Subtract => lhs - rhs,
+                Operator::Multiply => lhs * rhs,
+                Operator::Divide if rhs == 0.0 => return None,
+                Operator::Divide => lhs / rhs,
+            }
+        }
+    };
+    Some(value)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "number {value}"),
+            Token::Ident(name) => write!(f, "identifier '{name}'"),
+            Token::Symbol(symbol) => write!(f, "'{symbol}'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, EvaluationError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch.is_ascii_digit() || ch == '.' {
+            let mut literal = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' {
+                    literal.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let value = literal.parse().map_err(|_| {
+                EvaluationError::InvalidScoreFormula(format!("invalid number '{literal}'"))
+            })?;
+            tokens.push(Token::Number(value));
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Ident(name));
+        } else if "+-*/()".contains(ch) {
+            tokens.push(Token::Symbol(ch));
+            chars.next();
+        } else {
+            return Err(EvaluationError::InvalidScoreFormula(format!(
+                "unexpected character '{ch}'"
+            )));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser: `expression := term (('+' | '-') term)*`,
+/// `term := factor (('*' | '/') factor)*`, `factor := '-' factor | atom`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, EvaluationError> {
+        let mut expr = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Subtract
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, EvaluationError> {
+        let mut expr = self.factor()?;
+        loop {
+            let op = if self.eat('*') {
+                Operator::Multiply
+            } else if self.eat('/') {
+                Operator::Divide
+            } else {
+                return Ok(expr);
+            };
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.factor()?));
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expr, EvaluationError> {
+        if self.eat('-') {
+            return Ok(Expr::Negate(Box::new(self.factor()?)));
+        }
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Ident(name)) => {
+                Component::from_name(&name)
+                    .map(Expr::Variable)
+                    .ok_or_else(|| {
+                        EvaluationError::InvalidScoreFormula(format!(
+                            "unknown variable '{name}' (expected one of: {})",
+                            Component::NAMES.join(", ")
+                        ))
+                    })
+            }
+            Some(Token::Symbol('(')) => {
+                let expr = self.expression()?;
+                if !self.eat(')') {
+                    return Err(EvaluationError::InvalidScoreFormula(
+                        "missing closing ')'".into(),
+                    ));
+                }
+                Ok(expr)
+            }
+            Some(token) => Err(EvaluationError::InvalidScoreFormula(format!(
+                "unexpected {token}"
+            ))),
+            None => Err(EvaluationError::InvalidScoreFormula(
+                "unexpected end of expression".into(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use serde_json::Value;
+
+/// Mean Earth radius used by the haversine formula.
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+/// Distance past the tolerance at which a coordinate scores 0.5.
+const HALF_SCORE_DISTANCE_KM: f64 = 1.0;
+
+/// A point given as `{"lat": .., "lon": ..}` in decimal degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinate {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Coordinate {
+    /// Reads an object with numeric `lat` in `[-90, 90]` and `lon` in `[-180, 180]`.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let lat = value.get("lat")?.as_f64()?;
+        let lon = value.get("lon")?.as_f64()?;
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Self { lat, lon })
+    }
+
+    /// Great-circle distance in kilometres.
+    pub fn distance_km(&self, other: &Coordinate) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let half_dlat = (lat2 - lat1) / 2.0;
+        let half_dlon = (other.lon - self.lon).to_radians() / 2.0;
+        let a = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// Scores two coordinates by distance: 1.0 within `tolerance_km`, then
+/// `1 / (1 + excess_km / HALF_SCORE_DISTANCE_KM)`. Returns `None` when either side is not a
+/// valid coordinate.
+pub fn coordinate_similarity(
+    expected: &Value,
+    predicted: &Value,
+    tolerance_km: f64,
+) -> Option<f64> {
+    let distance = Coordinate::from_json(expected)?.distance_km(&Coordinate::from_json(predicted)?);
+    let excess = (distance - tolerance_km).max(0.0);
+    Some(1.0 / (1.0 + excess / HALF_SCORE_DISTANCE_KM))
+}
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{evaluate_predictions_with_options, Document};
+use crate::metrics::EvaluationMetrics;
+use crate::options::EvaluationOptions;
+
+/// Group of ground-truth documents without a usable `--group-by` value.
+pub const UNGROUPED: &str = "__ungrouped__";
+
+/// Headline metrics of the documents sharing one `--group-by` value.
+#[derive(Debug, Serialize)]
+pub struct GroupMetrics {
+    pub num_documents: u32,
+    pub document_coverage: f64,
+    pub numeric_field_similarity: f64,
+    pub text_field_similarity: f64,
+    pub structural_completeness: f64,
+    pub overall_score: f64,
+    #[serde(skip)]
+    metrics: EvaluationMetrics,
+}
+
+impl GroupMetrics {
+    fn from_metrics(metrics: EvaluationMetrics) -> Self {
+        Self {
+            num_documents: metrics.num_documents,
+            document_coverage: metrics.document_coverage,
+            numeric_field_similarity: metrics.numeric_field_similarity,
+            text_field_similarity: metrics.text_field_similarity,
+            structural_completeness: metrics.structural_completeness,
+            overall_score: metrics.overall_score,
+            metrics,
+        }
+    }
+
+    /// Combines the same group scored over disjoint document shards.
+    pub fn merge(self, other: GroupMetrics) -> Result<GroupMetrics, EvaluationError> {
+        Ok(Self::from_metrics(self.metrics.merge(other.metrics)?))
+    }
+}
+
+/// Scores each group of ground-truth documents, keyed by the value at `path` (dot-separated,
+/// e.g. `vendor` or `supplier.country`), against its predictions.
+///
+/// String, number and boolean values name the group as written; documents where the path is
+/// missing, null, an empty string, an object or an array fall into [`UNGROUPED`]. Predictions
+/// without a ground-truth document belong to no group.
+pub fn evaluate_groups(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    path: &str,
+) -> Result<BTreeMap<String, GroupMetrics>, EvaluationError> {
+    let mut groups: BTreeMap<String, BTreeMap<String, Document>> = BTreeMap::new();
+    for (doc_id, document) in ground_truth {
+        groups
+            .entry(group_key(&document.fields, path))
+            .or_default()
+            .insert(doc_id.clone(), document.clone());
+    }
+    groups
+        .into_iter()
+        .map(|(group, ground_truth)| {
+            let predictions: BTreeMap<String, Document> = predictions
+                .iter()
+                .filter(|(doc_id, _)| ground_truth.contains_key(*doc_id))
+                .map(|(doc_id, document)| (doc_id.clone(), document.clone()))
+                .collect();
+            let metrics = evaluate_predictions_with_options(&ground_truth, &predictions, options)?;
+            Ok((group, GroupMetrics::from_metrics(metrics)))
+        })
+        .collect()
+}
+
+fn group_key(fields: &Value, path: &str) -> String {
+    let value = path
+        .split('.')
+        .try_fold(fields, |node, segment| match node {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        });
+    match value {
+        Some(Value::String(text)) if !text.trim().is_empty() => text.trim().to_string(),
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+        _ => UNGROUPED.to_string(),
+    }
+}
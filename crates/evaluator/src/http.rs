@@ -0,0 +1,94 @@
+//! The minimal HTTP/1.x client behind `--webhook` and remote `--ground-truth` URLs: one
+//! request per connection, no redirects and no TLS.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+pub(crate) const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where a request goes, split out of an `http://` URL.
+#[derive(Debug)]
+pub(crate) struct Endpoint {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) path: String,
+}
+
+impl Endpoint {
+    /// Splits `http://host[:port][/path]`; any other scheme, or no host, gives `None`.
+    pub(crate) fn parse(url: &str) -> Option<Self> {
+        let rest = url.strip_prefix("http://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// A response read to the end of the connection.
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) status_line: String,
+    // Only read by remote ground-truth loading.
+    #[cfg_attr(not(feature = "remote"), allow(dead_code))]
+    pub(crate) body: Vec<u8>,
+}
+
+impl Response {
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Writes `request` (everything from the request line on) to `endpoint` and reads the
+/// response until the server closes the connection, so the request must ask for
+/// `Connection: close`. Errors are returned as their description.
+pub(crate) fn exchange(endpoint: &Endpoint, request: &str) -> Result<Response, String> {
+    let address = (endpoint.host.as_str(), endpoint.port)
+        .to_socket_addrs()
+        .map_err(|err| err.to_string())?
+        .next()
+        .ok_or_else(|| format!("could not resolve {}", endpoint.host))?;
+    let mut stream =
+        TcpStream::connect_timeout(&address, TIMEOUT).map_err(|err| err.to_string())?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|err| err.to_string())?;
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|err| err.to_string())?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|err| err.to_string())?;
+    let (head, body) = match response.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(index) => (&response[..index], response[index + 4..].to_vec()),
+        None => (&response[..], Vec::new()),
+    };
+    let head = String::from_utf8_lossy(head);
+    let status_line = head.lines().next().unwrap_or_default().to_string();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| format!("malformed response '{status_line}'"))?;
+    Ok(Response {
+        status,
+        status_line,
+        body,
+    })
+}
@@ -0,0 +1,82 @@
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::EvaluationError;
+
+/// Key of an include directive: `{"$include": "shared/address.json"}` is replaced by the
+/// JSON of that file, resolved relative to the file that contains the directive.
+pub const INCLUDE_KEY: &str = "$include";
+
+/// Inlines every `$include` directive of a ground-truth payload read from `path` (or from
+/// stdin, resolved relative to the working directory). Included files may include others;
+/// a file that ends up including itself is reported as [`EvaluationError::IncludeCycle`].
+/// Payloads without directives are returned unchanged.
+pub fn resolve_includes(payload: &str, path: &Path) -> Result<String, EvaluationError> {
+    if !payload.contains(INCLUDE_KEY) {
+        return Ok(payload.to_string());
+    }
+    let mut value: Value =
+        serde_json::from_str(payload).map_err(|err| EvaluationError::invalid_json(err, payload))?;
+    let mut chain = Vec::new();
+    let base_dir = match path.canonicalize() {
+        Ok(path) => {
+            let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            chain.push(path);
+            base_dir
+        }
+        Err(_) => PathBuf::new(),
+    };
+    inline_includes(&mut value, &base_dir, &mut chain)?;
+    Ok(serde_json::to_string(&value)?)
+}
+
+fn inline_includes(
+    value: &mut Value,
+    base_dir: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<(), EvaluationError> {
+    if let Some(target) = include_target(value) {
+        *value = load_include(&base_dir.join(target), chain)?;
+        return Ok(());
+    }
+    match value {
+        Value::Object(map) => {
+            for entry in map.values_mut() {
+                inline_includes(entry, base_dir, chain)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_includes(item, base_dir, chain)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The path of an object consisting only of a string `$include` entry.
+fn include_target(value: &Value) -> Option<&str> {
+    let map = value.as_object().filter(|map| map.len() == 1)?;
+    map.get(INCLUDE_KEY)?.as_str()
+}
+
+fn load_include(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Value, EvaluationError> {
+    let path = path
+        .canonicalize()
+        .map_err(|_| EvaluationError::IncludeNotFound(path.to_path_buf()))?;
+    if chain.contains(&path) {
+        let mut cycle = chain.clone();
+        cycle.push(path);
+        return Err(EvaluationError::IncludeCycle(cycle));
+    }
+    let payload = fs::read_to_string(&path)?;
+    let mut value: Value = serde_json::from_str(&payload)
+        .map_err(|err| EvaluationError::invalid_json(err, &payload))?;
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    chain.push(path);
+    inline_includes(&mut value, &base_dir, chain)?;
+    chain.pop();
+    Ok(value)
+}
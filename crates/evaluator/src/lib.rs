@@ -1,5 +1,54 @@
+pub mod analysis;
+pub mod annotations;
+pub mod baseline;
+pub mod binary;
+pub mod bootstrap;
+pub mod changes;
+pub mod codes;
+pub mod digest;
+pub mod durations;
+pub mod edits;
 pub mod embedded;
+pub mod encoding;
 pub mod error;
 pub mod evaluator;
+pub mod explain;
+pub mod fields;
+pub mod formula;
+pub mod geo;
+pub mod groups;
+#[cfg(any(feature = "webhook", feature = "remote"))]
+mod http;
+pub mod includes;
+pub mod lint;
+pub mod markup;
 pub mod metrics;
+pub mod money;
+pub mod msgpack;
+pub mod numbers;
+pub mod options;
+pub mod order;
+pub mod percentiles;
+pub mod profile;
+pub mod redact;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod required;
+pub mod retry;
+pub mod review;
+pub mod run_id;
+pub mod runs;
+pub mod sequences;
+pub mod sets;
+pub mod shards;
+pub mod snapshot;
+pub mod stopwords;
+pub mod summary;
+pub mod synonyms;
 pub mod template;
+pub mod transforms;
+pub mod units;
+pub mod verify;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+pub mod window;
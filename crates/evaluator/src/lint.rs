@@ -0,0 +1,330 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::annotations::IdentifierFormat;
+use crate::error::EvaluationError;
+use crate::evaluator::flatten_fields;
+use crate::numbers::{NumericAsymmetry, NumericTolerance};
+use crate::options::TextMetric;
+use crate::sets::SetMode;
+use crate::synonyms::SynonymMap;
+use crate::transforms::Transform;
+use crate::units::UnitSpec;
+
+/// Location reported for problems with the template's root schema.
+pub const ROOT: &str = "(root)";
+
+const JSON_TYPES: &[&str] = &[
+    "array", "boolean", "integer", "null", "number", "object", "string",
+];
+
+/// An annotation keyword, a check of its value and a description of what the check expects.
+type AnnotationCheck = (&'static str, fn(&Value) -> bool, &'static str);
+
+/// Annotation keywords read on property schemas.
+const FIELD_ANNOTATIONS: &[AnnotationCheck] = &[
+    ("x-text-metric", is_text_metric, "a text metric name"),
+    (
+        "x-path-separator",
+        is_non_empty_string,
+        "a non-empty string",
+    ),
+    (
+        "x-numeric-tolerance",
+        is_tolerance,
+        "a number or a \"5%\" string",
+    ),
+    (
+        "x-numeric-asymmetry",
+        is_asymmetry,
+        "{\"over\": .., \"under\": ..} or \"over=2,under=1\"",
+    ),
+    (
+        "x-synonyms",
+        is_synonyms,
+        "an object of term to synonym list",
+    ),
+    (
+        "x-identifier",
+        is_identifier,
+        "a boolean or {\"ignore-separators\": true}",
+    ),
+    ("x-percent", Value::is_boolean, "a boolean"),
+    ("x-coordinate", Value::is_boolean, "a boolean"),
+    ("x-money", Value::is_boolean, "a boolean"),
+    ("x-duration", Value::is_boolean, "a boolean"),
+    ("x-number-sequence", Value::is_boolean, "a boolean"),
+    (
+        "x-unit",
+        is_unit,
+        "a boolean or {\"conversions\": {..}, \"synonyms\": {..}}",
+    ),
+    ("x-set", is_set, "a boolean or \"multiset\""),
+    ("x-sequence", Value::is_boolean, "a boolean"),
+    ("x-json-string", Value::is_boolean, "a boolean"),
+    ("x-stop-words", Value::is_boolean, "a boolean"),
+    (
+        "x-transform",
+        is_transform_pipeline,
+        "a list of known transform names",
+    ),
+    ("x-min-score", is_score, "a number from 0 to 1"),
+    ("x-aliases", is_string_list, "a list of strings"),
+];
+
+/// Annotation keywords read only on the template's root schema.
+const ROOT_ANNOTATIONS: &[AnnotationCheck] = &[
+    ("x-document-type", is_non_empty_string, "a field path"),
+    (
+        "x-required-by-type",
+        is_required_by_type,
+        "an object of type to list of field paths",
+    ),
+];
+
+/// One authoring problem in an extraction template, with `--lint-template`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateIssue {
+    /// Field pattern of the property the problem is in (`items.*.code`), or [`ROOT`].
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Checks an extraction template for mistakes that would otherwise be ignored silently
+/// while scoring: malformed `properties`, `items`, `required` and `type` keywords, unknown
+/// `x-` keywords, annotation values the evaluator cannot read, property names and
+/// `x-aliases` that resolve to the same field path, and annotations outside any property
+/// schema the evaluator reads (e.g. under a misspelt `properties`). An empty list means
+/// the template is clean.
+pub fn lint_template(template: &Value) -> Result<Vec<TemplateIssue>, EvaluationError> {
+    let mut linter = Linter::default();
+    linter.node(template, &mut Vec::new(), &mut Vec::new());
+    linter.stray_annotations(template)?;
+    Ok(linter.issues)
+}
+
+#[derive(Default)]
+struct Linter {
+    issues: Vec<TemplateIssue>,
+    /// Schema paths (`properties.items.items`) of every node walked as a property schema.
+    schema_paths: BTreeSet<String>,
+}
+
+impl Linter {
+    fn report(&mut self, field: &[String], message: String) {
+        let field = if field.is_empty() {
+            ROOT.to_string()
+        } else {
+            field.join(".")
+        };
+        self.issues.push(TemplateIssue { field, message });
+    }
+
+    fn node(&mut self, node: &Value, field: &mut Vec<String>, schema: &mut Vec<String>) {
+        self.schema_paths.insert(schema.join("."));
+        let Some(map) = node.as_object() else {
+            self.report(field, format!("schema must be an object, got {node}"));
+            return;
+        };
+        let known: &[_] = if field.is_empty() {
+            ROOT_ANNOTATIONS
+        } else {
+            FIELD_ANNOTATIONS
+        };
+        for (key, value) in map.iter().filter(|(key, _)| key.starts_with("x-")) {
+            match known.iter().find(|(name, _, _)| name == key) {
+                Some((_, valid, expected)) if !valid(value) => {
+                    self.report(field, format!("{key} must be {expected}, got {value}"));
+                }
+                Some(_) => {}
+                None => self.report(field, format!("unknown annotation {key}")),
+            }
+        }
+        if let Some(types) = map.get("type") {
+            self.types(field, types);
+        }
+        if let Some(properties) = map.get("properties") {
+            self.properties(map, properties, field, schema);
+        } else if map.contains_key("required") {
+            self.report(field, "required is set without properties".to_string());
+        }
+        match map.get("items") {
+            Some(items) if items.is_object() => {
+                field.push("*".to_string());
+                schema.push("items".to_string());
+                self.node(items, field, schema);
+                schema.pop();
+                field.pop();
+            }
+            Some(items) => {
+                self.report(field, format!("items must be a schema object, got {items}"))
+            }
+            None => {}
+        }
+    }
+
+    fn types(&mut self, field: &[String], types: &Value) {
+        let names: Vec<&Value> = match types {
+            Value::Array(names) => names.iter().collect(),
+            name => vec![name],
+        };
+        for name in names {
+            if !name.as_str().is_some_and(|name| JSON_TYPES.contains(&name)) {
+                self.report(field, format!("unknown type {name}"));
+            }
+        }
+    }
+
+    fn properties(
+        &mut self,
+        map: &serde_json::Map<String, Value>,
+        properties: &Value,
+        field: &mut Vec<String>,
+        schema: &mut Vec<String>,
+    ) {
+        let Some(properties) = properties.as_object() else {
+            self.report(
+                field,
+                format!("properties must be an object, got {properties}"),
+            );
+            return;
+        };
+        match map.get("required") {
+            Some(Value::Array(names)) => {
+                for name in names {
+                    match name.as_str() {
+                        Some(name) if properties.contains_key(name) => {}
+                        Some(name) => {
+                            self.report(field, format!("required names unknown property {name}"));
+                        }
+                        None => self.report(field, format!("required must list names, got {name}")),
+                    }
+                }
+            }
+            Some(other) => self.report(field, format!("required must be an array, got {other}")),
+            None => {}
+        }
+
+        let mut names: BTreeMap<&str, &str> = BTreeMap::new();
+        for (key, child) in properties {
+            let aliases = child
+                .get("x-aliases")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str);
+            for name in std::iter::once(key.as_str()).chain(aliases) {
+                if let Some(first) = names.insert(name, key) {
+                    let message = if first == key {
+                        format!("property {key} lists its own name {name} in x-aliases")
+                    } else {
+                        format!("properties {first} and {key} both resolve to the field {name}")
+                    };
+                    self.report(field, message);
+                }
+            }
+        }
+
+        schema.push("properties".to_string());
+        for (key, child) in properties {
+            field.push(key.clone());
+            schema.push(key.clone());
+            self.node(child, field, schema);
+            schema.pop();
+            field.pop();
+        }
+        schema.pop();
+    }
+
+    /// Flattens the whole template and reports `x-` keywords held by objects that were not
+    /// walked as property schemas, which the evaluator never reads.
+    fn stray_annotations(&mut self, template: &Value) -> Result<(), EvaluationError> {
+        let mut strays = BTreeSet::new();
+        for path in flatten_fields(template, Vec::new())?.keys() {
+            let segments: Vec<&str> = path.split('.').collect();
+            let Some(position) = segments
+                .iter()
+                .position(|segment| segment.starts_with("x-"))
+            else {
+                continue;
+            };
+            let owner = segments[..position].join(".");
+            if !self.schema_paths.contains(&owner) {
+                strays.insert((owner, segments[position].to_string()));
+            }
+        }
+        for (owner, key) in strays {
+            self.issues.push(TemplateIssue {
+                field: ROOT.to_string(),
+                message: format!(
+                    "{key} at {owner} is not inside a property schema and is never read"
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn is_text_metric(value: &Value) -> bool {
+    value.as_str().and_then(TextMetric::from_name).is_some()
+}
+
+fn is_non_empty_string(value: &Value) -> bool {
+    value.as_str().is_some_and(|text| !text.is_empty())
+}
+
+fn is_tolerance(value: &Value) -> bool {
+    NumericTolerance::from_json(value).is_some()
+}
+
+fn is_asymmetry(value: &Value) -> bool {
+    NumericAsymmetry::from_json(value).is_some()
+}
+
+fn is_synonyms(value: &Value) -> bool {
+    SynonymMap::from_json(value).is_ok()
+}
+
+// `false` switches these annotations off, so it is accepted though it parses to nothing.
+
+fn is_identifier(value: &Value) -> bool {
+    value == &Value::Bool(false) || IdentifierFormat::from_json(value).is_some()
+}
+
+fn is_unit(value: &Value) -> bool {
+    value == &Value::Bool(false) || UnitSpec::from_json(value).is_some()
+}
+
+fn is_set(value: &Value) -> bool {
+    value == &Value::Bool(false) || SetMode::from_json(value).is_some()
+}
+
+fn is_transform_pipeline(value: &Value) -> bool {
+    Transform::pipeline_from_json(value).is_some()
+}
+
+fn is_score(value: &Value) -> bool {
+    value
+        .as_f64()
+        .is_some_and(|score| (0.0..=1.0).contains(&score))
+}
+
+fn is_string_list(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string))
+}
+
+fn is_required_by_type(value: &Value) -> bool {
+    value
+        .as_object()
+        .is_some_and(|types| types.values().all(is_string_list))
+}
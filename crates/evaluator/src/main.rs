@@ -1,37 +1,780 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use pdf_eval::analysis::worst_fields;
+use pdf_eval::annotations::{field_pattern, FieldAnnotations};
+use pdf_eval::baseline::{compare_with_baseline, latest_metrics_file};
+use pdf_eval::bootstrap::bootstrap_overall_score;
+use pdf_eval::changes::compare_changes;
+use pdf_eval::codes::{parse_code_table_source, CodeTable};
+use pdf_eval::digest::digest_ground_truth;
+use pdf_eval::edits::count_edit_operations;
 use pdf_eval::embedded;
 use pdf_eval::evaluator::{
-    evaluate_predictions, load_ground_truth_from_embed, load_ground_truth_from_path,
-    load_predictions,
+    append_metrics_log, evaluate_predictions_with_checkpoint, evaluate_predictions_with_options,
+    explain_text_field, is_stdin, is_url, load_ground_truth_from_embed,
+    load_ground_truth_from_path_with, load_inline_document, load_predictions_retrying,
+    save_document_scores, unwrap_field_values, Checkpoint, Document,
 };
-use pdf_eval::metrics::EvaluationMetrics;
+use pdf_eval::fields::{
+    infer_field_types, list_field_paths, resolve_field_types, INFERENCE_SAMPLE_SIZE,
+};
+use pdf_eval::formula::ScoreFormula;
+use pdf_eval::groups::evaluate_groups;
+use pdf_eval::lint::lint_template;
+use pdf_eval::money::FxRates;
+use pdf_eval::msgpack::{from_msgpack, is_msgpack, to_msgpack, OutputFormat};
+use pdf_eval::numbers::{NumberLocale, NumericAsymmetry, NumericTolerance};
+use pdf_eval::options::{
+    parse_credit, parse_depth_decay, parse_duration, parse_geo_tolerance, parse_outlier_factor,
+    parse_score_threshold, ComponentWeighting, CoverageMode, EvaluationOptions, FieldBuckets,
+    FieldMinimum, InputFormat, KeyAlias, PathRemap, TextLengthWeighting, TextMetric,
+    ValidationMode, DEFAULT_MAX_FIELD_LENGTH,
+};
+use pdf_eval::order::check_order;
+use pdf_eval::percentiles::{parse_percentile, ErrorBaseline, DEFAULT_ERROR_PERCENTILE};
+use pdf_eval::redact::{empty_collections_as_null, redact_values, to_output_json, to_output_line};
+use pdf_eval::required::{missing_required_fields, RequiredFields};
+use pdf_eval::retry::ReadRetry;
+use pdf_eval::review::{
+    decisions_by_document, review_candidates, review_fields, AmbiguousBand, ReviewDecision,
+};
+use pdf_eval::run_id::derive_run_id;
+use pdf_eval::runs::evaluate_runs;
+use pdf_eval::snapshot::{check_snapshot, SnapshotOutcome};
+use pdf_eval::stopwords::StopWords;
+use pdf_eval::summary::terminal_summary;
+use pdf_eval::synonyms::SynonymMap;
 use pdf_eval::template;
+use pdf_eval::verify::{verify_metrics, Thresholds};
+use pdf_eval::window::{evaluate_decayed, parse_half_life};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Error for a scoring run without predictions, naming the modes that run without them.
+const PREDICTIONS_REQUIRED: &str =
+    "--predictions (or --predict-json / --runs) is required for scoring; \
+     only --info, --template, --verify-ground-truth, --lint-template, --list-fields, \
+     --dump-field-types, --infer-types and the verify/digest subcommands run without it";
 
 #[derive(Debug, Parser)]
 #[command(about = "Score prediction JSON files against an embedded ground truth.")]
 struct Args {
-    #[arg(long, help = "Path to the predictions JSON file")]
+    #[arg(long, help = "Path to the predictions JSON file ('-' reads stdin)")]
     predictions: Option<PathBuf>,
 
-    #[arg(long, help = "Optional path to an alternate ground truth JSON file")]
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Retry reading prediction files up to N times, with doubling backoff from 100ms, on transient IO errors such as stale network-filesystem handles"
+    )]
+    read_retries: u32,
+
+    #[arg(
+        long,
+        help = "Optional path to an alternate ground truth JSON file ('-' reads stdin, an http:// URL needs the remote feature)"
+    )]
     ground_truth: Option<PathBuf>,
 
     #[arg(long, help = "Write metrics to this path instead of stdout")]
     output: Option<PathBuf>,
 
+    #[arg(
+        long,
+        help = "Write one <document_id>.json per document plus aggregate.json into this directory"
+    )]
+    output_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Validate every input record and stop at the first structural problem, reporting its position"
+    )]
+    fail_fast: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "fail_fast",
+        help = "Validate every input record and report all structural problems at once"
+    )]
+    collect_errors: bool,
+
     #[arg(long, help = "Print build metadata and exit")]
     info: bool,
 
+    #[arg(
+        long,
+        help = "Check the embedded ground truth against its build-time SHA-256 and exit"
+    )]
+    verify_ground_truth: bool,
+
+    #[arg(
+        long,
+        help = "Verify the embedded ground truth before every run that scores against it"
+    )]
+    paranoid: bool,
+
     #[arg(long, help = "Print the extraction template JSON and exit")]
     template: bool,
+
+    #[arg(
+        long,
+        help = "Print every leaf field path in the ground truth with its value types and exit"
+    )]
+    list_fields: bool,
+
+    #[arg(
+        long,
+        help = "Check the extraction template (or --template-file) for malformed structure, unknown or invalid annotations and duplicate field paths, print the problems and exit"
+    )]
+    lint_template: bool,
+
+    #[arg(
+        long,
+        help = "Print every scored ground-truth field path with the comparison type resolved for it (after template annotations and detection) and exit"
+    )]
+    dump_field_types: bool,
+
+    #[arg(
+        long,
+        help = "Print to stderr how overall_score is computed from the component metrics and their weights (or --score-formula)"
+    )]
+    explain_score: bool,
+
+    #[arg(
+        long,
+        help = "Guess each ground-truth field's type (number, date, currency, boolean or text) from sampled values, print it with a confidence and a suggested template annotation, and exit"
+    )]
+    infer_types: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ComponentWeighting::Equal,
+        help = "How numeric and text similarity are weighted in the overall score"
+    )]
+    component_weighting: ComponentWeighting,
+
+    #[arg(
+        long,
+        conflicts_with = "only_text",
+        help = "Average only numeric fields into the similarity (text fields are still reported per field); the numeric similarity takes the text share of the overall score"
+    )]
+    only_numeric: bool,
+
+    #[arg(
+        long,
+        help = "Average only text fields into the similarity (numeric fields are still reported per field); the text similarity takes the numeric share of the overall score"
+    )]
+    only_text: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = CoverageMode::Binary,
+        help = "Count a predicted document fully (binary) or by the fraction of its fields present (weighted)"
+    )]
+    coverage_mode: CoverageMode,
+
+    #[arg(
+        long,
+        help = "Arithmetic expression over coverage, structural, numeric and text used as the overall score"
+    )]
+    score_formula: Option<ScoreFormula>,
+
+    #[arg(
+        long,
+        help = "Previous predictions JSON; report score changes for fields whose value changed ('-' reads stdin)"
+    )]
+    previous_predictions: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TextMetric::Gestalt,
+        help = "Similarity metric for text fields without an x-text-metric template annotation"
+    )]
+    text_metric: TextMetric,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "METRICS",
+        help = "Also score text fields with each of these metrics (e.g. gestalt,hierarchical-path) and report the highest-scoring one per field"
+    )]
+    compare_text_metrics: Vec<TextMetric>,
+
+    #[arg(
+        long,
+        help = "Segment separator for hierarchical-path text scoring [default: /]"
+    )]
+    path_separator: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        default_value = "0s",
+        help = "Timestamps closer than this (e.g. 30s, 1h) score proportionally; 0s requires the same instant"
+    )]
+    timestamp_tolerance: Duration,
+
+    #[arg(
+        long,
+        value_parser = parse_geo_tolerance,
+        default_value_t = 0.0,
+        help = "Coordinate fields (x-coordinate) within this many kilometres of the ground truth score 1.0"
+    )]
+    geo_tolerance_km: f64,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "JSON object of currency code to rate in a common base (e.g. {\"USD\": 1, \"EUR\": 1.08}); x-money amounts in two listed currencies are compared after conversion"
+    )]
+    fx_rates: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Also score numeric fields predicted as strings, read with this locale (en: 1,234.56, eu: 1.234,56)"
+    )]
+    locale: Option<NumberLocale>,
+
+    #[arg(
+        long,
+        help = "Numeric difference scored as exact: absolute (0.01) or relative (5%); x-numeric-tolerance annotations take precedence"
+    )]
+    numeric_tolerance: Option<NumericTolerance>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "JSON object of field (array indices as *) to historical absolute errors; each field's tolerance becomes the --error-percentile of its errors"
+    )]
+    error_baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_parser = parse_percentile,
+        value_name = "P",
+        default_value_t = DEFAULT_ERROR_PERCENTILE,
+        requires = "error_baseline",
+        help = "Percentile (0-100] of the --error-baseline errors used as each field's tolerance"
+    )]
+    error_percentile: f64,
+
+    #[arg(
+        long,
+        value_name = "over=SLOPE,under=SLOPE",
+        help = "Penalize numeric over- and under-predictions with different slopes (e.g. over=2,under=1); x-numeric-asymmetry annotations take precedence"
+    )]
+    numeric_asymmetry: Option<NumericAsymmetry>,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_MAX_FIELD_LENGTH,
+        help = "Truncate text values to this many characters before scoring (0 disables the cap)"
+    )]
+    max_field_length: usize,
+
+    #[arg(
+        long,
+        help = "Read field annotations from this extraction template instead of the embedded one"
+    )]
+    template_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        num_args = 1..,
+        value_name = "FILE",
+        conflicts_with = "predictions",
+        help = "Prediction files from repeated runs; report the mean and variance of their scores"
+    )]
+    runs: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Mask expected/predicted values in every output with same-length placeholders; scores and paths are kept"
+    )]
+    redact: bool,
+
+    #[arg(
+        long,
+        value_name = "JSON",
+        requires = "predict_json",
+        conflicts_with = "ground_truth",
+        help = "Ground-truth fields object of a single document, inline (use with --predict-json)"
+    )]
+    truth_json: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "JSON",
+        requires = "truth_json",
+        conflicts_with_all = ["predictions", "runs"],
+        help = "Predicted fields object of a single document, inline (use with --truth-json)"
+    )]
+    predict_json: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "redact",
+        requires = "output_dir",
+        help = "Attach the expected and predicted values (cut to --max-field-length) to each field of the per-document breakdown written to --output-dir"
+    )]
+    include_raw_values: bool,
+
+    #[arg(
+        long,
+        help = "Score only documents present in both ground truth and predictions; excluded counts are reported separately"
+    )]
+    intersection_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "intersection_only",
+        help = "Fail, listing the missing documents, unless every ground-truth document has a prediction (document_coverage of 1.0), whatever the score"
+    )]
+    strict_coverage: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Score only the first N ground-truth documents in id order (and their predictions), e.g. for bounded CI smoke tests"
+    )]
+    max_documents: Option<usize>,
+
+    #[arg(
+        long,
+        value_parser = parse_depth_decay,
+        help = "Scale each field's weight in the similarities by this factor per nesting level [default: 1.0, no decay]"
+    )]
+    depth_decay: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "POST the metrics JSON to this http:// URL after scoring (requires the webhook feature)"
+    )]
+    webhook: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "JSON object of canonical term to synonyms; text fields whose values share a canonical term score 1.0"
+    )]
+    synonyms: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_parser = parse_code_table_source,
+        value_name = "FIELD=FILE",
+        help = "JSON object of code to label for FIELD (array indices as *); a code on either side is read as its label, so 2 matches \"Paid\" (repeatable)"
+    )]
+    code_table: Vec<(String, PathBuf)>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Report order inversions among matched elements of this array field (repeatable, e.g. items)"
+    )]
+    check_order: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Report fields the template requires (per x-document-type, if typed) that a prediction lacks, even when the ground truth omits them too"
+    )]
+    check_required: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Score this ordered array field as one value by aligning its elements, so inserted or dropped elements do not shift the rest (repeatable, e.g. items; same as x-sequence)"
+    )]
+    sequence: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Also report metrics per value of this ground-truth field (e.g. vendor); documents without it are grouped as __ungrouped__"
+    )]
+    group_by: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Report a 95% confidence interval for the overall score from N bootstrap resamples of the documents"
+    )]
+    bootstrap: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "SEED",
+        requires = "bootstrap",
+        help = "Seed for --bootstrap resampling (defaults to one drawn from the clock and reported in the output)"
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        help = "Id reported as run_id in the metrics (defaults to a UUID derived from the arguments, start time and process id; none with --snapshot)"
+    )]
+    run_id: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "H",
+        value_parser = parse_half_life,
+        requires = "decay_by",
+        help = "Report an overall score in which each document counts half as much per H it is older than the newest (see --decay-by)"
+    )]
+    half_life: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "half_life",
+        help = "Ground-truth field giving each document's ingestion order (a number) or date/timestamp (H then in days) for --half-life"
+    )]
+    decay_by: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Append the metrics and a timestamp as one JSON line to this run log, creating it if needed"
+    )]
+    append: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Report headline metric deltas against the most recently modified *.json metrics file in DIR"
+    )]
+    baseline_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_parser = parse_credit,
+        default_value_t = 0.0,
+        help = "Score between 0 and 1 given to optional template fields missing from a prediction"
+    )]
+    optional_missing_credit: f64,
+
+    #[arg(
+        long,
+        help = "Compare integer values exactly as integers (1.0 if equal, else 0.0) instead of through lossy floats"
+    )]
+    strict_numbers: bool,
+
+    #[arg(
+        long,
+        help = "Score numbers inside text fields (\"12.5%\" vs \"12.53%\") by numeric similarity and the surrounding text separately"
+    )]
+    embedded_numbers: bool,
+
+    #[arg(
+        long,
+        help = "Read null as 0 in numeric fields, on either side, so a null amount matches a zero amount"
+    )]
+    null_numeric_as_zero: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TextLengthWeighting::None,
+        help = "Weight each text field in text similarity by the length (or its square root) of the expected value"
+    )]
+    text_length_weighting: TextLengthWeighting,
+
+    #[arg(
+        long,
+        value_name = "KEY",
+        help = "Score predicted fields wrapped as {\"<KEY>\": ..., <provenance>} by their KEY value alone"
+    )]
+    value_key: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = InputFormat::Records,
+        help = "Layout of prediction files: an array of records, or an object-map of document id to fields"
+    )]
+    input_format: InputFormat,
+
+    #[arg(
+        long,
+        help = "Report insertion, deletion and substitution counts across text fields"
+    )]
+    edit_operations: bool,
+
+    #[arg(
+        long,
+        help = "Print document_id,overall_score CSV lines sorted by score instead of the JSON metrics"
+    )]
+    summary_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "summary_only",
+        help = "Print only the fields that did not score 1.0, per document, with their expected and predicted values and score, instead of the JSON metrics"
+    )]
+    diff_only: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write a CSV matrix of ground-truth field paths (rows) by documents (columns): 1 present, 0 missing, empty when not in the document's ground truth"
+    )]
+    coverage_matrix: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Report cumulative time spent flattening, scoring numeric and text fields, and matching field paths"
+    )]
+    profile: bool,
+
+    #[arg(
+        long,
+        help = "Print JSON on a single line instead of pretty-printed (also for --output)"
+    )]
+    compact: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Json,
+        conflicts_with_all = ["snapshot", "summary_only"],
+        help = "Encoding of the printed output (also for --output); msgpack is the JSON document in binary form"
+    )]
+    format: OutputFormat,
+
+    #[arg(
+        long,
+        help = "Print empty objects and arrays in the JSON output as null instead of {} and [] (also for --output)"
+    )]
+    empty_as_null: bool,
+
+    #[arg(
+        long,
+        help = "Print the terminal summary (shown on stderr when stdout is a terminal) without colors"
+    )]
+    no_color: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Compare the printed output with this stored snapshot and fail with a line diff when they differ"
+    )]
+    snapshot: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "snapshot",
+        help = "Write the current output to the --snapshot file instead of comparing against it"
+    )]
+    update_snapshots: bool,
+
+    #[arg(
+        long,
+        value_name = "GT_PATH=PREDICTED_PATH",
+        help = "Score predicted fields at PREDICTED_PATH (or nested below it) as if they were at GT_PATH (repeatable)"
+    )]
+    remap: Vec<PathRemap>,
+
+    #[arg(
+        long,
+        value_name = "ALIAS=CANONICAL",
+        help = "Read object keys named ALIAS, at any nesting level, as CANONICAL on both sides (repeatable; overrides the template's x-aliases)"
+    )]
+    alias: Vec<KeyAlias>,
+
+    #[arg(
+        long,
+        value_parser = parse_outlier_factor,
+        value_name = "F",
+        help = "Report numeric predictions at least F times larger or smaller than expected (e.g. 10 for decimal-point errors)"
+    )]
+    outlier_factor: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "DOC:PATH",
+        help = "Print the score and gestalt matching blocks of one text field (e.g. doc-1:customer.name) and exit"
+    )]
+    explain_text: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "english|FILE",
+        help = "Drop these words (built-in English list or a whitespace-separated file) from x-stop-words text fields before scoring"
+    )]
+    stop_words: Option<String>,
+
+    #[arg(
+        long,
+        help = "Decode string fields that hold a JSON object or array and score them structurally, as x-json-string does"
+    )]
+    parse_json_strings: bool,
+
+    #[arg(
+        long,
+        help = "Drop null elements from the start and end of every array before flattening, so null padding does not count as extra fields"
+    )]
+    trim_null_array_elements: bool,
+
+    #[arg(
+        long = "field-min",
+        value_name = "PATH=SCORE",
+        help = "Fail (after printing the metrics) when the mean score of this field across documents is below SCORE (repeatable)"
+    )]
+    field_min: Vec<FieldMinimum>,
+
+    #[arg(
+        long,
+        value_name = "SENTINEL",
+        help = "Ground-truth value marking a field as not annotated (e.g. __IGNORE__); such fields are excluded from every metric"
+    )]
+    ignore_value: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Report up to N field paths whose mean score across documents is below --worst-field-threshold, worst first"
+    )]
+    worst_fields: Option<usize>,
+
+    #[arg(
+        long,
+        value_parser = parse_score_threshold,
+        default_value_t = 0.5,
+        help = "Mean field score below which --worst-fields reports a field"
+    )]
+    worst_field_threshold: f64,
+
+    #[arg(
+        long,
+        value_parser = parse_score_threshold,
+        value_name = "MIN_SCORE",
+        help = "Count numeric fields scoring at least MIN_SCORE but below 1.0 as near misses (e.g. 0.9)"
+    )]
+    near_miss_band: Option<f64>,
+
+    #[arg(
+        long,
+        conflicts_with = "checkpoint",
+        help = "Prompt on the terminal to accept or reject fields scoring within --ambiguous-band, overriding their scores (skipped without a terminal)"
+    )]
+    interactive: bool,
+
+    #[arg(
+        long,
+        value_name = "LOW,HIGH",
+        default_value = "0.5,0.9",
+        help = "Field scores offered for review by --interactive"
+    )]
+    ambiguous_band: AmbiguousBand,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "interactive",
+        help = "Write the --interactive accept/reject decisions to FILE as JSON"
+    )]
+    review_decisions: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        help = "Threads used to score documents (0 uses all cores, 1 is sequential)"
+    )]
+    parallel: usize,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Save scoring progress to this file while evaluating (scores sequentially)"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 100,
+        requires = "checkpoint",
+        help = "Ground-truth documents scored between two --checkpoint saves"
+    )]
+    checkpoint_every: usize,
+
+    #[arg(
+        long,
+        requires = "checkpoint",
+        help = "Skip documents already saved in the --checkpoint file instead of starting over"
+    )]
+    resume: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check an archived metrics file against thresholds without re-running the evaluation.
+    Verify(VerifyArgs),
+    /// Print the SHA-256 and document count that embedding a ground-truth file would record.
+    Digest {
+        #[arg(value_name = "FILE", help = "Candidate ground-truth file")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+struct VerifyArgs {
+    #[arg(
+        value_name = "METRICS",
+        help = "Metrics written by --output, as JSON or --format msgpack (or one --append run-log entry)"
+    )]
+    metrics: PathBuf,
+
+    #[arg(
+        long,
+        value_parser = parse_score_threshold,
+        value_name = "SCORE",
+        help = "Fail when overall_score is below SCORE"
+    )]
+    min_score: Option<f64>,
+
+    #[arg(
+        long,
+        value_parser = parse_score_threshold,
+        value_name = "SCORE",
+        help = "Fail when document_coverage is below SCORE"
+    )]
+    min_coverage: Option<f64>,
+
+    #[arg(
+        long = "field-min",
+        value_name = "PATH=SCORE",
+        help = "Fail when the field's mean score recorded under field_minimums is below SCORE (repeatable)"
+    )]
+    field_min: Vec<FieldMinimum>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::Verify(verify)) => return verify_thresholds(verify),
+        Some(Command::Digest { path }) => return print_digest(path),
+        None => {}
+    }
+
     if args.info {
         println!("{}", embedded::build_info_json());
         return Ok(());
@@ -42,38 +785,646 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    let predictions_path = args
-        .predictions
-        .as_deref()
-        .context("--predictions is required unless --info is specified")?;
+    if args.verify_ground_truth {
+        let digest =
+            embedded::verify_ground_truth().context("embedded ground truth failed verification")?;
+        println!(
+            "{}",
+            serde_json::json!({ "ground_truth_sha256": digest, "verified": true })
+        );
+        return Ok(());
+    }
+
+    if args.lint_template {
+        let issues =
+            lint_template(&load_template(&args)?).context("failed to lint the template")?;
+        emit_json(&issues, &args)?;
+        if !issues.is_empty() {
+            bail!(
+                "template has {} problem(s):{}",
+                issues.len(),
+                issues
+                    .iter()
+                    .map(|issue| format!("\n  - {issue}"))
+                    .collect::<String>()
+            );
+        }
+        return Ok(());
+    }
+
+    if args.predictions.is_none()
+        && args.predict_json.is_none()
+        && args.runs.is_empty()
+        && !args.list_fields
+        && !args.dump_field_types
+        && !args.infer_types
+    {
+        bail!("{PREDICTIONS_REQUIRED}");
+    }
+
+    let stdin_inputs = [
+        args.predictions.as_deref(),
+        args.ground_truth.as_deref(),
+        args.previous_predictions.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(args.runs.iter().map(PathBuf::as_path))
+    .filter(|path| is_stdin(path))
+    .count();
+    if stdin_inputs > 1 {
+        bail!("only one of --predictions, --ground-truth, --previous-predictions and --runs can read from stdin");
+    }
+
+    let validation = if args.fail_fast {
+        ValidationMode::FailFast
+    } else if args.collect_errors {
+        ValidationMode::CollectErrors
+    } else {
+        ValidationMode::Lenient
+    };
 
-    let ground_truth = if let Some(path) = &args.ground_truth {
-        load_ground_truth_from_path(path)
+    let ground_truth = if let Some(fields) = &args.truth_json {
+        load_inline_document(fields).context("--truth-json is not a valid fields object")?
+    } else if let Some(url) = args.ground_truth.as_deref().filter(|path| is_url(path)) {
+        load_remote_ground_truth(&url.to_string_lossy(), validation)?
+    } else if let Some(path) = &args.ground_truth {
+        load_ground_truth_from_path_with(path, validation)
             .with_context(|| format!("failed to load ground truth from {}", path.display()))?
     } else {
+        if args.paranoid {
+            embedded::verify_ground_truth().context("embedded ground truth failed verification")?;
+        }
         load_ground_truth_from_embed().context("embedded ground truth is missing")?
     };
 
-    let predictions = load_predictions(predictions_path).with_context(|| {
-        format!(
-            "failed to load predictions from {}",
-            predictions_path.display()
+    if args.list_fields {
+        let fields = list_field_paths(&ground_truth).context("failed to list field paths")?;
+        return emit_json(&fields, &args);
+    }
+
+    if args.infer_types {
+        let fields = infer_field_types(&ground_truth, INFERENCE_SAMPLE_SIZE)
+            .context("failed to infer field types")?;
+        return emit_json(&fields, &args);
+    }
+
+    let mut options = build_options(&args)?;
+
+    if args.dump_field_types {
+        let fields = resolve_field_types(&ground_truth, &options)
+            .context("failed to resolve field types")?;
+        return emit_json(&fields, &args);
+    }
+
+    if !args.runs.is_empty() {
+        let runs = args
+            .runs
+            .iter()
+            .map(|path| load_predictions_from(path, validation, &args))
+            .collect::<Result<Vec<_>>>()?;
+        let summary = evaluate_runs(&ground_truth, &runs, &options)
+            .context("failed to compute repeated-run metrics")?;
+        return emit_json(&summary, &args);
+    }
+
+    let predictions = match &args.predict_json {
+        Some(fields) => {
+            let mut predictions = load_inline_document(fields)
+                .context("--predict-json is not a valid fields object")?;
+            if let Some(value_key) = &args.value_key {
+                unwrap_field_values(&mut predictions, value_key);
+            }
+            predictions
+        }
+        None => {
+            let predictions_path = args.predictions.as_deref().context(PREDICTIONS_REQUIRED)?;
+            load_predictions_from(predictions_path, validation, &args)?
+        }
+    };
+
+    if let Some(target) = &args.explain_text {
+        let (doc_id, path) = target
+            .rsplit_once(':')
+            .context("--explain-text expects DOC:PATH, e.g. doc-1:customer.name")?;
+        let explanation = explain_text_field(&ground_truth, &predictions, &options, doc_id, path)
+            .with_context(|| format!("failed to explain {target}"))?;
+        return emit_json(&explanation, &args);
+    }
+
+    let mut metrics = match &args.checkpoint {
+        Some(path) => {
+            let checkpoint = Checkpoint {
+                path: path.clone(),
+                every: args.checkpoint_every,
+                resume: args.resume,
+            };
+            evaluate_predictions_with_checkpoint(&ground_truth, &predictions, &options, &checkpoint)
+        }
+        None => evaluate_predictions_with_options(&ground_truth, &predictions, &options),
+    }
+    .context("failed to compute evaluation metrics")?;
+
+    if args.interactive {
+        let decisions = review_ambiguous_fields(&ground_truth, &predictions, &options, &args)?;
+        if let Some(path) = &args.review_decisions {
+            std::fs::write(path, serde_json::to_string_pretty(&decisions)? + "\n")
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        if !decisions.is_empty() {
+            options.field_decisions = decisions_by_document(&decisions);
+            metrics = evaluate_predictions_with_options(&ground_truth, &predictions, &options)
+                .context("failed to compute evaluation metrics")?;
+        }
+    }
+
+    if let Some(path) = &args.previous_predictions {
+        let mut previous = load_predictions_retrying(
+            path,
+            validation,
+            args.input_format,
+            &ReadRetry::new(args.read_retries),
         )
-    })?;
+        .with_context(|| {
+            format!(
+                "failed to load previous predictions from {}",
+                path.display()
+            )
+        })?;
+        if let Some(value_key) = &args.value_key {
+            unwrap_field_values(&mut previous, value_key);
+        }
+        metrics.changes = Some(
+            compare_changes(&ground_truth, &previous, &predictions, &options)
+                .context("failed to compare against previous predictions")?,
+        );
+    }
+
+    if let Some(dir) = &args.baseline_dir {
+        let path = latest_metrics_file(dir)
+            .with_context(|| format!("failed to find a baseline run in {}", dir.display()))?;
+        metrics.baseline = Some(
+            compare_with_baseline(&metrics, &path)
+                .with_context(|| format!("failed to compare against {}", path.display()))?,
+        );
+    }
+
+    if let Some(limit) = args.worst_fields {
+        metrics.worst_fields = Some(worst_fields(
+            &metrics.documents,
+            args.worst_field_threshold,
+            limit,
+        ));
+    }
+
+    if args.edit_operations {
+        metrics.edit_operations = Some(
+            count_edit_operations(&ground_truth, &predictions, &options)
+                .context("failed to count edit operations")?,
+        );
+    }
+
+    if let Some(resamples) = args.bootstrap {
+        let seed = args.seed.unwrap_or_else(clock_seed);
+        metrics.confidence_interval = Some(
+            bootstrap_overall_score(&ground_truth, &predictions, &options, resamples, seed)
+                .context("failed to bootstrap the overall score")?,
+        );
+    }
+
+    if let (Some(half_life), Some(path)) = (args.half_life, &args.decay_by) {
+        metrics.decayed_score = Some(
+            evaluate_decayed(&ground_truth, &predictions, &options, path, half_life)
+                .with_context(|| format!("failed to decay scores by {path}"))?,
+        );
+    }
+
+    if let Some(path) = &args.group_by {
+        metrics.groups = Some(
+            evaluate_groups(&ground_truth, &predictions, &options, path)
+                .with_context(|| format!("failed to score groups of {path}"))?,
+        );
+    }
+
+    if args.check_required {
+        let required = RequiredFields::from_template(&load_template(&args)?);
+        metrics.missing_required_fields = Some(missing_required_fields(
+            &ground_truth,
+            &predictions,
+            &required,
+        ));
+    }
+
+    if !args.check_order.is_empty() {
+        metrics.order_violations =
+            Some(check_order(&ground_truth, &predictions, &args.check_order));
+    }
+
+    // Generated ids differ on every run, which would never match a stored snapshot.
+    metrics.run_id = args.run_id.clone().or_else(|| {
+        args.snapshot.is_none().then(|| {
+            let arguments: Vec<String> = std::env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect();
+            derive_run_id(&arguments, clock_nanos(), std::process::id())
+        })
+    });
+
+    if let Some(dir) = &args.output_dir {
+        save_document_scores(dir, &metrics, args.redact).with_context(|| {
+            format!("failed to write per-document results to {}", dir.display())
+        })?;
+    }
+
+    if args.explain_score {
+        eprint!("{}", metrics.explain_score()?);
+    }
+
+    for (doc_id, paths) in &metrics.mojibake_fields {
+        for path in paths {
+            eprintln!("warning: {doc_id}: predicted field {path} looks mis-encoded (mojibake)");
+        }
+    }
+
+    if let Some(path) = &args.coverage_matrix {
+        std::fs::write(path, metrics.coverage_matrix_csv())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    if args.summary_only {
+        emit_text(&metrics.document_summary_csv(), &args)?;
+    } else if args.diff_only {
+        emit_json(&metrics.field_diffs(), &args)?;
+    } else {
+        emit_json(&metrics, &args)?;
+    }
+
+    if std::io::stdout().is_terminal() {
+        let color = !args.no_color
+            && std::env::var_os("NO_COLOR").is_none()
+            && std::io::stderr().is_terminal();
+        eprint!("{}", terminal_summary(&metrics, color));
+    }
+
+    if let Some(path) = &args.append {
+        append_metrics_log(path, &metrics, args.redact)
+            .with_context(|| format!("failed to append metrics to {}", path.display()))?;
+    }
+
+    if let Some(url) = &args.webhook {
+        post_webhook(url, &to_output_json(&metrics, args.redact)?)?;
+    }
+
+    if args.strict_coverage && metrics.document_coverage < 1.0 {
+        bail!(
+            "--strict-coverage: document_coverage is {}, missing documents: {}",
+            metrics.document_coverage,
+            metrics.missing_documents.join(", ")
+        );
+    }
 
-    let metrics = evaluate_predictions(&ground_truth, &predictions)
+    let failed: Vec<String> = metrics
+        .field_minimums
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| match check.mean_score {
+            Some(mean) => format!("{} ({mean} < {})", check.field, check.min_score),
+            None => format!("{} (never scored)", check.field),
+        })
+        .collect();
+    if !failed.is_empty() {
+        bail!(
+            "critical fields below their minimum score: {}",
+            failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Prompts for each field scoring within the ambiguous band when stdin and stderr are both
+/// terminals; otherwise warns and keeps the automatic scores.
+fn review_ambiguous_fields(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    args: &Args,
+) -> Result<Vec<ReviewDecision>> {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        eprintln!("warning: --interactive needs a terminal; keeping automatic scores");
+        return Ok(Vec::new());
+    }
+    let review_options = EvaluationOptions {
+        include_raw_values: true,
+        ..options.clone()
+    };
+    let metrics = evaluate_predictions_with_options(ground_truth, predictions, &review_options)
         .context("failed to compute evaluation metrics")?;
+    let candidates = review_candidates(&metrics, args.ambiguous_band);
+    review_fields(
+        &candidates,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    )
+    .context("failed to read review decisions")
+}
 
-    emit_metrics(&metrics, args.output.as_deref())?;
+fn clock_seed() -> u64 {
+    clock_nanos() as u64
+}
+
+fn clock_nanos() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos())
+}
+
+fn print_digest(path: &Path) -> Result<()> {
+    let raw = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let digest = digest_ground_truth(&raw)
+        .map_err(anyhow::Error::msg)
+        .with_context(|| format!("{} cannot be embedded", path.display()))?;
+    println!(
+        "{}",
+        serde_json::json!({
+            "ground_truth_sha256": digest.sha256,
+            "document_count": digest.document_count,
+        })
+    );
     Ok(())
 }
 
-fn emit_metrics(metrics: &EvaluationMetrics, output: Option<&std::path::Path>) -> Result<()> {
-    let payload = serde_json::to_string_pretty(metrics)?;
-    if let Some(path) = output {
-        std::fs::write(path, payload.clone() + "\n")
+fn verify_thresholds(args: &VerifyArgs) -> Result<()> {
+    let thresholds = Thresholds {
+        min_score: args.min_score,
+        min_coverage: args.min_coverage,
+        field_minimums: args.field_min.clone(),
+    };
+    if thresholds.min_score.is_none()
+        && thresholds.min_coverage.is_none()
+        && thresholds.field_minimums.is_empty()
+    {
+        bail!("verify needs at least one of --min-score, --min-coverage or --field-min");
+    }
+    let path = &args.metrics;
+    let raw = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let metrics: serde_json::Value = if is_msgpack(&raw) {
+        from_msgpack(&raw)
+            .with_context(|| format!("metrics {} is not valid MessagePack", path.display()))?
+    } else {
+        serde_json::from_slice(&raw)
+            .with_context(|| format!("metrics {} is not valid JSON", path.display()))?
+    };
+    let checks = verify_metrics(&metrics, &thresholds);
+    println!("{}", serde_json::to_string_pretty(&checks)?);
+    let failed: Vec<String> = checks
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| check.describe_failure())
+        .collect();
+    if !failed.is_empty() {
+        bail!("thresholds not met: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "remote")]
+fn load_remote_ground_truth(
+    url: &str,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>> {
+    pdf_eval::remote::load_ground_truth_from_url_with(url, validation)
+        .with_context(|| format!("failed to load ground truth from {url}"))
+}
+
+#[cfg(not(feature = "remote"))]
+fn load_remote_ground_truth(
+    _url: &str,
+    _validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>> {
+    bail!("--ground-truth URLs require pdf_eval to be built with the `remote` feature")
+}
+
+#[cfg(feature = "webhook")]
+fn post_webhook(url: &str, payload: &str) -> Result<()> {
+    pdf_eval::webhook::post_json(url, payload).context("failed to post metrics to the webhook")
+}
+
+#[cfg(not(feature = "webhook"))]
+fn post_webhook(_url: &str, _payload: &str) -> Result<()> {
+    bail!("--webhook requires pdf_eval to be built with the `webhook` feature")
+}
+
+fn load_predictions_from(
+    path: &Path,
+    validation: ValidationMode,
+    args: &Args,
+) -> Result<BTreeMap<String, Document>> {
+    let retry = ReadRetry::new(args.read_retries);
+    let mut predictions = load_predictions_retrying(path, validation, args.input_format, &retry)
+        .with_context(|| format!("failed to load predictions from {}", path.display()))?;
+    if let Some(value_key) = &args.value_key {
+        unwrap_field_values(&mut predictions, value_key);
+    }
+    Ok(predictions)
+}
+
+/// The `--template-file` template, or the embedded one.
+fn load_template(args: &Args) -> Result<serde_json::Value> {
+    let Some(path) = &args.template_file else {
+        return Ok(template::extraction_template().clone());
+    };
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read template {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("template {} is not valid JSON", path.display()))
+}
+
+fn build_options(args: &Args) -> Result<EvaluationOptions> {
+    let annotations = FieldAnnotations::from_template(&load_template(args)?);
+    let synonyms = match &args.synonyms {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read synonyms {}", path.display()))?;
+            let value: serde_json::Value = serde_json::from_str(&raw)
+                .with_context(|| format!("synonyms {} is not valid JSON", path.display()))?;
+            SynonymMap::from_json(&value)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("invalid synonyms in {}", path.display()))?
+        }
+        None => SynonymMap::default(),
+    };
+    let fx_rates = match &args.fx_rates {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read exchange rates {}", path.display()))?;
+            let value: serde_json::Value = serde_json::from_str(&raw)
+                .with_context(|| format!("exchange rates {} is not valid JSON", path.display()))?;
+            Some(
+                FxRates::from_json(&value)
+                    .map_err(anyhow::Error::msg)
+                    .with_context(|| format!("invalid exchange rates in {}", path.display()))?,
+            )
+        }
+        None => None,
+    };
+    let derived_tolerances = match &args.error_baseline {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read error baseline {}", path.display()))?;
+            let value: serde_json::Value = serde_json::from_str(&raw)
+                .with_context(|| format!("error baseline {} is not valid JSON", path.display()))?;
+            ErrorBaseline::from_json(&value)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("invalid error baseline in {}", path.display()))?
+                .tolerances(args.error_percentile)
+        }
+        None => BTreeMap::new(),
+    };
+    let mut code_tables = BTreeMap::new();
+    for (field, path) in &args.code_table {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read code table {}", path.display()))?;
+        let value: serde_json::Value = serde_json::from_str(&raw)
+            .with_context(|| format!("code table {} is not valid JSON", path.display()))?;
+        let table = CodeTable::from_json(&value)
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("invalid code table in {}", path.display()))?;
+        code_tables.insert(field.clone(), table);
+    }
+
+    let stop_words = match args.stop_words.as_deref() {
+        Some("english") => StopWords::english(),
+        Some(path) => StopWords::from_text(
+            &std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read stop words {path}"))?,
+        ),
+        None => StopWords::default(),
+    };
+
+    Ok(EvaluationOptions {
+        component_weighting: args.component_weighting,
+        coverage_mode: args.coverage_mode,
+        score_formula: args.score_formula.clone(),
+        text_metric: args.text_metric,
+        compare_text_metrics: args.compare_text_metrics.clone(),
+        path_separator: args.path_separator.clone(),
+        locale: args.locale,
+        numeric_tolerance: args.numeric_tolerance,
+        numeric_asymmetry: args.numeric_asymmetry,
+        max_field_length: (args.max_field_length > 0).then_some(args.max_field_length),
+        timestamp_tolerance: args.timestamp_tolerance,
+        annotations,
+        intersection_only: args.intersection_only,
+        max_documents: args.max_documents,
+        depth_decay: args.depth_decay,
+        synonyms,
+        optional_missing_credit: args.optional_missing_credit,
+        strict_numbers: args.strict_numbers,
+        null_numeric_as_zero: args.null_numeric_as_zero,
+        text_length_weighting: args.text_length_weighting,
+        threads: args.parallel,
+        geo_tolerance_km: args.geo_tolerance_km,
+        fx_rates,
+        remaps: args.remap.clone(),
+        outlier_factor: args.outlier_factor,
+        stop_words,
+        parse_json_strings: args.parse_json_strings,
+        trim_null_array_elements: args.trim_null_array_elements,
+        embedded_numbers: args.embedded_numbers,
+        field_minimums: args.field_min.clone(),
+        ignore_value: args.ignore_value.clone(),
+        include_raw_values: args.include_raw_values || args.diff_only,
+        near_miss_band: args.near_miss_band,
+        aliases: args.alias.clone(),
+        profile: args.profile,
+        code_tables,
+        derived_tolerances,
+        field_decisions: BTreeMap::new(),
+        sequence_paths: args
+            .sequence
+            .iter()
+            .map(|path| field_pattern(path))
+            .collect(),
+        field_buckets: if args.only_numeric {
+            FieldBuckets::Numeric
+        } else if args.only_text {
+            FieldBuckets::Text
+        } else {
+            FieldBuckets::All
+        },
+    })
+}
+
+/// Prints `value` as JSON through [`emit_text`], with empty collections as `null` under
+/// `--empty-as-null`.
+fn emit_json<T: Serialize>(value: &T, args: &Args) -> Result<()> {
+    if args.format == OutputFormat::Msgpack {
+        let mut output = serde_json::to_value(value)?;
+        if args.redact {
+            redact_values(&mut output);
+        }
+        if args.empty_as_null {
+            empty_collections_as_null(&mut output);
+        }
+        return emit_bytes(&to_msgpack(&output), args);
+    }
+    if args.empty_as_null {
+        let mut output = serde_json::to_value(value)?;
+        empty_collections_as_null(&mut output);
+        return emit_json_value(&output, args);
+    }
+    emit_json_value(value, args)
+}
+
+fn emit_json_value<T: Serialize>(value: &T, args: &Args) -> Result<()> {
+    let payload = if args.compact {
+        to_output_line(value, args.redact)?
+    } else {
+        to_output_json(value, args.redact)?
+    };
+    emit_text(&(payload + "\n"), args)
+}
+
+/// Writes binary `payload` to stdout and, with `--output`, also to that file.
+fn emit_bytes(payload: &[u8], args: &Args) -> Result<()> {
+    if let Some(path) = &args.output {
+        std::fs::write(path, payload)
             .with_context(|| format!("failed to write {}", path.display()))?;
     }
-    println!("{}", payload);
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(payload)?;
+    stdout.flush()?;
     Ok(())
 }
+
+/// Prints `payload` and, with `--output`, also writes it to that file. With `--snapshot`,
+/// the payload is then checked against (or, with `--update-snapshots`, saved as) the snapshot.
+fn emit_text(payload: &str, args: &Args) -> Result<()> {
+    if let Some(path) = &args.output {
+        std::fs::write(path, payload)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    print!("{payload}");
+    let Some(path) = &args.snapshot else {
+        return Ok(());
+    };
+    match check_snapshot(path, payload, args.update_snapshots)
+        .with_context(|| format!("failed to check snapshot {}", path.display()))?
+    {
+        SnapshotOutcome::Matched | SnapshotOutcome::Updated => Ok(()),
+        SnapshotOutcome::Missing => bail!(
+            "snapshot {} does not exist; rerun with --update-snapshots to create it",
+            path.display()
+        ),
+        SnapshotOutcome::Mismatched(diff) => {
+            eprint!(
+                "--- {} (snapshot)\n+++ current output\n{diff}",
+                path.display()
+            );
+            bail!(
+                "output differs from snapshot {}; rerun with --update-snapshots to accept it",
+                path.display()
+            )
+        }
+    }
+}
@@ -0,0 +1,154 @@
+/// HTML elements that sit inside a word's flow; other tags separate words.
+const INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "dfn", "em", "font", "i", "kbd",
+    "mark", "q", "s", "samp", "small", "span", "strike", "strong", "sub", "sup", "time", "u",
+    "var",
+];
+
+/// The text content of an HTML fragment: tags and comments are dropped (block-level tags
+/// such as `<p>` or `<br>` leave a space), common entities are decoded and whitespace is
+/// collapsed. `"<b>Total</b>"` becomes `"Total"`.
+pub fn strip_html(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        plain.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let end = if tag.starts_with("<!--") {
+            tag.find("-->").map(|end| end + 3)
+        } else {
+            tag.find('>').map(|end| end + 1)
+        };
+        let Some(end) = end else {
+            plain.push_str(tag);
+            rest = "";
+            break;
+        };
+        if !is_inline_tag(&tag[..end]) {
+            plain.push(' ');
+        }
+        rest = &tag[end..];
+    }
+    plain.push_str(rest);
+    collapse_whitespace(&decode_entities(&plain))
+}
+
+fn is_inline_tag(tag: &str) -> bool {
+    let name: String = tag
+        .trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    INLINE_TAGS.contains(&name.as_str())
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let entity = candidate
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| entity_char(&candidate[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn entity_char(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// The text of a Markdown fragment: heading, quote and list markers at the start of a line,
+/// emphasis (`*`, `**`, `~~`, and `_` at word edges), inline code backticks and link or
+/// image targets are dropped, keeping the link text; whitespace is collapsed.
+/// `"**Total** due"` becomes `"Total due"`.
+pub fn strip_markdown(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(strip_markdown_line).collect();
+    collapse_whitespace(&lines.join(" "))
+}
+
+fn strip_markdown_line(line: &str) -> String {
+    let mut line = line.trim_start();
+    line = line.trim_start_matches('>').trim_start();
+    line = line.trim_start_matches('#').trim_start();
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(marker) {
+            line = item;
+        }
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(item) = line[digits..].strip_prefix(". ") {
+            line = item;
+        }
+    }
+
+    let chars: Vec<char> = line.chars().collect();
+    let mut plain = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '!' if chars.get(i + 1) == Some(&'[') => i += 1,
+            ']' if chars.get(i + 1) == Some(&'(') => {
+                match chars[i + 2..].iter().position(|&c| c == ')') {
+                    Some(close) => i += close + 3,
+                    None => {
+                        plain.push(']');
+                        i += 1;
+                    }
+                }
+            }
+            '[' | '*' | '`' => i += 1,
+            '~' if chars.get(i + 1) == Some(&'~') => i += 2,
+            '_' => {
+                let before = i.checked_sub(1).map(|j| chars[j]);
+                let after = chars.get(i + 1).copied();
+                let inside_word = before.is_some_and(char::is_alphanumeric)
+                    && after.is_some_and(char::is_alphanumeric);
+                if inside_word {
+                    plain.push('_');
+                }
+                i += 1;
+            }
+            c => {
+                plain.push(c);
+                i += 1;
+            }
+        }
+    }
+    plain
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,8 +1,32 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::BTreeMap;
 
+use crate::analysis::{
+    check_field_minimums, detect_systematic_biases, worst_fields, FieldMinimumCheck,
+    NumericOutlier, SystematicBias, WorstFields,
+};
+use crate::baseline::BaselineComparison;
+use crate::bootstrap::ConfidenceInterval;
+use crate::changes::ChangeReport;
+use crate::edits::EditOperations;
+use crate::error::EvaluationError;
+use crate::evaluator::{combine_components, ratio_or_one, SimilarityTotals};
+use crate::formula::ScoreFormula;
+use crate::groups::GroupMetrics;
+use crate::numbers::NumericTolerance;
+use crate::options::{ComponentWeighting, FieldBuckets, FieldMinimum, TextLengthWeighting};
+use crate::order::OrderViolation;
+use crate::profile::PhaseProfile;
+use crate::sequences::SequenceOperations;
+use crate::window::DecayedScore;
+
 #[derive(Debug, Serialize)]
 pub struct EvaluationMetrics {
+    /// Id of the run that produced these metrics, set by the CLI (or `--run-id`) to correlate
+    /// them with the run's logs and artifacts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
     pub num_documents: u32,
     pub num_fields: u32,
     pub document_coverage: f64,
@@ -10,12 +34,197 @@ pub struct EvaluationMetrics {
     pub text_field_similarity: f64,
     pub structural_completeness: f64,
     pub overall_score: f64,
+    /// Bootstrap interval around `overall_score`, with `--bootstrap`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_interval: Option<ConfidenceInterval>,
+    /// Recency-weighted overall score, with `--half-life`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decayed_score: Option<DecayedScore>,
+    /// The only field kind similarity-scored, with `--only-numeric` or `--only-text`.
+    #[serde(skip_serializing_if = "FieldBuckets::is_all")]
+    pub evaluated_buckets: FieldBuckets,
     pub missing_documents: Vec<String>,
     pub extra_documents: Vec<String>,
     pub missing_field_count: u32,
     pub extra_field_count: u32,
     pub missing_fields: BTreeMap<String, Vec<String>>,
     pub extra_fields: BTreeMap<String, Vec<String>>,
+    /// Text fields cut to `--max-field-length` characters before scoring.
+    pub truncated_field_count: u32,
+    /// Numeric fields scoring within the `--near-miss-band`: close, but not exact.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub near_miss_numeric_count: Option<u32>,
+    /// Weighting applied to text fields in `text_field_similarity`, when not `none`.
+    #[serde(skip_serializing_if = "is_unweighted")]
+    pub text_length_weighting: TextLengthWeighting,
+    /// Binary fields per document whose expected or predicted value is not valid base64.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub malformed_binary_fields: BTreeMap<String, Vec<String>>,
+    /// `x-coordinate` fields per document whose expected or predicted value is not a valid
+    /// `{"lat": .., "lon": ..}` object.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub malformed_coordinate_fields: BTreeMap<String, Vec<String>>,
+    /// `x-percent` fields per document where a percentage string was read as a fraction.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub normalized_percent_fields: BTreeMap<String, Vec<String>>,
+    /// Predicted text fields per document that look mis-encoded (mojibake such as `Ã©`
+    /// for `é`). A warning only: the fields are scored as usual.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mojibake_fields: BTreeMap<String, Vec<String>>,
+    /// Element insertions, deletions and substitutions per sequence field pattern, summed
+    /// over documents.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub sequence_operations: BTreeMap<String, SequenceOperations>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub systematic_biases: Vec<SystematicBias>,
+    /// Per-document numeric errors of at least `--outlier-factor`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub numeric_outliers: Vec<NumericOutlier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<ChangeReport>,
+    /// Headline metric deltas against the latest run in `--baseline-dir`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineComparison>,
+    /// Time spent per scoring phase, with `--profile`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<PhaseProfile>,
+    /// Critical fields checked against `--field-min` / `x-min-score`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_minimums: Vec<FieldMinimumCheck>,
+    /// Fields with the lowest mean score, with `--worst-fields`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worst_fields: Option<WorstFields>,
+    /// Character edits across text fields, with `--edit-operations`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_operations: Option<EditOperations>,
+    /// Inversions in the arrays named by `--check-order`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_violations: Option<Vec<OrderViolation>>,
+    /// Template-required fields absent from each prediction, with `--check-required`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_required_fields: Option<BTreeMap<String, Vec<String>>>,
+    /// Metrics per value of the `--group-by` field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<BTreeMap<String, GroupMetrics>>,
+    /// Documents left out of scoring by `--intersection-only`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excluded_documents: Option<ExcludedDocuments>,
+    /// How many ground-truth documents `--max-documents` kept, out of how many.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_cap: Option<DocumentCap>,
+    #[serde(skip)]
+    pub documents: Vec<DocumentScore>,
+    #[serde(skip)]
+    pub(crate) totals: AggregateTotals,
+}
+
+/// Unrounded sums behind the aggregate metrics, plus the rule that combined them, so that
+/// metrics from separate document shards can be merged exactly.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AggregateTotals {
+    pub(crate) coverage_sum: f64,
+    pub(crate) matched_fields: u32,
+    pub(crate) similarity: SimilarityTotals,
+    pub(crate) numeric_pairs: BTreeMap<String, Vec<(f64, f64)>>,
+    pub(crate) component_weighting: ComponentWeighting,
+    pub(crate) score_formula: Option<ScoreFormula>,
+}
+
+/// Scores for a single ground-truth document, using the same components as the aggregate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentScore {
+    pub document_id: String,
+    pub has_prediction: bool,
+    pub num_fields: u32,
+    pub matched_fields: u32,
+    pub numeric_field_similarity: f64,
+    pub text_field_similarity: f64,
+    pub structural_completeness: f64,
+    pub overall_score: f64,
+    pub missing_fields: Vec<String>,
+    pub extra_fields: Vec<String>,
+    /// Ground-truth paths matched through `--remap`, with the predicted path they came from.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remapped_fields: BTreeMap<String, String>,
+    /// Predicted paths whose keys were read through an alias, with the path as written.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliased_fields: BTreeMap<String, String>,
+    pub field_scores: Vec<FieldScore>,
+}
+
+/// The `--max-documents` cap of a run, so a capped run is not mistaken for a full one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DocumentCap {
+    pub max_documents: u32,
+    /// Ground-truth documents before the cap.
+    pub ground_truth_documents: u32,
+}
+
+/// Counts of documents that only one side contained.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ExcludedDocuments {
+    /// Ground-truth documents without a prediction.
+    pub missing_documents: u32,
+    /// Predicted documents without a ground-truth counterpart.
+    pub extra_documents: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Numeric,
+    Text,
+}
+
+/// Score of a single ground-truth field within a document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldScore {
+    pub path: String,
+    pub kind: FieldKind,
+    pub score: f64,
+    /// Numeric tolerance that applied to this field, if any. Not read back from checkpoints,
+    /// which restore it from the options instead.
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub tolerance: Option<NumericTolerance>,
+    /// Score of each `--compare-text-metrics` metric on this text field, by metric name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub text_metric_scores: BTreeMap<String, f64>,
+    /// The compared metric that scored this field highest; the earliest listed wins ties.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_text_metric: Option<String>,
+    /// The expected value as compared, with `--include-raw-values`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+    /// The predicted value as compared, with `--include-raw-values`; left out when the
+    /// prediction has no value for the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predicted: Option<Value>,
+}
+
+/// The mismatched fields of every document, with `--diff-only`.
+#[derive(Debug, Serialize)]
+pub struct FieldDiffs<'a> {
+    pub field_diffs: Vec<DocumentDiff<'a>>,
+}
+
+/// The fields of one document that did not score 1.0.
+#[derive(Debug, Serialize)]
+pub struct DocumentDiff<'a> {
+    pub document_id: &'a str,
+    pub has_prediction: bool,
+    pub fields: Vec<FieldDiff<'a>>,
+}
+
+/// A mismatched field: its values as compared and the score they got.
+#[derive(Debug, Serialize)]
+pub struct FieldDiff<'a> {
+    pub path: &'a str,
+    pub kind: FieldKind,
+    pub score: f64,
+    pub expected: Option<&'a Value>,
+    /// Left out when the prediction has no value for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicted: Option<&'a Value>,
 }
 
 impl EvaluationMetrics {
@@ -36,6 +245,7 @@ impl EvaluationMetrics {
         extra_fields: BTreeMap<String, Vec<String>>,
     ) -> Self {
         Self {
+            run_id: None,
             num_documents,
             num_fields,
             document_coverage: round(document_coverage),
@@ -43,16 +253,318 @@ impl EvaluationMetrics {
             text_field_similarity: round(text_field_similarity),
             structural_completeness: round(structural_completeness),
             overall_score: round(overall_score),
+            confidence_interval: None,
+            decayed_score: None,
+            evaluated_buckets: FieldBuckets::All,
             missing_documents,
             extra_documents,
             missing_field_count,
             extra_field_count,
             missing_fields,
             extra_fields,
+            truncated_field_count: 0,
+            near_miss_numeric_count: None,
+            text_length_weighting: TextLengthWeighting::None,
+            malformed_binary_fields: BTreeMap::new(),
+            malformed_coordinate_fields: BTreeMap::new(),
+            normalized_percent_fields: BTreeMap::new(),
+            mojibake_fields: BTreeMap::new(),
+            sequence_operations: BTreeMap::new(),
+            systematic_biases: Vec::new(),
+            numeric_outliers: Vec::new(),
+            changes: None,
+            baseline: None,
+            profile: None,
+            field_minimums: Vec::new(),
+            worst_fields: None,
+            edit_operations: None,
+            order_violations: None,
+            missing_required_fields: None,
+            groups: None,
+            excluded_documents: None,
+            document_cap: None,
+            documents: Vec::new(),
+            totals: AggregateTotals::default(),
         }
     }
+
+    /// Combines metrics scored over disjoint document shards into the metrics of a single
+    /// run over all of them.
+    ///
+    /// Averages are re-derived from the unrounded sums of both sides rather than by averaging
+    /// averages, so both sides must come from [`evaluate_predictions_with_options`] with the
+    /// same options; the overall score is recombined with the options of `self`, which fails
+    /// if a score formula divides by zero for the merged components.
+    ///
+    /// [`evaluate_predictions_with_options`]: crate::evaluator::evaluate_predictions_with_options
+    pub fn merge(self, other: EvaluationMetrics) -> Result<EvaluationMetrics, EvaluationError> {
+        let mut totals = self.totals;
+        totals.coverage_sum += other.totals.coverage_sum;
+        totals.matched_fields += other.totals.matched_fields;
+        totals.similarity.merge(&other.totals.similarity);
+        for (field, pairs) in other.totals.numeric_pairs {
+            totals.numeric_pairs.entry(field).or_default().extend(pairs);
+        }
+
+        let num_documents = self.num_documents + other.num_documents;
+        let num_fields = self.num_fields + other.num_fields;
+        let coverage = if num_documents == 0 {
+            0.0
+        } else {
+            totals.coverage_sum / f64::from(num_documents)
+        };
+        let structural_completeness = ratio_or_one(totals.matched_fields, num_fields);
+        let overall_score = combine_components(
+            totals.component_weighting,
+            totals.score_formula.as_ref(),
+            coverage,
+            structural_completeness,
+            &totals.similarity,
+        )?;
+
+        let mut missing_fields = self.missing_fields;
+        missing_fields.extend(other.missing_fields);
+        let mut extra_fields = self.extra_fields;
+        extra_fields.extend(other.extra_fields);
+
+        let mut merged = Self::new(
+            num_documents,
+            num_fields,
+            coverage,
+            totals.similarity.numeric_similarity(),
+            totals.similarity.text_similarity(),
+            structural_completeness,
+            overall_score,
+            sorted_union(self.missing_documents, other.missing_documents),
+            sorted_union(self.extra_documents, other.extra_documents),
+            self.missing_field_count + other.missing_field_count,
+            self.extra_field_count + other.extra_field_count,
+            missing_fields,
+            extra_fields,
+        );
+        merged.truncated_field_count = self.truncated_field_count + other.truncated_field_count;
+        merged.near_miss_numeric_count =
+            match (self.near_miss_numeric_count, other.near_miss_numeric_count) {
+                (Some(count), Some(other)) => Some(count + other),
+                (count, other) => count.or(other),
+            };
+        merged.text_length_weighting = self.text_length_weighting;
+        merged.evaluated_buckets = self.evaluated_buckets;
+        merged.malformed_binary_fields = self.malformed_binary_fields;
+        merged
+            .malformed_binary_fields
+            .extend(other.malformed_binary_fields);
+        merged.malformed_coordinate_fields = self.malformed_coordinate_fields;
+        merged
+            .malformed_coordinate_fields
+            .extend(other.malformed_coordinate_fields);
+        merged.normalized_percent_fields = self.normalized_percent_fields;
+        merged
+            .normalized_percent_fields
+            .extend(other.normalized_percent_fields);
+        merged.mojibake_fields = self.mojibake_fields;
+        merged.mojibake_fields.extend(other.mojibake_fields);
+        merged.sequence_operations = self.sequence_operations;
+        for (field, operations) in other.sequence_operations {
+            merged
+                .sequence_operations
+                .entry(field)
+                .or_default()
+                .add(&operations);
+        }
+        merged.systematic_biases = detect_systematic_biases(&totals.numeric_pairs);
+        merged.numeric_outliers = self.numeric_outliers;
+        merged.numeric_outliers.extend(other.numeric_outliers);
+        sort_outliers(&mut merged.numeric_outliers);
+        merged.profile = match (self.profile, other.profile) {
+            (Some(profile), Some(other)) => Some(profile.merge(other).rounded()),
+            (profile, other) => profile.or(other),
+        };
+        merged.changes = match (self.changes, other.changes) {
+            (Some(changes), Some(other)) => Some(changes.merge(other)),
+            (changes, other) => changes.or(other),
+        };
+        merged.edit_operations = match (self.edit_operations, other.edit_operations) {
+            (Some(mut operations), Some(other)) => {
+                operations.add(&other);
+                Some(operations)
+            }
+            (operations, other) => operations.or(other),
+        };
+        merged.order_violations = match (self.order_violations, other.order_violations) {
+            (None, None) => None,
+            (violations, other) => {
+                let mut violations: Vec<_> =
+                    violations.into_iter().chain(other).flatten().collect();
+                violations.sort_by(|a, b| a.document_id.cmp(&b.document_id));
+                Some(violations)
+            }
+        };
+        merged.missing_required_fields =
+            match (self.missing_required_fields, other.missing_required_fields) {
+                (Some(mut fields), Some(other)) => {
+                    fields.extend(other);
+                    Some(fields)
+                }
+                (fields, other) => fields.or(other),
+            };
+        merged.groups = match (self.groups, other.groups) {
+            (Some(mut groups), Some(other)) => {
+                for (group, metrics) in other {
+                    let metrics = match groups.remove(&group) {
+                        Some(existing) => existing.merge(metrics)?,
+                        None => metrics,
+                    };
+                    groups.insert(group, metrics);
+                }
+                Some(groups)
+            }
+            (groups, other) => groups.or(other),
+        };
+        merged.excluded_documents = match (self.excluded_documents, other.excluded_documents) {
+            (None, None) => None,
+            (excluded, other) => {
+                let (excluded, other) = (excluded.unwrap_or_default(), other.unwrap_or_default());
+                Some(ExcludedDocuments {
+                    missing_documents: excluded.missing_documents + other.missing_documents,
+                    extra_documents: excluded.extra_documents + other.extra_documents,
+                })
+            }
+        };
+        // Resampled intervals do not combine across shards; bootstrap the merged documents.
+        merged.confidence_interval = None;
+        // Ages are measured from each shard's newest document; decay the merged documents.
+        merged.decayed_score = None;
+        merged.run_id = self.run_id.or(other.run_id);
+        merged.document_cap = self.document_cap.or(other.document_cap);
+        merged.documents = self.documents;
+        merged.documents.extend(other.documents);
+        merged
+            .documents
+            .sort_by(|a, b| a.document_id.cmp(&b.document_id));
+        let minimums: Vec<FieldMinimum> = self
+            .field_minimums
+            .iter()
+            .map(|check| FieldMinimum {
+                field: check.field.clone(),
+                min_score: check.min_score,
+            })
+            .collect();
+        merged.field_minimums = check_field_minimums(&merged.documents, &minimums);
+        merged.worst_fields = self
+            .worst_fields
+            .or(other.worst_fields)
+            .map(|worst| worst_fields(&merged.documents, worst.threshold, worst.limit));
+        merged.totals = totals;
+        Ok(merged)
+    }
+
+    /// One `document_id,overall_score` CSV line per document under a header, lowest score
+    /// first (ties by id) so outliers lead the list.
+    pub fn document_summary_csv(&self) -> String {
+        let mut documents: Vec<&DocumentScore> = self.documents.iter().collect();
+        documents.sort_by(|a, b| {
+            a.overall_score
+                .total_cmp(&b.overall_score)
+                .then_with(|| a.document_id.cmp(&b.document_id))
+        });
+        let mut csv = String::from("document_id,overall_score\n");
+        for document in documents {
+            csv.push_str(&csv_field(&document.document_id));
+            csv.push_str(&format!(",{:?}\n", document.overall_score));
+        }
+        csv
+    }
+
+    /// The fields scoring below 1.0 in each document, documents without any left out. The
+    /// values are those recorded under `--include-raw-values`, `null` otherwise.
+    pub fn field_diffs(&self) -> FieldDiffs<'_> {
+        let field_diffs = self
+            .documents
+            .iter()
+            .filter_map(|document| {
+                let fields: Vec<FieldDiff> = document
+                    .field_scores
+                    .iter()
+                    .filter(|field| field.score < 1.0)
+                    .map(|field| FieldDiff {
+                        path: &field.path,
+                        kind: field.kind,
+                        score: field.score,
+                        expected: field.expected.as_ref(),
+                        predicted: field.predicted.as_ref(),
+                    })
+                    .collect();
+                (!fields.is_empty()).then_some(DocumentDiff {
+                    document_id: &document.document_id,
+                    has_prediction: document.has_prediction,
+                    fields,
+                })
+            })
+            .collect();
+        FieldDiffs { field_diffs }
+    }
+
+    /// Field presence across the corpus as CSV: one row per ground-truth field path (sorted)
+    /// and one column per document, in evaluation order. A cell is `1` when the prediction
+    /// has the field, `0` when it is missing, and empty when the document's ground truth
+    /// does not have the field.
+    pub fn coverage_matrix_csv(&self) -> String {
+        let mut present: BTreeMap<&str, BTreeMap<&str, bool>> = BTreeMap::new();
+        for document in &self.documents {
+            let id = document.document_id.as_str();
+            for field in &document.field_scores {
+                present.entry(&field.path).or_default().insert(id, true);
+            }
+            for path in &document.missing_fields {
+                present.entry(path).or_default().insert(id, false);
+            }
+        }
+        let mut csv = String::from("field");
+        for document in &self.documents {
+            csv.push(',');
+            csv.push_str(&csv_field(&document.document_id));
+        }
+        csv.push('\n');
+        for (path, documents) in present {
+            csv.push_str(&csv_field(path));
+            for document in &self.documents {
+                csv.push_str(match documents.get(document.document_id.as_str()) {
+                    Some(true) => ",1",
+                    Some(false) => ",0",
+                    None => ",",
+                });
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quotes a CSV field containing a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn sort_outliers(outliers: &mut [NumericOutlier]) {
+    outliers.sort_by(|a, b| (&a.document_id, &a.field).cmp(&(&b.document_id, &b.field)));
+}
+
+fn is_unweighted(weighting: &TextLengthWeighting) -> bool {
+    *weighting == TextLengthWeighting::None
+}
+
+fn sorted_union(mut ids: Vec<String>, other: Vec<String>) -> Vec<String> {
+    ids.extend(other);
+    ids.sort();
+    ids.dedup();
+    ids
 }
 
-fn round(value: f64) -> f64 {
+pub(crate) fn round(value: f64) -> f64 {
     (value * 10_000.0).round() / 10_000.0
 }
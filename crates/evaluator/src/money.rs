@@ -0,0 +1,106 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Share of a money field's score that comes from its currency; the rest comes from the
+/// amount.
+pub const CURRENCY_WEIGHT: f64 = 0.25;
+
+/// An amount of a currency, written `{"amount": 100, "currency": "USD"}`. The code is kept
+/// trimmed and uppercased.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Money {
+    pub amount: f64,
+    pub currency: String,
+}
+
+impl Money {
+    /// Reads an object with a numeric `amount` (a JSON number or a plain numeric string) and
+    /// a non-empty `currency` code.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let amount = match value.get("amount")? {
+            Value::String(text) => text.trim().parse().ok()?,
+            amount => amount.as_f64()?,
+        };
+        let currency = value.get("currency")?.as_str()?.trim().to_uppercase();
+        (!currency.is_empty()).then_some(Self { amount, currency })
+    }
+}
+
+/// Exchange rates from `--fx-rates`: the value of one unit of each currency in a common
+/// base, e.g. `{"USD": 1.0, "EUR": 1.08}`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FxRates {
+    rates: BTreeMap<String, f64>,
+}
+
+impl FxRates {
+    /// Reads a JSON object mapping each currency code to its positive rate.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let rates = value
+            .as_object()
+            .ok_or("exchange rates must be an object of currency code to rate")?;
+        let rates = rates
+            .iter()
+            .map(|(currency, rate)| {
+                let rate = rate
+                    .as_f64()
+                    .filter(|rate| rate.is_finite() && *rate > 0.0)
+                    .ok_or_else(|| format!("rate of '{currency}' must be a positive number"))?;
+                Ok((currency.trim().to_uppercase(), rate))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rates })
+    }
+
+    fn rate(&self, currency: &str) -> Option<f64> {
+        self.rates.get(currency).copied()
+    }
+}
+
+/// Scores two money values: the amounts, compared by `amount_score`, count for
+/// `1 - CURRENCY_WEIGHT` and the currencies for [`CURRENCY_WEIGHT`]. The currencies match
+/// when their codes are equal or, with `rates`, when both have a rate; the amounts are then
+/// compared in the common base, so `108 USD` matches `100 EUR` at `EUR = 1.08`. A right
+/// amount in the wrong currency therefore never scores 1.0. Returns `None` when either side
+/// is not a money value.
+pub fn money_similarity(
+    expected: &Value,
+    predicted: &Value,
+    rates: Option<&FxRates>,
+    amount_score: impl Fn(f64, f64) -> f64,
+) -> Option<f64> {
+    let (expected, predicted) = (Money::from_json(expected)?, Money::from_json(predicted)?);
+    let (amount, currency) = match rates.and_then(|rates| {
+        Some((
+            rates.rate(&expected.currency)?,
+            rates.rate(&predicted.currency)?,
+        ))
+    }) {
+        Some((expected_rate, predicted_rate)) => (
+            amount_score(
+                expected.amount * expected_rate,
+                predicted.amount * predicted_rate,
+            ),
+            1.0,
+        ),
+        None => (
+            amount_score(expected.amount, predicted.amount),
+            if expected.currency == predicted.currency {
+                1.0
+            } else {
+                0.0
+            },
+        ),
+    };
+    Some((1.0 - CURRENCY_WEIGHT) * amount + CURRENCY_WEIGHT * currency)
+}
+
+/// The amount of a money value in the common base when its currency has a rate, as written
+/// otherwise.
+pub fn base_amount(value: &Value, rates: Option<&FxRates>) -> Option<f64> {
+    let money = Money::from_json(value)?;
+    let rate = rates
+        .and_then(|rates| rates.rate(&money.currency))
+        .unwrap_or(1.0);
+    Some(money.amount * rate)
+}
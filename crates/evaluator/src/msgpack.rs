@@ -0,0 +1,292 @@
+use clap::ValueEnum;
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+/// Encoding of the metrics written to stdout and `--output`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Pretty-printed (or, with `--compact`, single-line) JSON.
+    #[default]
+    Json,
+    /// MessagePack: the same document as the JSON form, in a compact binary encoding.
+    Msgpack,
+}
+
+#[derive(Debug, Error)]
+pub enum MsgpackError {
+    #[error("MessagePack input ended unexpectedly")]
+    UnexpectedEnd,
+    #[error("unsupported MessagePack type 0x{0:02x}")]
+    UnsupportedType(u8),
+    #[error("MessagePack map keys must be strings")]
+    NonStringKey,
+    #[error("MessagePack string is not valid UTF-8")]
+    InvalidUtf8,
+    #[error("MessagePack float {0} has no JSON representation")]
+    NonFiniteFloat(f64),
+    #[error("{0} trailing bytes after the MessagePack value")]
+    TrailingBytes(usize),
+    #[error("MessagePack arrays and maps are nested more than {MAX_DEPTH} levels deep")]
+    TooDeep,
+}
+
+/// Deepest nesting of arrays and maps the decoder accepts, the same limit serde_json applies
+/// to JSON, so corrupt input cannot overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Whether `bytes` starts like a MessagePack map, as every encoded metrics document does.
+/// JSON text never starts with these bytes, so JSON and MessagePack files can share a path.
+pub fn is_msgpack(bytes: &[u8]) -> bool {
+    matches!(bytes.first(), Some(0x80..=0x8f | 0xde | 0xdf))
+}
+
+/// Encodes a JSON document as MessagePack, using the smallest representation of each value.
+pub fn to_msgpack(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode(value, &mut out);
+    out
+}
+
+/// Decodes one MessagePack value into the JSON document [`to_msgpack`] was given.
+pub fn from_msgpack(bytes: &[u8]) -> Result<Value, MsgpackError> {
+    let mut reader = Reader {
+        bytes,
+        position: 0,
+        depth: 0,
+    };
+    let value = reader.value()?;
+    match bytes.len() - reader.position {
+        0 => Ok(value),
+        trailing => Err(MsgpackError::TrailingBytes(trailing)),
+    }
+}
+
+fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => encode_number(number, out),
+        Value::String(text) => {
+            encode_length(text.len(), [0xa0, 0xd9, 0xda, 0xdb], 31, out);
+            out.extend_from_slice(text.as_bytes());
+        }
+        Value::Array(items) => {
+            encode_length(items.len(), [0x90, 0x00, 0xdc, 0xdd], 15, out);
+            items.iter().for_each(|item| encode(item, out));
+        }
+        Value::Object(map) => {
+            encode_length(map.len(), [0x80, 0x00, 0xde, 0xdf], 15, out);
+            for (key, entry) in map {
+                encode(&Value::String(key.clone()), out);
+                encode(entry, out);
+            }
+        }
+    }
+}
+
+/// Writes a length header: the `fix` marker with the length in its low bits up to
+/// `fix_max`, then the 8-bit (when the type has one, i.e. its marker is non-zero), 16-bit
+/// and 32-bit forms.
+fn encode_length(
+    len: usize,
+    [fix, len8, len16, len32]: [u8; 4],
+    fix_max: usize,
+    out: &mut Vec<u8>,
+) {
+    if len <= fix_max {
+        out.push(fix | len as u8);
+    } else if len8 != 0 && len <= u8::MAX as usize {
+        out.extend_from_slice(&[len8, len as u8]);
+    } else if len <= u16::MAX as usize {
+        out.push(len16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(len32);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn encode_number(number: &Number, out: &mut Vec<u8>) {
+    if let Some(n) = number.as_u64() {
+        match n {
+            0..=0x7f => out.push(n as u8),
+            0x80..=0xff => out.extend_from_slice(&[0xcc, n as u8]),
+            0x100..=0xffff => {
+                out.push(0xcd);
+                out.extend_from_slice(&(n as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                out.push(0xce);
+                out.extend_from_slice(&(n as u32).to_be_bytes());
+            }
+            _ => {
+                out.push(0xcf);
+                out.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+    } else if let Some(n) = number.as_i64() {
+        // Only negative integers reach here.
+        if n >= -32 {
+            out.push(n as i8 as u8);
+        } else if n >= i8::MIN as i64 {
+            out.extend_from_slice(&[0xd0, n as i8 as u8]);
+        } else if n >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend_from_slice(&(n as i16).to_be_bytes());
+        } else if n >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend_from_slice(&(n as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    } else {
+        // Always 64-bit: a float32 would not read back as the same JSON number.
+        let n = number.as_f64().unwrap_or_default();
+        out.push(0xcb);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// Arrays and maps currently open around the value being read.
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], MsgpackError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(MsgpackError::UnexpectedEnd)?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], MsgpackError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn byte(&mut self) -> Result<u8, MsgpackError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len16(&mut self) -> Result<usize, MsgpackError> {
+        Ok(u16::from_be_bytes(self.array()?) as usize)
+    }
+
+    fn len32(&mut self) -> Result<usize, MsgpackError> {
+        Ok(u32::from_be_bytes(self.array()?) as usize)
+    }
+
+    fn value(&mut self) -> Result<Value, MsgpackError> {
+        let marker = self.byte()?;
+        match marker {
+            0x00..=0x7f => Ok(Value::from(marker)),
+            0xe0..=0xff => Ok(Value::from(marker as i8)),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize),
+            0x90..=0x9f => self.sequence((marker & 0x0f) as usize),
+            0xa0..=0xbf => self.string((marker & 0x1f) as usize),
+            0xc0 => Ok(Value::Null),
+            0xc2 => Ok(Value::Bool(false)),
+            0xc3 => Ok(Value::Bool(true)),
+            0xca => float(f32::from_be_bytes(self.array()?) as f64),
+            0xcb => float(f64::from_be_bytes(self.array()?)),
+            0xcc => Ok(Value::from(self.byte()?)),
+            0xcd => Ok(Value::from(u16::from_be_bytes(self.array()?))),
+            0xce => Ok(Value::from(u32::from_be_bytes(self.array()?))),
+            0xcf => Ok(Value::from(u64::from_be_bytes(self.array()?))),
+            0xd0 => Ok(Value::from(self.byte()? as i8)),
+            0xd1 => Ok(Value::from(i16::from_be_bytes(self.array()?))),
+            0xd2 => Ok(Value::from(i32::from_be_bytes(self.array()?))),
+            0xd3 => Ok(Value::from(i64::from_be_bytes(self.array()?))),
+            0xd9 => {
+                let len = self.byte()? as usize;
+                self.string(len)
+            }
+            0xda => {
+                let len = self.len16()?;
+                self.string(len)
+            }
+            0xdb => {
+                let len = self.len32()?;
+                self.string(len)
+            }
+            0xdc => {
+                let len = self.len16()?;
+                self.sequence(len)
+            }
+            0xdd => {
+                let len = self.len32()?;
+                self.sequence(len)
+            }
+            0xde => {
+                let len = self.len16()?;
+                self.map(len)
+            }
+            0xdf => {
+                let len = self.len32()?;
+                self.map(len)
+            }
+            other => Err(MsgpackError::UnsupportedType(other)),
+        }
+    }
+
+    fn string(&mut self, len: usize) -> Result<Value, MsgpackError> {
+        let bytes = self.take(len)?;
+        let text = std::str::from_utf8(bytes).map_err(|_| MsgpackError::InvalidUtf8)?;
+        Ok(Value::String(text.to_string()))
+    }
+
+    /// Reads the contents of an array or map with `read`, one level deeper.
+    fn nested(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<Value, MsgpackError>,
+    ) -> Result<Value, MsgpackError> {
+        if self.depth == MAX_DEPTH {
+            return Err(MsgpackError::TooDeep);
+        }
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn sequence(&mut self, len: usize) -> Result<Value, MsgpackError> {
+        self.nested(|reader| {
+            // Each element takes at least one byte, which bounds allocations on corrupt input.
+            let mut items = Vec::with_capacity(len.min(reader.bytes.len() - reader.position));
+            for _ in 0..len {
+                items.push(reader.value()?);
+            }
+            Ok(Value::Array(items))
+        })
+    }
+
+    fn map(&mut self, len: usize) -> Result<Value, MsgpackError> {
+        self.nested(|reader| {
+            let mut map = Map::new();
+            for _ in 0..len {
+                let Value::String(key) = reader.value()? else {
+                    return Err(MsgpackError::NonStringKey);
+                };
+                let entry = reader.value()?;
+                map.insert(key, entry);
+            }
+            Ok(Value::Object(map))
+        })
+    }
+}
+
+fn float(n: f64) -> Result<Value, MsgpackError> {
+    Number::from_f64(n)
+        .map(Value::Number)
+        .ok_or(MsgpackError::NonFiniteFloat(n))
+}
@@ -0,0 +1,275 @@
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// Number formatting conventions used to read numbers that were emitted as strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NumberLocale {
+    /// Period decimals with comma thousands separators, e.g. `1,234.56`.
+    En,
+    /// Comma decimals with period thousands separators, e.g. `1.234,56`.
+    Eu,
+}
+
+impl NumberLocale {
+    fn separators(self) -> (char, char) {
+        match self {
+            NumberLocale::En => ('.', ','),
+            NumberLocale::Eu => (',', '.'),
+        }
+    }
+}
+
+/// Parses a plain number written with `locale` conventions. Spaces, non-breaking spaces and
+/// apostrophes are accepted as additional thousands separators.
+pub fn parse_localized(value: &str, locale: NumberLocale) -> Option<f64> {
+    let (decimal, thousands) = locale.separators();
+    let mut normalized = String::with_capacity(value.len());
+    for c in value.trim().chars() {
+        match c {
+            c if c == thousands => {}
+            ' ' | '\u{a0}' | '\u{202f}' | '\'' => {}
+            c if c == decimal => normalized.push('.'),
+            '0'..='9' | '+' | '-' => normalized.push(c),
+            _ => return None,
+        }
+    }
+    if !normalized.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    normalized.parse().ok()
+}
+
+/// Parses a number written in scientific notation, such as `1.2e3` or `-4E-2`. Strings
+/// without an exponent are left to [`parse_localized`], so ids like `"1002"` stay text, and
+/// values out of `f64` range such as `1e400` are rejected.
+pub fn parse_scientific(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (mantissa, exponent) = value.split_once(['e', 'E'])?;
+    let mantissa = mantissa.strip_prefix(['+', '-']).unwrap_or(mantissa);
+    let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if whole.len() + fraction.len() == 0
+        || !digits(whole)
+        || !digits(fraction)
+        || exponent.is_empty()
+        || !digits(exponent)
+    {
+        return None;
+    }
+    value.parse().ok().filter(|number: &f64| number.is_finite())
+}
+
+/// Every number written in `text`, in order: `2 x 500ml` gives `[2, 500]`. Without a locale
+/// only `.` is read as a decimal point; with one, both of its separators are read as it
+/// defines them. Signs are not read, so `10-12` gives `[10, 12]`.
+pub fn extract_numbers(text: &str, locale: Option<NumberLocale>) -> Vec<f64> {
+    split_numbers(text, locale).0
+}
+
+/// Placeholder [`split_numbers`] leaves where it took a number out of a text.
+pub const NUMBER_PLACEHOLDER: char = '#';
+
+/// Splits `text` into the numbers written in it, read as [`extract_numbers`] reads them, and
+/// the text around them, with each number replaced by [`NUMBER_PLACEHOLDER`]:
+/// `"up 12.5% in Q3"` gives `[12.5, 3]` and `"up #% in Q#"`.
+pub fn split_numbers(text: &str, locale: Option<NumberLocale>) -> (Vec<f64>, String) {
+    let is_separator = |c: char| match locale {
+        Some(locale) => {
+            let (decimal, thousands) = locale.separators();
+            c == decimal || c == thousands
+        }
+        None => c == '.',
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut numbers = Vec::new();
+    let mut rest = String::with_capacity(text.len());
+    let mut index = 0;
+    while index < chars.len() {
+        if !chars[index].is_ascii_digit() {
+            rest.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < chars.len()
+            && (chars[index].is_ascii_digit()
+                || is_separator(chars[index])
+                    && chars.get(index + 1).is_some_and(char::is_ascii_digit))
+        {
+            index += 1;
+        }
+        let token: String = chars[start..index].iter().collect();
+        let number = match locale {
+            Some(locale) => parse_localized(&token, locale),
+            None => token.parse().ok(),
+        };
+        match number {
+            Some(number) => {
+                numbers.push(number);
+                rest.push(NUMBER_PLACEHOLDER);
+            }
+            None => rest.push_str(&token),
+        }
+    }
+    (numbers, rest)
+}
+
+/// Parses a percentage such as `20%` or `12,5 %` into a fraction (`0.2`, `0.125`). The number
+/// follows `locale` conventions when one is given.
+pub fn parse_percent(value: &str, locale: Option<NumberLocale>) -> Option<f64> {
+    let amount = value.trim().strip_suffix('%')?;
+    let amount = match locale {
+        Some(locale) => parse_localized(amount, locale)?,
+        None => amount.trim().parse().ok()?,
+    };
+    Some(amount / 100.0)
+}
+
+/// How far a predicted number may be from the expected one and still score 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumericTolerance {
+    /// Maximum absolute difference, written as a plain number (`0.01`).
+    Absolute(f64),
+    /// Maximum difference as a fraction of the expected value, written as a percentage (`5%`).
+    Relative(f64),
+}
+
+impl NumericTolerance {
+    pub fn accepts(&self, expected: f64, predicted: f64) -> bool {
+        self.accepts_difference(expected, (expected - predicted).abs())
+    }
+
+    /// Like [`NumericTolerance::accepts`], for an absolute difference computed by the caller.
+    pub fn accepts_difference(&self, expected: f64, difference: f64) -> bool {
+        match *self {
+            NumericTolerance::Absolute(limit) => difference <= limit,
+            NumericTolerance::Relative(fraction) => difference <= expected.abs() * fraction,
+        }
+    }
+
+    /// Reads a tolerance from a template annotation, which may be a number or a string.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(number) => number.as_f64().map(NumericTolerance::Absolute),
+            Value::String(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for NumericTolerance {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (amount, relative) = match value.strip_suffix('%') {
+            Some(amount) => (amount.trim(), true),
+            None => (value, false),
+        };
+        let amount: f64 = amount
+            .parse()
+            .ok()
+            .filter(|amount: &f64| amount.is_finite() && *amount >= 0.0)
+            .ok_or_else(|| format!("invalid numeric tolerance '{value}' (use e.g. 0.01 or 5%)"))?;
+        Ok(if relative {
+            NumericTolerance::Relative(amount / 100.0)
+        } else {
+            NumericTolerance::Absolute(amount)
+        })
+    }
+}
+
+/// Slopes applied to the relative deviation of a numeric prediction, so over- and
+/// under-predictions can be penalized differently (`over=2,under=1` halves the room for
+/// over-predicting). Both slopes are 1.0 in symmetric scoring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericAsymmetry {
+    /// Slope for predictions above the expected value.
+    pub over: f64,
+    /// Slope for predictions below the expected value.
+    pub under: f64,
+}
+
+impl Default for NumericAsymmetry {
+    fn default() -> Self {
+        Self {
+            over: 1.0,
+            under: 1.0,
+        }
+    }
+}
+
+impl NumericAsymmetry {
+    /// The slope for a prediction on this side of the expected value.
+    pub fn slope(&self, expected: f64, predicted: f64) -> f64 {
+        if predicted > expected {
+            self.over
+        } else {
+            self.under
+        }
+    }
+
+    /// Reads a template annotation: `{"over": 2, "under": 1}` or the `over=2,under=1` string.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(text) => text.parse().ok(),
+            Value::Object(map) => {
+                let slope = |key: &str| match map.get(key) {
+                    Some(slope) => slope
+                        .as_f64()
+                        .filter(|slope| slope.is_finite() && *slope >= 0.0),
+                    None => Some(1.0),
+                };
+                Some(Self {
+                    over: slope("over")?,
+                    under: slope("under")?,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for NumericAsymmetry {
+    type Err = String;
+
+    /// Parses `over=SLOPE,under=SLOPE`; a side left out keeps slope 1.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid numeric asymmetry '{value}' (use e.g. over=2,under=1)");
+        let mut asymmetry = Self::default();
+        for part in value.split(',') {
+            let (side, slope) = part.split_once('=').ok_or_else(invalid)?;
+            let slope: f64 = slope
+                .trim()
+                .parse()
+                .ok()
+                .filter(|slope: &f64| slope.is_finite() && *slope >= 0.0)
+                .ok_or_else(invalid)?;
+            match side.trim() {
+                "over" => asymmetry.over = slope,
+                "under" => asymmetry.under = slope,
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(asymmetry)
+    }
+}
+
+impl fmt::Display for NumericTolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericTolerance::Absolute(limit) => write!(f, "{limit}"),
+            NumericTolerance::Relative(fraction) => write!(f, "{}%", fraction * 100.0),
+        }
+    }
+}
+
+impl Serialize for NumericTolerance {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
@@ -0,0 +1,673 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::annotations::{field_pattern, FieldAnnotations, IdentifierFormat};
+use crate::codes::CodeTable;
+use crate::formula::ScoreFormula;
+use crate::money::FxRates;
+use crate::numbers::{NumberLocale, NumericAsymmetry, NumericTolerance};
+use crate::sets::SetMode;
+use crate::stopwords::StopWords;
+use crate::synonyms::SynonymMap;
+use crate::transforms::Transform;
+use crate::units::UnitSpec;
+
+/// Text length cap applied by the CLI unless `--max-field-length` says otherwise.
+pub const DEFAULT_MAX_FIELD_LENGTH: usize = 10_000;
+
+/// Separator used by [`TextMetric::HierarchicalPath`] unless overridden.
+pub const DEFAULT_PATH_SEPARATOR: &str = "/";
+
+/// How the numeric and text similarity components share their half of the overall score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ComponentWeighting {
+    /// Numeric and text similarity each contribute a fixed quarter of the overall score.
+    #[default]
+    Equal,
+    /// Numeric and text similarity split their half of the overall score in proportion
+    /// to the number of ground-truth fields of each kind.
+    ByCount,
+}
+
+/// How each text field's score is weighted within `text_field_similarity`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextLengthWeighting {
+    /// Every text field counts the same.
+    #[default]
+    None,
+    /// Fields count in proportion to the character length of the expected value.
+    Length,
+    /// Fields count in proportion to the square root of the expected value's length.
+    Sqrt,
+}
+
+impl TextLengthWeighting {
+    fn weight(self, expected: &Value) -> f64 {
+        let length = match expected {
+            Value::String(text) => text.chars().count(),
+            other => other.to_string().chars().count(),
+        }
+        .max(1) as f64;
+        match self {
+            TextLengthWeighting::None => 1.0,
+            TextLengthWeighting::Length => length,
+            TextLengthWeighting::Sqrt => length.sqrt(),
+        }
+    }
+}
+
+/// How each ground-truth document contributes to `document_coverage`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CoverageMode {
+    /// A document counts fully as soon as any prediction exists for it.
+    #[default]
+    Binary,
+    /// A document counts for the fraction of its ground-truth fields present in the prediction.
+    Weighted,
+}
+
+/// How strictly prediction and ground-truth payloads are checked while loading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Only require object-valued `fields`; later duplicates replace earlier records.
+    #[default]
+    Lenient,
+    /// Check every record's id, uniqueness and structure, stopping at the first problem.
+    FailFast,
+    /// Check every record like [`ValidationMode::FailFast`] but report all problems at once.
+    CollectErrors,
+}
+
+/// Layout of a predictions payload.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// An array of `{"document_id": .., "fields": {..}}` records.
+    #[default]
+    Records,
+    /// An object mapping each document id to its fields: `{"doc-1": {..}, "doc-2": {..}}`.
+    ObjectMap,
+}
+
+/// Which kinds of ground-truth field are averaged into the similarities, with
+/// `--only-numeric` or `--only-text`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FieldBuckets {
+    #[default]
+    All,
+    /// Only numeric fields; the text similarity is left out.
+    Numeric,
+    /// Only text fields; the numeric similarity is left out.
+    Text,
+}
+
+impl FieldBuckets {
+    pub fn is_all(&self) -> bool {
+        *self == FieldBuckets::All
+    }
+
+    /// Whether fields of the numeric (`true`) or text (`false`) bucket are averaged.
+    pub fn scores(self, numeric: bool) -> bool {
+        match self {
+            FieldBuckets::All => true,
+            FieldBuckets::Numeric => numeric,
+            FieldBuckets::Text => !numeric,
+        }
+    }
+}
+
+/// Similarity metric applied to text fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TextMetric {
+    /// Ratcliff/Obershelp gestalt pattern matching over characters.
+    #[default]
+    Gestalt,
+    /// Fraction of leading path segments (e.g. `A/B/C`) that match, for category fields.
+    HierarchicalPath,
+    /// Parses both values as timestamps and scores the distance between the instants,
+    /// falling back to gestalt matching when either side does not parse.
+    Timestamp,
+    /// Decodes both values as base64 and scores 1.0 when the decoded bytes hash the same,
+    /// 0.0 otherwise (including malformed base64). Values are never truncated.
+    Binary,
+    /// Splits multi-line addresses into lines, pairs the lines of both sides in the order
+    /// that scores best and averages the gestalt score of each pair; lines only one side
+    /// has score 0.0.
+    Address,
+}
+
+impl TextMetric {
+    /// Parses the kebab-case name used on the command line and in template annotations.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_str(name, true).ok()
+    }
+
+    /// The kebab-case name accepted by [`TextMetric::from_name`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gestalt => "gestalt",
+            Self::HierarchicalPath => "hierarchical-path",
+            Self::Timestamp => "timestamp",
+            Self::Binary => "binary",
+            Self::Address => "address",
+        }
+    }
+}
+
+/// `--remap GT_PATH=PREDICTED_PATH`: predicted fields at `prediction`, or nested below it,
+/// are scored as if they were at `ground_truth`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRemap {
+    pub ground_truth: String,
+    pub prediction: String,
+}
+
+impl PathRemap {
+    /// The ground-truth path for a flattened predicted path covered by this remap.
+    pub fn apply(&self, path: &str) -> Option<String> {
+        let rest = path.strip_prefix(&self.prediction)?;
+        (rest.is_empty() || rest.starts_with('.')).then(|| format!("{}{rest}", self.ground_truth))
+    }
+}
+
+impl FromStr for PathRemap {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((ground_truth, prediction))
+                if !ground_truth.trim().is_empty() && !prediction.trim().is_empty() =>
+            {
+                Ok(Self {
+                    ground_truth: ground_truth.trim().to_string(),
+                    prediction: prediction.trim().to_string(),
+                })
+            }
+            _ => Err(format!(
+                "invalid remap '{value}' (use GT_PATH=PREDICTED_PATH, e.g. customer_name=customer.name)"
+            )),
+        }
+    }
+}
+
+/// `--alias ALIAS=CANONICAL`: object keys named `alias`, at any nesting level, are read as
+/// `canonical` on both sides before matching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAlias {
+    pub alias: String,
+    pub canonical: String,
+}
+
+impl FromStr for KeyAlias {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((alias, canonical))
+                if !alias.trim().is_empty() && !canonical.trim().is_empty() =>
+            {
+                Ok(Self {
+                    alias: alias.trim().to_string(),
+                    canonical: canonical.trim().to_string(),
+                })
+            }
+            _ => Err(format!(
+                "invalid alias '{value}' (use ALIAS=CANONICAL, e.g. amount=total)"
+            )),
+        }
+    }
+}
+
+/// `--field-min PATH=SCORE`: the mean score a critical field must reach across documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMinimum {
+    /// Field pattern, with array indices as `*` (e.g. `items.*.amount`).
+    pub field: String,
+    pub min_score: f64,
+}
+
+impl FromStr for FieldMinimum {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((field, min_score)) = value
+            .split_once('=')
+            .filter(|(field, _)| !field.trim().is_empty())
+        else {
+            return Err(format!(
+                "invalid field minimum '{value}' (use PATH=SCORE, e.g. total_amount=0.95)"
+            ));
+        };
+        Ok(Self {
+            field: field_pattern(field.trim()),
+            min_score: parse_score_threshold(min_score)?,
+        })
+    }
+}
+
+/// Knobs that alter how predictions are scored against the ground truth.
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationOptions {
+    pub component_weighting: ComponentWeighting,
+    pub coverage_mode: CoverageMode,
+    /// Replaces the built-in overall-score computation when set.
+    pub score_formula: Option<ScoreFormula>,
+    /// Text metric for fields without an `x-text-metric` annotation.
+    pub text_metric: TextMetric,
+    /// Metrics also computed on every text field to report which one scores it highest;
+    /// ignored with fewer than two. Field scores still use [`EvaluationOptions::text_metric_for`].
+    pub compare_text_metrics: Vec<TextMetric>,
+    /// Separator for hierarchical paths; defaults to [`DEFAULT_PATH_SEPARATOR`].
+    pub path_separator: Option<String>,
+    /// Locale used to read numeric predictions that were emitted as strings. Without one,
+    /// string predictions for numeric fields are read only in scientific notation (`"1.2e3"`),
+    /// and a scientific-notation ground-truth string is scored numerically only in fields
+    /// the template types as `number`/`integer` or when the prediction is a JSON number.
+    pub locale: Option<NumberLocale>,
+    /// Numeric difference that still counts as exact for fields without an
+    /// `x-numeric-tolerance` annotation.
+    pub numeric_tolerance: Option<NumericTolerance>,
+    /// Slopes for over- and under-predictions of fields without an `x-numeric-asymmetry`
+    /// annotation; unset scores both sides alike.
+    pub numeric_asymmetry: Option<NumericAsymmetry>,
+    /// Text values longer than this many characters are truncated before scoring.
+    pub max_field_length: Option<usize>,
+    /// Instants closer than this score proportionally; zero requires the same instant.
+    pub timestamp_tolerance: Duration,
+    /// Per-field overrides read from the extraction template.
+    pub annotations: FieldAnnotations,
+    /// Score only documents present in both the ground truth and the predictions.
+    pub intersection_only: bool,
+    /// Score only the first this many ground-truth documents, in id order, and the
+    /// predictions for them.
+    pub max_documents: Option<usize>,
+    /// Per-level multiplier on a field's weight in the similarity averages; top-level fields
+    /// weigh 1. Unset weighs every field equally.
+    pub depth_decay: Option<f64>,
+    /// Synonyms for text fields without an `x-synonyms` annotation.
+    pub synonyms: SynonymMap,
+    /// Score given to an optional template field that the prediction leaves out.
+    pub optional_missing_credit: f64,
+    /// Compare integer-valued numbers exactly as integers instead of through `f64`.
+    pub strict_numbers: bool,
+    /// Read a numeric field's `null`, on either side, as `0`.
+    pub null_numeric_as_zero: bool,
+    /// Weighting of text fields by the length of their expected value.
+    pub text_length_weighting: TextLengthWeighting,
+    /// Threads scoring documents; 0 uses every available core and 1 scores sequentially.
+    pub threads: usize,
+    /// Distance in kilometres within which `x-coordinate` fields score 1.0.
+    pub geo_tolerance_km: f64,
+    /// Exchange rates for comparing `x-money` amounts across currencies.
+    pub fx_rates: Option<FxRates>,
+    /// Predicted paths moved to ground-truth paths before matching.
+    pub remaps: Vec<PathRemap>,
+    /// Report numeric predictions off by at least this factor from the expected value.
+    pub outlier_factor: Option<f64>,
+    /// Words removed from `x-stop-words` text fields before scoring.
+    pub stop_words: StopWords,
+    /// Decode any string field holding a JSON object or array before flattening, not only
+    /// `x-json-string` fields.
+    pub parse_json_strings: bool,
+    /// Drop `null` elements from the start and end of every array before flattening, so
+    /// null padding is not scored as extra or missing fields.
+    pub trim_null_array_elements: bool,
+    /// Score numbers written inside gestalt text fields as numbers, and the text around them
+    /// as text, when both values contain one.
+    pub embedded_numbers: bool,
+    /// Minimum mean scores of critical fields, on top of the template's `x-min-score`.
+    pub field_minimums: Vec<FieldMinimum>,
+    /// Ground-truth string marking a field as not annotated; such fields, and predictions for
+    /// them, are left out of every metric.
+    pub ignore_value: Option<String>,
+    /// Attach the expected and predicted values to each entry of the per-document field
+    /// breakdown.
+    pub include_raw_values: bool,
+    /// Lowest score of the near-miss band: numeric fields scoring at least this, but below
+    /// 1.0, are counted as near misses. Unset skips the count.
+    pub near_miss_band: Option<f64>,
+    /// Key aliases, on top of the template's `x-aliases`.
+    pub aliases: Vec<KeyAlias>,
+    /// Time the scoring phases and report them under `profile`.
+    pub profile: bool,
+    /// Code-to-label tables by field pattern (array indices as `*`); codes are read as their
+    /// labels on both sides before scoring.
+    pub code_tables: BTreeMap<String, CodeTable>,
+    /// Absolute tolerances by field pattern derived from a percentile of historical errors
+    /// (see [`crate::percentiles::ErrorBaseline`]).
+    pub derived_tolerances: BTreeMap<String, NumericTolerance>,
+    /// Reviewer verdicts by document id, then field path, from `--interactive`: accepted
+    /// fields score 1 and rejected fields 0, whatever their automatic score.
+    pub field_decisions: BTreeMap<String, BTreeMap<String, bool>>,
+    /// Array field patterns (indices as `*`) scored by sequence alignment, from `--sequence`.
+    pub sequence_paths: Vec<String>,
+    /// Field kinds averaged into the similarities; fields of a skipped kind are still scored
+    /// per field and count towards coverage and structural completeness.
+    pub field_buckets: FieldBuckets,
+}
+
+impl EvaluationOptions {
+    /// `component_weighting`, except that scoring a single bucket weights by count, so the
+    /// skipped bucket (which then has no fields) gives its share to the scored one instead
+    /// of contributing a vacuous 1.0.
+    pub fn effective_component_weighting(&self) -> ComponentWeighting {
+        if self.field_buckets.is_all() {
+            self.component_weighting
+        } else {
+            ComponentWeighting::ByCount
+        }
+    }
+
+    /// Weight of a text field: its [`EvaluationOptions::field_weight`] scaled by
+    /// `text_length_weighting`.
+    pub fn text_field_weight(&self, path: &str, expected: &Value) -> f64 {
+        self.field_weight(path) * self.text_length_weighting.weight(expected)
+    }
+
+    /// Weight of a flattened field path: `depth_decay ^ (segments - 1)`.
+    pub fn field_weight(&self, path: &str) -> f64 {
+        match self.depth_decay {
+            Some(decay) => {
+                let depth = path.split('.').count().saturating_sub(1);
+                decay.powi(depth as i32)
+            }
+            None => 1.0,
+        }
+    }
+
+    pub fn text_metric_for(&self, path: &str) -> TextMetric {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.text_metric)
+            .unwrap_or(self.text_metric)
+    }
+
+    pub fn synonyms_for(&self, path: &str) -> Option<&SynonymMap> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.synonyms.as_ref())
+            .or((!self.synonyms.is_empty()).then_some(&self.synonyms))
+    }
+
+    /// The stop words to drop from this field, if it is flagged with `x-stop-words`.
+    pub fn stop_words_for(&self, path: &str) -> Option<&StopWords> {
+        (!self.stop_words.is_empty()
+            && self
+                .annotations
+                .get(path)
+                .is_some_and(|annotation| annotation.stop_words))
+        .then_some(&self.stop_words)
+    }
+
+    pub fn identifier_format_for(&self, path: &str) -> Option<IdentifierFormat> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.identifier)
+    }
+
+    /// The `--code-table` of the field, if it has one.
+    pub fn code_table_for(&self, path: &str) -> Option<&CodeTable> {
+        if self.code_tables.is_empty() {
+            return None;
+        }
+        self.code_tables.get(&field_pattern(path))
+    }
+
+    /// The `x-transform` pipeline of the field, empty when it has none.
+    pub fn transforms_for(&self, path: &str) -> &[Transform] {
+        self.annotations
+            .get(path)
+            .map_or(&[], |annotation| annotation.transforms.as_slice())
+    }
+
+    /// Every critical field: `field_minimums` plus the template's `x-min-score` fields, with
+    /// `field_minimums` taking precedence, ordered by field.
+    pub fn critical_fields(&self) -> Vec<FieldMinimum> {
+        let mut minimums: BTreeMap<String, f64> = self
+            .annotations
+            .iter()
+            .filter_map(|(pattern, annotation)| Some((pattern.clone(), annotation.min_score?)))
+            .collect();
+        for minimum in &self.field_minimums {
+            minimums.insert(minimum.field.clone(), minimum.min_score);
+        }
+        minimums
+            .into_iter()
+            .map(|(field, min_score)| FieldMinimum { field, min_score })
+            .collect()
+    }
+
+    /// Canonical key name by alias: the template's `x-aliases` (naming the annotated
+    /// property), overridden by `--alias`.
+    pub fn key_aliases(&self) -> BTreeMap<String, String> {
+        let mut aliases = BTreeMap::new();
+        for (pattern, annotation) in self.annotations.iter() {
+            let canonical = pattern.rsplit('.').next().unwrap_or(pattern);
+            for alias in &annotation.aliases {
+                aliases.insert(alias.clone(), canonical.to_string());
+            }
+        }
+        for alias in &self.aliases {
+            aliases.insert(alias.alias.clone(), alias.canonical.clone());
+        }
+        aliases
+    }
+
+    /// Whether the template marks the field with `x-json-string`.
+    pub fn is_json_string(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.json_string)
+    }
+
+    /// Whether any string field may be decoded as JSON before flattening.
+    pub fn decodes_json_strings(&self) -> bool {
+        self.parse_json_strings || self.annotations.any(|annotation| annotation.json_string)
+    }
+
+    /// Whether the template marks the field with `x-number-sequence`.
+    pub fn is_number_sequence(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.number_sequence)
+    }
+
+    /// Whether the template marks the field with `x-percent`.
+    pub fn is_percent(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.percent)
+    }
+
+    /// The ground-truth path a flattened predicted path is remapped to, if any remap covers it.
+    pub fn remapped_path(&self, path: &str) -> Option<String> {
+        self.remaps.iter().find_map(|remap| remap.apply(path))
+    }
+
+    /// Whether the template marks the field with `x-money`.
+    pub fn is_money(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.money)
+    }
+
+    /// Whether the template types the field as `number` or `integer`.
+    pub fn is_numeric_typed(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.numeric)
+    }
+
+    /// Whether the template marks the field with `x-duration`.
+    pub fn is_duration(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.duration)
+    }
+
+    /// Whether the template marks the field with `x-coordinate`.
+    pub fn is_coordinate(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.coordinate)
+    }
+
+    /// The template's `x-unit` spec, if the field holds a number with a unit.
+    pub fn unit_spec_for(&self, path: &str) -> Option<&UnitSpec> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.unit.as_ref())
+    }
+
+    /// How the template's `x-set` scores the field, if it is a set.
+    pub fn set_mode_for(&self, path: &str) -> Option<SetMode> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.set)
+    }
+
+    /// Whether the field is an ordered array scored by sequence alignment, through the
+    /// template's `x-sequence` or `--sequence`.
+    pub fn is_sequence(&self, path: &str) -> bool {
+        self.annotations
+            .get(path)
+            .is_some_and(|annotation| annotation.sequence)
+            || !self.sequence_paths.is_empty() && self.sequence_paths.contains(&field_pattern(path))
+    }
+
+    /// Credit for a ground-truth field absent from the prediction: `optional_missing_credit`
+    /// when the template marks it optional, nothing otherwise.
+    pub fn missing_field_credit(&self, path: &str) -> Option<f64> {
+        (self.optional_missing_credit > 0.0 && self.annotations.is_optional(path))
+            .then_some(self.optional_missing_credit)
+    }
+
+    /// The field's `x-numeric-tolerance`, else its tolerance derived from the error
+    /// baseline, else `--numeric-tolerance`.
+    pub fn numeric_tolerance_for(&self, path: &str) -> Option<NumericTolerance> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.numeric_tolerance)
+            .or_else(|| self.derived_tolerance_for(path))
+            .or(self.numeric_tolerance)
+    }
+
+    /// The reviewer's score for this field of the document, if it was adjudicated.
+    pub fn decided_score(&self, document_id: &str, path: &str) -> Option<f64> {
+        self.field_decisions
+            .get(document_id)?
+            .get(path)
+            .map(|&accepted| if accepted { 1.0 } else { 0.0 })
+    }
+
+    fn derived_tolerance_for(&self, path: &str) -> Option<NumericTolerance> {
+        if self.derived_tolerances.is_empty() {
+            return None;
+        }
+        self.derived_tolerances.get(&field_pattern(path)).copied()
+    }
+
+    pub fn numeric_asymmetry_for(&self, path: &str) -> Option<NumericAsymmetry> {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.numeric_asymmetry)
+            .or(self.numeric_asymmetry)
+    }
+
+    pub fn path_separator_for(&self, path: &str) -> &str {
+        self.annotations
+            .get(path)
+            .and_then(|annotation| annotation.path_separator.as_deref())
+            .or(self.path_separator.as_deref())
+            .unwrap_or(DEFAULT_PATH_SEPARATOR)
+    }
+}
+
+/// Parses a duration such as `90s`, `15m`, `1h` or `2d` (a bare number means seconds).
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}'"))?;
+    let seconds = match unit.trim() {
+        "" | "s" => amount,
+        "m" => amount * 60.0,
+        "h" => amount * 3_600.0,
+        "d" => amount * 86_400.0,
+        other => {
+            return Err(format!(
+                "unknown duration unit '{other}' (use s, m, h or d)"
+            ))
+        }
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration '{value}' is out of range"))
+}
+
+/// Parses a `--depth-decay` factor, which must be a finite number greater than zero.
+pub fn parse_depth_decay(value: &str) -> Result<f64, String> {
+    let decay: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid depth decay '{value}'"))?;
+    if !decay.is_finite() || decay <= 0.0 {
+        return Err(format!(
+            "depth decay must be greater than zero, got '{value}'"
+        ));
+    }
+    Ok(decay)
+}
+
+/// Parses a non-negative `--geo-tolerance-km` distance.
+pub fn parse_geo_tolerance(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|km: &f64| km.is_finite() && *km >= 0.0)
+        .ok_or_else(|| format!("invalid distance '{value}' (use kilometres, e.g. 0.5)"))
+}
+
+/// Parses an `--outlier-factor` greater than 1.
+pub fn parse_outlier_factor(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|factor: &f64| factor.is_finite() && *factor > 1.0)
+        .ok_or_else(|| format!("invalid outlier factor '{value}' (use a number above 1, e.g. 10)"))
+}
+
+/// Parses a `--worst-field-threshold` score between 0 and 1.
+pub fn parse_score_threshold(value: &str) -> Result<f64, String> {
+    let threshold: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid score threshold '{value}'"))?;
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!(
+            "score threshold must be between 0 and 1, got '{value}'"
+        ));
+    }
+    Ok(threshold)
+}
+
+/// Parses an `--optional-missing-credit` value between 0 and 1.
+pub fn parse_credit(value: &str) -> Result<f64, String> {
+    let credit: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid credit '{value}'"))?;
+    if !(0.0..=1.0).contains(&credit) {
+        return Err(format!("credit must be between 0 and 1, got '{value}'"));
+    }
+    Ok(credit)
+}
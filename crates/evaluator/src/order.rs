@@ -0,0 +1,93 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::evaluator::Document;
+
+/// An ordered array whose matched elements appear in a different relative order in the
+/// prediction than in the ground truth.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderViolation {
+    pub document_id: String,
+    pub path: String,
+    /// Elements found, with an identical value, on both sides.
+    pub matched_elements: u32,
+    /// Pairs of ground-truth indices `[i, j]` with `i < j` whose predictions appear as
+    /// `j` before `i`.
+    pub inversions: Vec<[usize; 2]>,
+}
+
+/// Checks the arrays at `paths` (dot-separated, e.g. `items` or `shipment.events`) in every
+/// document that has a prediction and reports those with order inversions.
+///
+/// Elements are matched by value: each ground-truth element pairs with the first unused
+/// predicted element equal to it. Unmatched elements are ignored, so missing or wrong
+/// elements show up in the regular scores rather than as order problems.
+pub fn check_order(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    paths: &[String],
+) -> Vec<OrderViolation> {
+    let mut violations = Vec::new();
+    for (doc_id, gt_doc) in ground_truth {
+        let Some(pred_doc) = predictions.get(doc_id) else {
+            continue;
+        };
+        for path in paths {
+            let (Some(expected), Some(predicted)) = (
+                array_at(&gt_doc.fields, path),
+                array_at(&pred_doc.fields, path),
+            ) else {
+                continue;
+            };
+            let positions = matched_positions(expected, predicted);
+            let inversions = inversions(&positions);
+            if !inversions.is_empty() {
+                violations.push(OrderViolation {
+                    document_id: doc_id.clone(),
+                    path: path.clone(),
+                    matched_elements: positions.len() as u32,
+                    inversions,
+                });
+            }
+        }
+    }
+    violations
+}
+
+fn array_at<'a>(fields: &'a Value, path: &str) -> Option<&'a Vec<Value>> {
+    path.split('.')
+        .try_fold(fields, |node, segment| match node {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })?
+        .as_array()
+}
+
+/// `(expected index, predicted index)` for every matched element, in expected order.
+fn matched_positions(expected: &[Value], predicted: &[Value]) -> Vec<(usize, usize)> {
+    let mut used = vec![false; predicted.len()];
+    let mut positions = Vec::new();
+    for (expected_index, element) in expected.iter().enumerate() {
+        let found =
+            (0..predicted.len()).find(|&index| !used[index] && predicted[index] == *element);
+        if let Some(predicted_index) = found {
+            used[predicted_index] = true;
+            positions.push((expected_index, predicted_index));
+        }
+    }
+    positions
+}
+
+fn inversions(positions: &[(usize, usize)]) -> Vec<[usize; 2]> {
+    let mut inverted = Vec::new();
+    for (i, (first_expected, first_predicted)) in positions.iter().enumerate() {
+        for (second_expected, second_predicted) in &positions[i + 1..] {
+            if second_predicted < first_predicted {
+                inverted.push([*first_expected, *second_expected]);
+            }
+        }
+    }
+    inverted
+}
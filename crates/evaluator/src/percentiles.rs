@@ -0,0 +1,66 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::numbers::NumericTolerance;
+
+/// Percentile of historical errors used for derived tolerances when none is given.
+pub const DEFAULT_ERROR_PERCENTILE: f64 = 95.0;
+
+/// Historical absolute errors of numeric fields, from a baseline statistics file such as
+/// `{"total_amount": [0.0, 0.02, 0.5]}` keyed by field pattern (array indices as `*`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorBaseline {
+    errors: BTreeMap<String, Vec<f64>>,
+}
+
+impl ErrorBaseline {
+    /// Reads a JSON object mapping each field pattern to an array of non-negative errors.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let fields = value
+            .as_object()
+            .ok_or("error baseline must be an object of field to error list")?;
+        let mut errors = BTreeMap::new();
+        for (field, values) in fields {
+            let values = values
+                .as_array()
+                .ok_or_else(|| format!("errors of '{field}' must be an array"))?
+                .iter()
+                .map(|error| error.as_f64().map(f64::abs))
+                .collect::<Option<Vec<f64>>>()
+                .ok_or_else(|| format!("errors of '{field}' must be numbers"))?;
+            if !values.is_empty() {
+                errors.insert(field.clone(), values);
+            }
+        }
+        Ok(Self { errors })
+    }
+
+    /// An absolute tolerance per field: the `percentile` (0-100] of its historical errors, by
+    /// the nearest-rank method, so `95` accepts differences no larger than 95% of past errors.
+    pub fn tolerances(&self, percentile: f64) -> BTreeMap<String, NumericTolerance> {
+        self.errors
+            .iter()
+            .map(|(field, errors)| {
+                let mut sorted = errors.clone();
+                sorted.sort_by(f64::total_cmp);
+                let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+                let error = sorted[rank.clamp(1, sorted.len()) - 1];
+                (field.clone(), NumericTolerance::Absolute(error))
+            })
+            .collect()
+    }
+}
+
+/// Parses `--error-percentile`, a percentile in (0, 100].
+pub fn parse_percentile(value: &str) -> Result<f64, String> {
+    let percentile: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid percentile '{value}'"))?;
+    if !(percentile > 0.0 && percentile <= 100.0) {
+        return Err(format!(
+            "percentile must be greater than 0 and at most 100, got '{value}'"
+        ));
+    }
+    Ok(percentile)
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::metrics::round;
+
+/// Cumulative wall-clock time, in milliseconds, spent in each phase of scoring with
+/// `--profile`. Phases of documents scored on different threads add up, so the total can
+/// exceed the run's elapsed time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseProfile {
+    /// Flattening ground-truth and predicted fields (decoding, aliases, remaps, transforms).
+    pub flatten_ms: f64,
+    /// Scoring numeric fields.
+    pub numeric_ms: f64,
+    /// Scoring text and set fields.
+    pub text_ms: f64,
+    /// Matching field paths and collecting missing and extra fields.
+    pub set_diff_ms: f64,
+}
+
+impl PhaseProfile {
+    /// Sums the phase times of two profiles.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            flatten_ms: self.flatten_ms + other.flatten_ms,
+            numeric_ms: self.numeric_ms + other.numeric_ms,
+            text_ms: self.text_ms + other.text_ms,
+            set_diff_ms: self.set_diff_ms + other.set_diff_ms,
+        }
+    }
+
+    pub(crate) fn rounded(self) -> Self {
+        Self {
+            flatten_ms: round(self.flatten_ms),
+            numeric_ms: round(self.numeric_ms),
+            text_ms: round(self.text_ms),
+            set_diff_ms: round(self.set_diff_ms),
+        }
+    }
+}
+
+/// Starts timing a phase, only when profiling: otherwise no clock is read.
+pub(crate) fn start(profile: bool) -> Option<Instant> {
+    profile.then(Instant::now)
+}
+
+/// Adds the time since `started` to a phase total, if the phase was timed.
+pub(crate) fn stop(started: Option<Instant>, total_ms: &mut f64) {
+    if let Some(started) = started {
+        *total_ms += started.elapsed().as_secs_f64() * 1000.0;
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::digest::sha256_hex;
+use crate::groups::UNGROUPED;
+
+const PLACEHOLDER: char = '*';
+
+/// Output keys whose values are raw document values rather than scores or paths.
+const VALUE_KEYS: &[&str] = &["expected", "predicted", "matched_text"];
+
+/// `systematic_biases` statistics are computed directly from expected and predicted numbers.
+const BIAS_KEY: &str = "systematic_biases";
+const BIAS_VALUE_KEYS: &[&str] = &["mean", "min", "max"];
+
+/// `--group-by` results are keyed by ground-truth values.
+const GROUPS_KEY: &str = "groups";
+
+/// Pretty-prints `value` for output, masking document values when `redact` is set.
+pub fn to_output_json<T: Serialize>(value: &T, redact: bool) -> serde_json::Result<String> {
+    if !redact {
+        return serde_json::to_string_pretty(value);
+    }
+    serde_json::to_string_pretty(&redacted(value)?)
+}
+
+/// Like [`to_output_json`], but on a single line.
+pub fn to_output_line<T: Serialize>(value: &T, redact: bool) -> serde_json::Result<String> {
+    if !redact {
+        return serde_json::to_string(value);
+    }
+    serde_json::to_string(&redacted(value)?)
+}
+
+fn redacted<T: Serialize>(value: &T) -> serde_json::Result<Value> {
+    let mut output = serde_json::to_value(value)?;
+    redact_values(&mut output);
+    Ok(output)
+}
+
+/// Replaces every value-bearing entry in an output document with a placeholder of the same
+/// length, leaving scores, counts and field paths untouched. `groups` keys are replaced by
+/// stable hashes (see [`redacted_group`]), so groups stay apart and comparable across runs.
+pub fn redact_values(output: &mut Value) {
+    match output {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if VALUE_KEYS.contains(&key.as_str()) {
+                    *entry = placeholder(entry);
+                } else if key == BIAS_KEY {
+                    redact_biases(entry);
+                } else if key == GROUPS_KEY {
+                    redact_groups(entry);
+                } else {
+                    redact_values(entry);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_values),
+        _ => {}
+    }
+}
+
+/// Replaces every empty object or array in an output document with `null`, at any depth, for
+/// consumers whose schemas expect `null` rather than `{}` or `[]`.
+pub fn empty_collections_as_null(output: &mut Value) {
+    match output {
+        Value::Object(map) if map.is_empty() => *output = Value::Null,
+        Value::Array(items) if items.is_empty() => *output = Value::Null,
+        Value::Object(map) => map.values_mut().for_each(empty_collections_as_null),
+        Value::Array(items) => items.iter_mut().for_each(empty_collections_as_null),
+        _ => {}
+    }
+}
+
+fn redact_groups(groups: &mut Value) {
+    let Value::Object(map) = groups else {
+        return;
+    };
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(group, mut metrics)| {
+            redact_values(&mut metrics);
+            (redacted_group(&group), metrics)
+        })
+        .collect::<Map<String, Value>>();
+}
+
+/// A `--group-by` value as `group-` and the first 12 hex digits of its SHA-256.
+/// [`UNGROUPED`] is kept, as it names no document value.
+pub fn redacted_group(group: &str) -> String {
+    if group == UNGROUPED {
+        return group.to_string();
+    }
+    format!("group-{}", &sha256_hex(group.as_bytes())[..12])
+}
+
+fn redact_biases(biases: &mut Value) {
+    let Value::Array(items) = biases else {
+        return;
+    };
+    for bias in items.iter_mut().filter_map(Value::as_object_mut) {
+        for key in BIAS_VALUE_KEYS {
+            if let Some(entry) = bias.get_mut(*key) {
+                *entry = placeholder(entry);
+            }
+        }
+    }
+}
+
+/// Masks a value with one placeholder character per character of its textual form.
+/// `null` stays `null` so "no value" remains distinguishable from "redacted value".
+fn placeholder(value: &Value) -> Value {
+    let length = match value {
+        Value::Null => return Value::Null,
+        Value::String(text) => text.chars().count(),
+        Value::Array(items) => return Value::Array(items.iter().map(placeholder).collect()),
+        Value::Object(map) => {
+            return Value::Object(
+                map.iter()
+                    .map(|(key, entry)| (key.clone(), placeholder(entry)))
+                    .collect(),
+            )
+        }
+        other => other.to_string().chars().count(),
+    };
+    Value::String(PLACEHOLDER.to_string().repeat(length))
+}
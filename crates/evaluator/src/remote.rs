@@ -0,0 +1,76 @@
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use thiserror::Error;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{load_ground_truth_from_payload_with, Document};
+use crate::http::{exchange, Endpoint};
+use crate::options::ValidationMode;
+
+/// Payloads already fetched in this process, by URL.
+static CACHE: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    #[error("unsupported ground-truth URL '{0}' (expected http://host[:port]/path)")]
+    UnsupportedUrl(String),
+    #[error("cannot fetch '{0}': https:// needs TLS, which pdf_eval does not include; download the file and pass its path instead")]
+    TlsUnsupported(String),
+    #[error("GET {url} failed: {reason}")]
+    Failed { url: String, reason: String },
+    #[error("GET {url}: server answered '{status_line}'")]
+    Status { url: String, status_line: String },
+    #[error("GET {0}: response body is not valid UTF-8")]
+    InvalidUtf8(String),
+    #[error(transparent)]
+    Invalid(#[from] EvaluationError),
+}
+
+/// Fetches the body of a plain `http://` URL with a single GET. A URL fetched before in
+/// this process is answered from memory, so it is downloaded at most once per run.
+pub fn fetch_text(url: &str) -> Result<String, RemoteError> {
+    if let Some(payload) = CACHE.lock().unwrap_or_else(|err| err.into_inner()).get(url) {
+        return Ok(payload.clone());
+    }
+    if url.starts_with("https://") {
+        return Err(RemoteError::TlsUnsupported(url.to_string()));
+    }
+    let endpoint =
+        Endpoint::parse(url).ok_or_else(|| RemoteError::UnsupportedUrl(url.to_string()))?;
+    // HTTP/1.0 keeps the server from answering with a chunked body.
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}:{}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        endpoint.path, endpoint.host, endpoint.port
+    );
+    let response = exchange(&endpoint, &request).map_err(|reason| RemoteError::Failed {
+        url: url.to_string(),
+        reason,
+    })?;
+    if !response.is_success() {
+        return Err(RemoteError::Status {
+            url: url.to_string(),
+            status_line: response.status_line,
+        });
+    }
+    let payload =
+        String::from_utf8(response.body).map_err(|_| RemoteError::InvalidUtf8(url.to_string()))?;
+    CACHE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(url.to_string(), payload.clone());
+    Ok(payload)
+}
+
+/// Loads ground truth served at `url`, validated like a local file. `$include` directives
+/// are not resolved, since there is no directory to resolve them against.
+pub fn load_ground_truth_from_url_with(
+    url: &str,
+    validation: ValidationMode,
+) -> Result<BTreeMap<String, Document>, RemoteError> {
+    Ok(load_ground_truth_from_payload_with(
+        &fetch_text(url)?,
+        validation,
+    )?)
+}
@@ -0,0 +1,143 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::evaluator::Document;
+
+/// Fields an extraction template requires in every document, whatever its ground truth
+/// holds, with `--check-required`.
+///
+/// A field is required when it is listed in its object's `required` array and every object
+/// containing it is required too; properties under array `items` are not, since an array
+/// may be empty. A typed template adds fields per document type:
+///
+/// ```json
+/// "x-document-type": "kind",
+/// "x-required-by-type": {"invoice": ["invoice.number"], "receipt": ["merchant.name"]}
+/// ```
+///
+/// where `x-document-type` is the dot-separated path of the field naming the type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequiredFields {
+    common: Vec<String>,
+    type_path: Option<String>,
+    by_type: BTreeMap<String, Vec<String>>,
+}
+
+impl RequiredFields {
+    pub fn from_template(template: &Value) -> Self {
+        let mut common = Vec::new();
+        collect_required(template, &mut Vec::new(), &mut common);
+        let by_type = template
+            .get("x-required-by-type")
+            .and_then(Value::as_object)
+            .map(|types| {
+                types
+                    .iter()
+                    .map(|(kind, paths)| {
+                        let paths = paths
+                            .as_array()
+                            .map(|paths| {
+                                paths
+                                    .iter()
+                                    .filter_map(Value::as_str)
+                                    .map(str::to_string)
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (kind.clone(), paths)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            common,
+            type_path: template
+                .get("x-document-type")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            by_type,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.common.is_empty() && self.by_type.values().all(Vec::is_empty)
+    }
+
+    /// Fields required in a document whose type is read from `typed_by` (its ground truth,
+    /// or the prediction itself when there is none).
+    pub fn for_document(&self, typed_by: &Value) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.common.iter().map(String::as_str).collect();
+        let kind = self
+            .type_path
+            .as_deref()
+            .and_then(|path| value_at(typed_by, path))
+            .and_then(|kind| match kind {
+                Value::String(text) => Some(text.trim().to_string()),
+                Value::Number(_) | Value::Bool(_) => Some(kind.to_string()),
+                _ => None,
+            });
+        if let Some(typed) = kind.and_then(|kind| self.by_type.get(&kind)) {
+            for path in typed {
+                if !paths.contains(&path.as_str()) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Template-required fields whose key is absent from each prediction, by document id.
+/// Every prediction is checked, with or without a ground-truth document; a required key
+/// holding `null` counts as present. Documents missing every required field only because
+/// they have no prediction are reported in `missing_documents` instead.
+pub fn missing_required_fields(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    required: &RequiredFields,
+) -> BTreeMap<String, Vec<String>> {
+    predictions
+        .iter()
+        .filter_map(|(doc_id, pred_doc)| {
+            let typed_by = ground_truth
+                .get(doc_id)
+                .map_or(&pred_doc.fields, |gt_doc| &gt_doc.fields);
+            let missing: Vec<String> = required
+                .for_document(typed_by)
+                .into_iter()
+                .filter(|path| value_at(&pred_doc.fields, path).is_none())
+                .map(str::to_string)
+                .collect();
+            (!missing.is_empty()).then(|| (doc_id.clone(), missing))
+        })
+        .collect()
+}
+
+fn collect_required(node: &Value, path: &mut Vec<String>, required: &mut Vec<String>) {
+    let Some(properties) = node.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    let names = node
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for name in names {
+        path.push(name.to_string());
+        required.push(path.join("."));
+        if let Some(child) = properties.get(name) {
+            collect_required(child, path, required);
+        }
+        path.pop();
+    }
+}
+
+fn value_at<'a>(fields: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(fields, |node, segment| match node {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
@@ -0,0 +1,67 @@
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+/// Delay before the first retry of a failed read; it doubles with each further retry.
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// How often to retry a file read that failed transiently, as on a network filesystem
+/// returning a stale handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadRetry {
+    /// Retries after the first attempt; zero reads once.
+    pub retries: u32,
+    /// Wait before the first retry, doubled before each later one.
+    pub backoff: Duration,
+}
+
+impl Default for ReadRetry {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+}
+
+impl ReadRetry {
+    pub fn new(retries: u32) -> Self {
+        Self {
+            retries,
+            ..Self::default()
+        }
+    }
+}
+
+/// Whether a failed read may succeed if repeated. Missing files, permissions and malformed
+/// content fail the same way every time, so they are not retried.
+pub fn is_transient(error: &io::Error) -> bool {
+    !matches!(
+        error.kind(),
+        io::ErrorKind::NotFound
+            | io::ErrorKind::PermissionDenied
+            | io::ErrorKind::InvalidInput
+            | io::ErrorKind::InvalidData
+            | io::ErrorKind::Unsupported
+    )
+}
+
+/// Runs `read` until it succeeds, fails with a non-transient error, or has been retried
+/// `policy.retries` times; the last error is returned.
+pub fn read_with_retry<T>(
+    policy: &ReadRetry,
+    mut read: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut backoff = policy.backoff;
+    let mut attempt = 0;
+    loop {
+        match read() {
+            Err(error) if attempt < policy.retries && is_transient(&error) => {
+                thread::sleep(backoff);
+                backoff = backoff.saturating_mul(2);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
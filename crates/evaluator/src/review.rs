@@ -0,0 +1,149 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::metrics::EvaluationMetrics;
+
+/// `--ambiguous-band LOW,HIGH`: fields scoring in `[low, high]` are offered for review with
+/// `--interactive`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbiguousBand {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl AmbiguousBand {
+    pub fn contains(&self, score: f64) -> bool {
+        (self.low..=self.high).contains(&score)
+    }
+}
+
+impl FromStr for AmbiguousBand {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid band '{value}' (use LOW,HIGH, e.g. 0.5,0.9)");
+        let (low, high) = value.split_once(',').ok_or_else(invalid)?;
+        let low: f64 = low.trim().parse().map_err(|_| invalid())?;
+        let high: f64 = high.trim().parse().map_err(|_| invalid())?;
+        if !(0.0..=1.0).contains(&low) || !(0.0..=1.0).contains(&high) || low > high {
+            return Err(format!("band '{value}' must satisfy 0 <= LOW <= HIGH <= 1"));
+        }
+        Ok(Self { low, high })
+    }
+}
+
+/// A scored field whose automatic score fell in the ambiguous band.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewCandidate {
+    pub document_id: String,
+    pub field: String,
+    pub score: f64,
+    pub expected: Option<Value>,
+    pub predicted: Option<Value>,
+}
+
+/// A reviewer's verdict on one field: accepted fields score 1, rejected ones 0.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewDecision {
+    pub document_id: String,
+    pub field: String,
+    pub automatic_score: f64,
+    pub accepted: bool,
+}
+
+/// Fields of `metrics` scoring within `band`, in document and field order.
+pub fn review_candidates(metrics: &EvaluationMetrics, band: AmbiguousBand) -> Vec<ReviewCandidate> {
+    metrics
+        .documents
+        .iter()
+        .flat_map(|document| {
+            document
+                .field_scores
+                .iter()
+                .filter(|field| band.contains(field.score))
+                .map(|field| ReviewCandidate {
+                    document_id: document.document_id.clone(),
+                    field: field.path.clone(),
+                    score: field.score,
+                    expected: field.expected.clone(),
+                    predicted: field.predicted.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Asks about each candidate on `output` and reads `a`ccept, `r`eject or `s`kip answers
+/// from `input`. Skipped fields keep their automatic score; end of input stops the review.
+pub fn review_fields(
+    candidates: &[ReviewCandidate],
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Vec<ReviewDecision>> {
+    let mut decisions = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        writeln!(
+            output,
+            "[{}/{}] {}: {} scored {}",
+            index + 1,
+            candidates.len(),
+            candidate.document_id,
+            candidate.field,
+            candidate.score
+        )?;
+        writeln!(
+            output,
+            "  expected:  {}",
+            shown(candidate.expected.as_ref())
+        )?;
+        writeln!(
+            output,
+            "  predicted: {}",
+            shown(candidate.predicted.as_ref())
+        )?;
+        let accepted = loop {
+            write!(output, "accept, reject or skip? [a/r/s] ")?;
+            output.flush()?;
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                return Ok(decisions);
+            }
+            match answer.trim().to_ascii_lowercase().as_str() {
+                "a" | "accept" => break Some(true),
+                "r" | "reject" => break Some(false),
+                "s" | "skip" | "" => break None,
+                _ => writeln!(output, "  please answer a, r or s")?,
+            }
+        };
+        if let Some(accepted) = accepted {
+            decisions.push(ReviewDecision {
+                document_id: candidate.document_id.clone(),
+                field: candidate.field.clone(),
+                automatic_score: candidate.score,
+                accepted,
+            });
+        }
+    }
+    Ok(decisions)
+}
+
+/// Decisions keyed by document id, then field path, as read by
+/// [`crate::options::EvaluationOptions::field_decisions`].
+pub fn decisions_by_document(
+    decisions: &[ReviewDecision],
+) -> BTreeMap<String, BTreeMap<String, bool>> {
+    let mut by_document: BTreeMap<String, BTreeMap<String, bool>> = BTreeMap::new();
+    for decision in decisions {
+        by_document
+            .entry(decision.document_id.clone())
+            .or_default()
+            .insert(decision.field.clone(), decision.accepted);
+    }
+    by_document
+}
+
+fn shown(value: Option<&Value>) -> String {
+    value.map_or_else(|| "(missing)".to_string(), Value::to_string)
+}
@@ -0,0 +1,28 @@
+use sha2::{Digest, Sha256};
+
+/// An id for one evaluation run, formatted as a version 8 (custom) UUID: the first 128 bits
+/// of the SHA-256 of the run's `inputs` (e.g. its command-line arguments), the time it
+/// started in nanoseconds and its process id. The same values always give the same id, and
+/// runs started at different times or by different processes get different ones.
+pub fn derive_run_id(inputs: &[String], started_nanos: u128, process_id: u32) -> String {
+    let mut hasher = Sha256::new();
+    for input in inputs {
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input.as_bytes());
+    }
+    hasher.update(started_nanos.to_le_bytes());
+    hasher.update(process_id.to_le_bytes());
+    let mut bytes = [0_u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
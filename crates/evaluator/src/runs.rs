@@ -0,0 +1,76 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::EvaluationError;
+use crate::evaluator::{evaluate_predictions_with_options, Document};
+use crate::metrics::round;
+use crate::options::EvaluationOptions;
+
+/// Spread of a score across repeated runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreStats {
+    pub mean: f64,
+    pub variance: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub scores: Vec<f64>,
+}
+
+impl ScoreStats {
+    fn from_scores(scores: Vec<f64>) -> Self {
+        let count = scores.len().max(1) as f64;
+        let mean = scores.iter().sum::<f64>() / count;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count;
+        let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self {
+            mean: round(mean),
+            variance: round(variance),
+            std_dev: round(variance.sqrt()),
+            min: round(min),
+            max: round(max),
+            scores,
+        }
+    }
+}
+
+/// Stability report for several prediction sets produced by repeated runs of one model.
+#[derive(Debug, Clone, Serialize)]
+pub struct RepeatedRunSummary {
+    pub num_runs: u32,
+    pub overall_score: ScoreStats,
+    pub documents: BTreeMap<String, ScoreStats>,
+}
+
+/// Scores every run against the same ground truth and reports the mean and (population)
+/// variance of the overall score and of each document's overall score.
+pub fn evaluate_runs(
+    ground_truth: &BTreeMap<String, Document>,
+    runs: &[BTreeMap<String, Document>],
+    options: &EvaluationOptions,
+) -> Result<RepeatedRunSummary, EvaluationError> {
+    if runs.is_empty() {
+        return Err(EvaluationError::EmptyInput);
+    }
+    let mut overall_scores = Vec::with_capacity(runs.len());
+    let mut document_scores: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for predictions in runs {
+        let metrics = evaluate_predictions_with_options(ground_truth, predictions, options)?;
+        overall_scores.push(metrics.overall_score);
+        for document in metrics.documents {
+            document_scores
+                .entry(document.document_id)
+                .or_default()
+                .push(document.overall_score);
+        }
+    }
+    Ok(RepeatedRunSummary {
+        num_runs: runs.len() as u32,
+        overall_score: ScoreStats::from_scores(overall_scores),
+        documents: document_scores
+            .into_iter()
+            .map(|(doc_id, scores)| (doc_id, ScoreStats::from_scores(scores)))
+            .collect(),
+    })
+}
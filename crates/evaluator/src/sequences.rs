@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+/// Element-level edits that turn an expected sequence into the predicted one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequenceOperations {
+    /// Aligned elements that score 1.0.
+    pub matches: u32,
+    /// Aligned elements that differ.
+    pub substitutions: u32,
+    /// Predicted elements without an expected counterpart.
+    pub insertions: u32,
+    /// Expected elements without a predicted counterpart.
+    pub deletions: u32,
+}
+
+impl SequenceOperations {
+    pub fn add(&mut self, other: &SequenceOperations) {
+        self.matches += other.matches;
+        self.substitutions += other.substitutions;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+    }
+}
+
+/// The cheapest alignment of two sequences, with its score in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SequenceAlignment {
+    /// One minus the edit distance relative to the longer sequence; two empty sequences
+    /// score 1.0.
+    pub score: f64,
+    pub operations: SequenceOperations,
+}
+
+/// Aligns `expected_len` elements against `predicted_len` by edit distance, where inserting
+/// or deleting an element costs 1 and pairing expected element `i` with predicted element
+/// `j` costs `1 - similarity(i, j)`. One inserted row then costs a single edit instead of
+/// shifting every later row out of place.
+pub fn align_sequences(
+    expected_len: usize,
+    predicted_len: usize,
+    similarity: impl Fn(usize, usize) -> f64,
+) -> SequenceAlignment {
+    let longest = expected_len.max(predicted_len);
+    if longest == 0 {
+        return SequenceAlignment {
+            score: 1.0,
+            operations: SequenceOperations::default(),
+        };
+    }
+    let columns = predicted_len + 1;
+    let mut pair_scores = vec![0.0; expected_len * predicted_len];
+    for i in 0..expected_len {
+        for j in 0..predicted_len {
+            pair_scores[i * predicted_len + j] = similarity(i, j).clamp(0.0, 1.0);
+        }
+    }
+    let mut cost = vec![0.0; (expected_len + 1) * columns];
+    for i in 0..=expected_len {
+        for j in 0..=predicted_len {
+            cost[i * columns + j] = match (i, j) {
+                (0, _) => j as f64,
+                (_, 0) => i as f64,
+                _ => {
+                    let pair = 1.0 - pair_scores[(i - 1) * predicted_len + (j - 1)];
+                    (cost[(i - 1) * columns + (j - 1)] + pair)
+                        .min(cost[(i - 1) * columns + j] + 1.0)
+                        .min(cost[i * columns + (j - 1)] + 1.0)
+                }
+            };
+        }
+    }
+
+    let mut operations = SequenceOperations::default();
+    let (mut i, mut j) = (expected_len, predicted_len);
+    while i > 0 || j > 0 {
+        let here = cost[i * columns + j];
+        if i > 0 && j > 0 {
+            let pair_score = pair_scores[(i - 1) * predicted_len + (j - 1)];
+            if (cost[(i - 1) * columns + (j - 1)] + 1.0 - pair_score - here).abs() < 1e-9 {
+                if pair_score >= 1.0 {
+                    operations.matches += 1;
+                } else {
+                    operations.substitutions += 1;
+                }
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && (j == 0 || (cost[(i - 1) * columns + j] + 1.0 - here).abs() < 1e-9) {
+            operations.deletions += 1;
+            i -= 1;
+        } else {
+            operations.insertions += 1;
+            j -= 1;
+        }
+    }
+    SequenceAlignment {
+        score: (1.0 - cost[expected_len * columns + predicted_len] / longest as f64).max(0.0),
+        operations,
+    }
+}
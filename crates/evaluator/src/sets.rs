@@ -0,0 +1,59 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How an `x-set` array field compares its elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetMode {
+    /// `true`: duplicates are ignored.
+    Set,
+    /// `"multiset"`: each repeated element counts separately.
+    Multiset,
+}
+
+impl SetMode {
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(true) => Some(SetMode::Set),
+            Value::String(mode) if mode == "multiset" => Some(SetMode::Multiset),
+            _ => None,
+        }
+    }
+}
+
+/// Scores two arrays by intersection over union of their elements, ignoring order. Elements
+/// are equal when their JSON is. Two empty arrays score 1.0. Returns `None` when either side
+/// is not an array.
+pub fn set_similarity(expected: &Value, predicted: &Value, mode: SetMode) -> Option<f64> {
+    let expected = element_counts(expected, mode)?;
+    let predicted = element_counts(predicted, mode)?;
+    let mut intersection = 0;
+    let mut union = 0;
+    for (element, &count) in &expected {
+        let other = predicted.get(element).copied().unwrap_or(0);
+        intersection += count.min(other);
+        union += count.max(other);
+    }
+    union += predicted
+        .iter()
+        .filter(|(element, _)| !expected.contains_key(*element))
+        .map(|(_, count)| count)
+        .sum::<usize>();
+    Some(if union == 0 {
+        1.0
+    } else {
+        intersection as f64 / union as f64
+    })
+}
+
+/// Occurrences of each element, keyed by its JSON text; every count is 1 in [`SetMode::Set`].
+fn element_counts(value: &Value, mode: SetMode) -> Option<BTreeMap<String, usize>> {
+    let mut counts = BTreeMap::new();
+    for element in value.as_array()? {
+        let count = counts.entry(element.to_string()).or_insert(0);
+        *count = match mode {
+            SetMode::Set => 1,
+            SetMode::Multiset => *count + 1,
+        };
+    }
+    Some(counts)
+}
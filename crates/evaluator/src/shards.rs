@@ -0,0 +1,30 @@
+//! Splitting and compression of the embedded ground truth, shared by `build.rs` (which
+//! includes this file) and the library. Only depends on crates that are also build
+//! dependencies.
+
+use flate2::{write::ZlibEncoder, Compression};
+use serde_json::Value;
+use std::io::Write;
+
+/// Splits a ground-truth payload into at most `count` JSON arrays of consecutive documents,
+/// as even in size as possible. With a `count` of 0 or 1 the payload is kept as it is, so a
+/// single shard holds the exact file bytes.
+pub fn split_documents(raw: &[u8], count: usize) -> Result<Vec<Vec<u8>>, String> {
+    if count <= 1 {
+        return Ok(vec![raw.to_vec()]);
+    }
+    let documents: Vec<Value> = serde_json::from_slice(raw)
+        .map_err(|err| format!("Ground truth must be a JSON array of documents: {err}"))?;
+    let shard_size = documents.len().div_ceil(count).max(1);
+    documents
+        .chunks(shard_size)
+        .map(|shard| serde_json::to_vec(shard).map_err(|err| err.to_string()))
+        .collect()
+}
+
+/// Zlib-compresses a shard as it is embedded.
+pub fn compress(payload: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(payload).expect("compression failed");
+    encoder.finish().expect("compression finalize failed")
+}
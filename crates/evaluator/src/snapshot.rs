@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use crate::error::EvaluationError;
+
+/// Result of checking an output against a stored snapshot file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The output is identical to the snapshot.
+    Matched,
+    /// The snapshot was written (`--update-snapshots`).
+    Updated,
+    /// The output differs; holds the line diff from [`line_diff`].
+    Mismatched(String),
+    /// No snapshot exists yet and updating was not requested.
+    Missing,
+}
+
+/// Compares `output` with the snapshot at `path`, or overwrites the snapshot with `output`
+/// when `update` is set.
+pub fn check_snapshot(
+    path: &Path,
+    output: &str,
+    update: bool,
+) -> Result<SnapshotOutcome, EvaluationError> {
+    if update {
+        std::fs::write(path, output)?;
+        return Ok(SnapshotOutcome::Updated);
+    }
+    let stored = match std::fs::read_to_string(path) {
+        Ok(stored) => stored,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(SnapshotOutcome::Missing)
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if stored == output {
+        Ok(SnapshotOutcome::Matched)
+    } else {
+        Ok(SnapshotOutcome::Mismatched(line_diff(&stored, output)))
+    }
+}
+
+/// Renders the lines that differ between `expected` and `actual` from a longest common
+/// subsequence alignment: `-` lines are only in `expected`, `+` lines only in `actual`, each
+/// prefixed with its 1-based line number on that side. Unchanged lines are omitted.
+pub fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let cols = actual.len() + 1;
+    // common[i * cols + j] = LCS length of expected[i..] and actual[j..].
+    let mut common = vec![0_usize; (expected.len() + 1) * cols];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i * cols + j] = if expected[i] == actual[j] {
+                common[(i + 1) * cols + j + 1] + 1
+            } else {
+                common[(i + 1) * cols + j].max(common[i * cols + j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+        } else if j == actual.len()
+            || (i < expected.len() && common[(i + 1) * cols + j] >= common[i * cols + j + 1])
+        {
+            diff.push_str(&format!("-{:>5} | {}\n", i + 1, expected[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{:>5} | {}\n", j + 1, actual[j]));
+            j += 1;
+        }
+    }
+    diff
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeSet;
+
+/// Built-in list selected with `--stop-words english`.
+const ENGLISH: &str = "\
+    a about above after again against all am an and any are as at be because been before \
+    being below between both but by can did do does doing down during each few for from \
+    further had has have having he her here hers herself him himself his how i if in into is \
+    it its itself just me more most my myself no nor not now of off on once only or other \
+    our ours ourselves out over own same she should so some such than that the their theirs \
+    them themselves then there these they this those through to too under until up very was \
+    we were what when where which while who whom why will with you your yours yourself \
+    yourselves";
+
+/// Filler words dropped from flagged text fields before scoring. Words match
+/// case-insensitively and ignore surrounding punctuation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StopWords {
+    words: BTreeSet<String>,
+}
+
+impl StopWords {
+    pub fn english() -> Self {
+        Self::from_text(ENGLISH)
+    }
+
+    /// Reads whitespace-separated words, e.g. one per line.
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            words: text.split_whitespace().map(normalize).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// `text` without its stop words, remaining words joined by single spaces.
+    pub fn remove(&self, text: &str) -> String {
+        text.split_whitespace()
+            .filter(|word| !self.words.contains(&normalize(word)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
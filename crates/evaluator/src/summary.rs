@@ -0,0 +1,79 @@
+use anstyle::{AnsiColor, Style};
+
+use crate::metrics::EvaluationMetrics;
+
+/// Scores at or above this are shown in green.
+const GOOD_SCORE: f64 = 0.9;
+/// Scores at or above this (and below [`GOOD_SCORE`]) are shown in yellow, lower ones in red.
+const FAIR_SCORE: f64 = 0.7;
+/// Documents listed under "lowest documents".
+const LOWEST_DOCUMENTS: usize = 3;
+
+/// A short human-readable summary of `metrics` for interactive use, with each score colored
+/// green, yellow or red by band when `color` is set.
+pub fn terminal_summary(metrics: &EvaluationMetrics, color: bool) -> String {
+    let score = |value: f64| {
+        let text = format!("{value:.4}");
+        if !color {
+            return text;
+        }
+        let band = if value >= GOOD_SCORE {
+            AnsiColor::Green
+        } else if value >= FAIR_SCORE {
+            AnsiColor::Yellow
+        } else {
+            AnsiColor::Red
+        };
+        let style = Style::new().fg_color(Some(band.into()));
+        format!("{}{text}{}", style.render(), style.render_reset())
+    };
+    let heading = |text: &str| {
+        if color {
+            let style = Style::new().bold();
+            format!("{}{text}{}", style.render(), style.render_reset())
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut summary = format!(
+        "{} ({} documents, {} fields)\n",
+        heading("Evaluation summary"),
+        metrics.num_documents,
+        metrics.num_fields
+    );
+    for (label, value) in [
+        ("overall score", metrics.overall_score),
+        ("document coverage", metrics.document_coverage),
+        ("numeric similarity", metrics.numeric_field_similarity),
+        ("text similarity", metrics.text_field_similarity),
+        ("structural completeness", metrics.structural_completeness),
+    ] {
+        summary.push_str(&format!("  {label:<24} {}\n", score(value)));
+    }
+    if !metrics.missing_documents.is_empty() {
+        summary.push_str(&format!(
+            "  {:<24} {}\n",
+            "missing documents",
+            metrics.missing_documents.len()
+        ));
+    }
+
+    let mut documents: Vec<_> = metrics.documents.iter().collect();
+    documents.sort_by(|a, b| {
+        a.overall_score
+            .total_cmp(&b.overall_score)
+            .then_with(|| a.document_id.cmp(&b.document_id))
+    });
+    if documents.len() > 1 {
+        summary.push_str(&format!("{}\n", heading("Lowest documents")));
+        for document in documents.into_iter().take(LOWEST_DOCUMENTS) {
+            summary.push_str(&format!(
+                "  {:<24} {}\n",
+                document.document_id,
+                score(document.overall_score)
+            ));
+        }
+    }
+    summary
+}
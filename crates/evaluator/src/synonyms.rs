@@ -0,0 +1,68 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Canonical terms and the synonyms that mean the same thing, e.g.
+/// `{"invoice": ["bill", "receipt"]}`. Terms match case-insensitively after trimming.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SynonymMap {
+    /// Normalized term (canonical or synonym) to its normalized canonical term.
+    canonical: BTreeMap<String, String>,
+}
+
+impl SynonymMap {
+    /// Reads a JSON object mapping each canonical term to an array of its synonyms.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let groups = value
+            .as_object()
+            .ok_or("synonyms must be an object of canonical term to synonym list")?;
+        let mut map = Self::default();
+        for (canonical, synonyms) in groups {
+            let synonyms = synonyms
+                .as_array()
+                .ok_or_else(|| format!("synonyms of '{canonical}' must be an array"))?;
+            let canonical_key = normalize(canonical);
+            map.insert(&canonical_key, &canonical_key)?;
+            for synonym in synonyms {
+                let synonym = synonym
+                    .as_str()
+                    .ok_or_else(|| format!("synonyms of '{canonical}' must be strings"))?;
+                map.insert(&normalize(synonym), &canonical_key)?;
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canonical.is_empty()
+    }
+
+    /// Canonical term for `term`, if it was declared.
+    pub fn canonical(&self, term: &str) -> Option<&str> {
+        self.canonical.get(&normalize(term)).map(String::as_str)
+    }
+
+    /// Whether both terms are declared and share a canonical term.
+    pub fn same_term(&self, left: &str, right: &str) -> bool {
+        matches!(
+            (self.canonical(left), self.canonical(right)),
+            (Some(left), Some(right)) if left == right
+        )
+    }
+
+    fn insert(&mut self, term: &str, canonical: &str) -> Result<(), String> {
+        match self.canonical.get(term) {
+            Some(existing) if existing != canonical => Err(format!(
+                "'{term}' is listed under both '{existing}' and '{canonical}'"
+            )),
+            _ => {
+                self.canonical
+                    .insert(term.to_string(), canonical.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+fn normalize(term: &str) -> String {
+    term.trim().to_lowercase()
+}
@@ -0,0 +1,112 @@
+use serde_json::{Number, Value};
+
+use crate::markup::{strip_html, strip_markdown};
+use crate::numbers::{parse_localized, NumberLocale};
+
+/// Currency symbols removed by [`Transform::StripCurrency`].
+const CURRENCY_SYMBOLS: &[char] = &[
+    '$', '€', '£', '¥', '₹', '₽', '₩', '₺', '₪', '₫', '₦', '₱', '₴', '¢', '₿',
+];
+
+/// One named step of an `x-transform` pipeline, applied to both values of a field before
+/// scoring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// `trim`: drop leading and trailing whitespace.
+    Trim,
+    /// `lowercase`
+    Lowercase,
+    /// `uppercase`
+    Uppercase,
+    /// `collapse-whitespace`: replace every run of whitespace with a single space.
+    CollapseWhitespace,
+    /// `strip-currency`: drop currency symbols (`$`, `€`, ...) and a leading or trailing
+    /// three-letter code such as `USD`.
+    StripCurrency,
+    /// `strip-html`: keep the text content of HTML markup (`<b>Total</b>` becomes `Total`).
+    StripHtml,
+    /// `strip-markdown`: drop Markdown markup such as emphasis, headings and link targets
+    /// (`**Total**` becomes `Total`).
+    StripMarkdown,
+    /// `parse-number`: turn a string into a JSON number, read with `--locale` conventions
+    /// (`en` when unset). Strings that are not numbers are left unchanged.
+    ParseNumber,
+}
+
+impl Transform {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "trim" => Some(Transform::Trim),
+            "lowercase" => Some(Transform::Lowercase),
+            "uppercase" => Some(Transform::Uppercase),
+            "collapse-whitespace" => Some(Transform::CollapseWhitespace),
+            "strip-currency" => Some(Transform::StripCurrency),
+            "strip-html" => Some(Transform::StripHtml),
+            "strip-markdown" => Some(Transform::StripMarkdown),
+            "parse-number" => Some(Transform::ParseNumber),
+            _ => None,
+        }
+    }
+
+    /// Reads a pipeline from a template annotation: an array of transform names. A pipeline
+    /// naming an unknown transform is ignored as a whole.
+    pub fn pipeline_from_json(value: &Value) -> Option<Vec<Self>> {
+        value
+            .as_array()?
+            .iter()
+            .map(|name| name.as_str().and_then(Self::from_name))
+            .collect()
+    }
+
+    /// Applies the transform to a string value; other values pass through unchanged.
+    pub fn apply(self, value: Value, locale: Option<NumberLocale>) -> Value {
+        let Value::String(text) = value else {
+            return value;
+        };
+        let text = match self {
+            Transform::Trim => text.trim().to_string(),
+            Transform::Lowercase => text.to_lowercase(),
+            Transform::Uppercase => text.to_uppercase(),
+            Transform::CollapseWhitespace => text.split_whitespace().collect::<Vec<_>>().join(" "),
+            Transform::StripCurrency => strip_currency(&text),
+            Transform::StripHtml => strip_html(&text),
+            Transform::StripMarkdown => strip_markdown(&text),
+            Transform::ParseNumber => {
+                return parse_localized(&text, locale.unwrap_or(NumberLocale::En))
+                    .and_then(Number::from_f64)
+                    .map_or(Value::String(text), Value::Number)
+            }
+        };
+        Value::String(text)
+    }
+}
+
+/// Runs `value` through every transform of `pipeline` in order.
+pub fn apply_pipeline(pipeline: &[Transform], value: Value, locale: Option<NumberLocale>) -> Value {
+    pipeline
+        .iter()
+        .fold(value, |value, transform| transform.apply(value, locale))
+}
+
+pub(crate) fn strip_currency(text: &str) -> String {
+    let without_symbols: String = text
+        .chars()
+        .filter(|c| !CURRENCY_SYMBOLS.contains(c))
+        .collect();
+    let mut amount = without_symbols.trim();
+    if let Some((code, rest)) = amount.split_once(char::is_whitespace) {
+        if is_currency_code(code) {
+            amount = rest.trim_start();
+        }
+    }
+    if let Some((rest, code)) = amount.rsplit_once(char::is_whitespace) {
+        if is_currency_code(code) {
+            amount = rest.trim_end();
+        }
+    }
+    amount.to_string()
+}
+
+fn is_currency_code(word: &str) -> bool {
+    word.len() == 3 && word.bytes().all(|b| b.is_ascii_uppercase())
+}
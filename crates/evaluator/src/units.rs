@@ -0,0 +1,121 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::numbers::{parse_localized, NumberLocale};
+use crate::synonyms::SynonymMap;
+
+/// Share of a unit-bearing field's score that comes from its unit; the rest comes from the
+/// number.
+pub const UNIT_WEIGHT: f64 = 0.25;
+
+/// A number with the unit written after it, e.g. `1500 kg`. The unit is kept as written,
+/// trimmed; it is empty for a bare number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub amount: f64,
+    pub unit: String,
+}
+
+impl Quantity {
+    /// Reads a JSON number, or a string holding a number followed by an optional unit. The
+    /// number follows `locale` conventions when one is given.
+    pub fn from_json(value: &Value, locale: Option<NumberLocale>) -> Option<Self> {
+        if let Some(amount) = value.as_f64() {
+            return Some(Self {
+                amount,
+                unit: String::new(),
+            });
+        }
+        let text = value.as_str()?.trim();
+        let unit_start = text
+            .char_indices()
+            .find(|&(_, c)| !is_number_char(c))
+            .map_or(text.len(), |(index, _)| index);
+        let (number, unit) = text.split_at(unit_start);
+        let amount = match locale {
+            Some(locale) => parse_localized(number, locale)?,
+            None => number.trim().parse().ok()?,
+        };
+        Some(Self {
+            amount,
+            unit: unit.trim().to_string(),
+        })
+    }
+}
+
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit()
+        || matches!(
+            c,
+            '.' | ',' | '+' | '-' | '\'' | ' ' | '\u{a0}' | '\u{202f}'
+        )
+}
+
+/// How the units of an `x-unit` field compare: `conversions` maps each unit to its factor
+/// relative to a shared base unit (`{"kg": 1, "t": 1000}`), and `synonyms` declares other
+/// spellings of a unit (`{"kg": ["kilogram"]}`). Units match case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitSpec {
+    conversions: BTreeMap<String, f64>,
+    synonyms: SynonymMap,
+}
+
+impl UnitSpec {
+    /// Reads `true`, or an object with optional `conversions` and `synonyms`.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(true) => Some(Self::default()),
+            Value::Object(map) => {
+                let mut spec = Self {
+                    synonyms: match map.get("synonyms") {
+                        Some(synonyms) => SynonymMap::from_json(synonyms).ok()?,
+                        None => SynonymMap::default(),
+                    },
+                    ..Self::default()
+                };
+                if let Some(conversions) = map.get("conversions") {
+                    for (unit, factor) in conversions.as_object()? {
+                        let factor = factor.as_f64().filter(|factor| *factor > 0.0)?;
+                        spec.conversions.insert(spec.canonical(unit), factor);
+                    }
+                }
+                Some(spec)
+            }
+            _ => None,
+        }
+    }
+
+    /// The unit's canonical spelling: its declared synonym group, or the unit lowercased.
+    fn canonical(&self, unit: &str) -> String {
+        self.synonyms
+            .canonical(unit)
+            .map_or_else(|| unit.trim().to_lowercase(), str::to_string)
+    }
+
+    fn factor(&self, unit: &str) -> Option<f64> {
+        self.conversions.get(&self.canonical(unit)).copied()
+    }
+
+    /// The amount in the base unit when the quantity's unit has a conversion, as written
+    /// otherwise.
+    pub fn base_amount(&self, quantity: &Quantity) -> f64 {
+        quantity.amount * self.factor(&quantity.unit).unwrap_or(1.0)
+    }
+
+    /// 1.0 when both units are the same after synonyms, or both convert to the base unit;
+    /// 0.0 otherwise.
+    pub fn unit_similarity(&self, expected: &Quantity, predicted: &Quantity) -> f64 {
+        let convertible =
+            self.factor(&expected.unit).is_some() && self.factor(&predicted.unit).is_some();
+        if convertible || self.canonical(&expected.unit) == self.canonical(&predicted.unit) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Combines the number and unit scores of a unit-bearing field.
+pub fn quantity_score(number_score: f64, unit_score: f64) -> f64 {
+    (1.0 - UNIT_WEIGHT) * number_score + UNIT_WEIGHT * unit_score
+}
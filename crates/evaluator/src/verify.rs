@@ -0,0 +1,85 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::options::FieldMinimum;
+
+/// Thresholds re-checked against an archived metrics file by `pdf_eval verify`.
+#[derive(Debug, Clone, Default)]
+pub struct Thresholds {
+    /// Minimum `overall_score`.
+    pub min_score: Option<f64>,
+    /// Minimum `document_coverage`.
+    pub min_coverage: Option<f64>,
+    /// Minimum mean scores of critical fields, read from the file's `field_minimums`.
+    pub field_minimums: Vec<FieldMinimum>,
+}
+
+/// Outcome of one threshold against an archived metrics file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThresholdCheck {
+    /// The headline metric (`overall_score`, `document_coverage`) or field pattern checked.
+    pub name: String,
+    pub threshold: f64,
+    /// The value recorded in the file, or `None` when the file does not record it.
+    pub value: Option<f64>,
+    /// Whether `value` reaches `threshold`. A value the file does not record fails.
+    pub passed: bool,
+}
+
+impl ThresholdCheck {
+    fn new(name: &str, threshold: f64, value: Option<f64>) -> Self {
+        Self {
+            name: name.to_string(),
+            threshold,
+            value,
+            passed: value.is_some_and(|value| value >= threshold),
+        }
+    }
+
+    /// `name (value < threshold)`, or `name (not recorded)`, for a failed check.
+    pub fn describe_failure(&self) -> String {
+        match self.value {
+            Some(value) => format!("{} ({value} < {})", self.name, self.threshold),
+            None => format!("{} (not recorded)", self.name),
+        }
+    }
+}
+
+/// Checks the metrics of a pdf_eval metrics file (or of one `--append` run-log entry, with
+/// the metrics under `metrics`) against `thresholds`, in the order headline metrics, then
+/// fields. A field's value is its `mean_score` in the file's `field_minimums`, so only fields
+/// checked with `--field-min` or `x-min-score` when the run was scored can be re-checked.
+pub fn verify_metrics(metrics: &Value, thresholds: &Thresholds) -> Vec<ThresholdCheck> {
+    let metrics = metrics.get("metrics").unwrap_or(metrics);
+    let headline = |name: &str| metrics.get(name).and_then(Value::as_f64);
+    let mut checks = Vec::new();
+    if let Some(threshold) = thresholds.min_score {
+        checks.push(ThresholdCheck::new(
+            "overall_score",
+            threshold,
+            headline("overall_score"),
+        ));
+    }
+    if let Some(threshold) = thresholds.min_coverage {
+        checks.push(ThresholdCheck::new(
+            "document_coverage",
+            threshold,
+            headline("document_coverage"),
+        ));
+    }
+    let recorded = metrics.get("field_minimums").and_then(Value::as_array);
+    for minimum in &thresholds.field_minimums {
+        let mean_score = recorded
+            .into_iter()
+            .flatten()
+            .find(|check| check.get("field").and_then(Value::as_str) == Some(&minimum.field))
+            .and_then(|check| check.get("mean_score"))
+            .and_then(Value::as_f64);
+        checks.push(ThresholdCheck::new(
+            &minimum.field,
+            minimum.min_score,
+            mean_score,
+        ));
+    }
+    checks
+}
@@ -0,0 +1,64 @@
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::http::{exchange, Endpoint};
+
+/// Number of times a POST is attempted before giving up.
+pub const WEBHOOK_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; later retries wait proportionally longer.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("unsupported webhook URL '{0}' (expected http://host[:port]/path)")]
+    UnsupportedUrl(String),
+    #[error("POST to {url} failed after {attempts} attempts: {reason}")]
+    Failed {
+        url: String,
+        attempts: u32,
+        reason: String,
+    },
+}
+
+/// POSTs `payload` as `application/json` to a plain `http://` URL, retrying failed attempts.
+///
+/// Any non-2xx response counts as a failure. The client is deliberately minimal: one
+/// HTTP/1.1 request per connection, no redirects and no TLS.
+pub fn post_json(url: &str, payload: &str) -> Result<(), WebhookError> {
+    let endpoint =
+        Endpoint::parse(url).ok_or_else(|| WebhookError::UnsupportedUrl(url.to_string()))?;
+    let mut reason = String::new();
+    for attempt in 1..=WEBHOOK_ATTEMPTS {
+        match send(&endpoint, payload) {
+            Ok(()) => return Ok(()),
+            Err(err) => reason = err,
+        }
+        if attempt < WEBHOOK_ATTEMPTS {
+            thread::sleep(RETRY_DELAY * attempt);
+        }
+    }
+    Err(WebhookError::Failed {
+        url: url.to_string(),
+        attempts: WEBHOOK_ATTEMPTS,
+        reason,
+    })
+}
+
+fn send(endpoint: &Endpoint, payload: &str) -> Result<(), String> {
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        endpoint.path,
+        endpoint.host,
+        endpoint.port,
+        payload.len(),
+        payload
+    );
+    let response = exchange(endpoint, &request)?;
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(format!("server answered '{}'", response.status_line))
+    }
+}
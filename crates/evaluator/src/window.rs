@@ -0,0 +1,221 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::error::EvaluationError;
+use crate::evaluator::{parse_timestamp_millis, selected_documents, Document, MetricsAccumulator};
+use crate::metrics::{round, EvaluationMetrics};
+use crate::options::EvaluationOptions;
+
+/// Metrics over the last `capacity` ground-truth documents added, for monitoring drift in
+/// a stream. Each document's contribution is kept apart, so adding a document to a full
+/// window evicts the oldest one's contribution without rescoring the others. The metrics
+/// are those of a [`MetricsAccumulator`] fed the documents currently in the window, in the
+/// order they were added.
+#[derive(Debug, Clone)]
+pub struct WindowedAccumulator {
+    options: EvaluationOptions,
+    capacity: usize,
+    window: VecDeque<MetricsAccumulator>,
+}
+
+impl WindowedAccumulator {
+    /// A window of at most `capacity` documents (at least one).
+    pub fn new(options: EvaluationOptions, capacity: usize) -> Self {
+        Self {
+            options,
+            capacity: capacity.max(1),
+            window: VecDeque::new(),
+        }
+    }
+
+    /// Scores one ground-truth document against its prediction, if any, evicting the oldest
+    /// document when the window is full. A document that fails to score leaves the window
+    /// unchanged.
+    pub fn add_document(
+        &mut self,
+        gt_doc: &Document,
+        pred_doc: Option<&Document>,
+    ) -> Result<(), EvaluationError> {
+        let mut contribution = MetricsAccumulator::new(self.options.clone());
+        contribution.add_document(gt_doc, pred_doc)?;
+        if self.window.len() == self.capacity {
+            self.window.pop_front();
+        }
+        self.window.push_back(contribution);
+        Ok(())
+    }
+
+    /// Number of documents in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// Metrics over the documents in the window; [`EvaluationError::EmptyInput`] before
+    /// the first document.
+    pub fn snapshot(&self) -> Result<EvaluationMetrics, EvaluationError> {
+        let mut totals = MetricsAccumulator::new(self.options.clone());
+        for contribution in &self.window {
+            totals.absorb(contribution);
+        }
+        totals.finalize()
+    }
+}
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Overall score with each document weighted by its recency, with `--half-life`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DecayedScore {
+    /// Age, in units of the `--decay-by` field, at which a document counts half as much as
+    /// the newest one.
+    pub half_life: f64,
+    /// Weighted mean of the per-document overall scores.
+    pub overall_score: f64,
+    /// Sum of the document weights: how many documents the score effectively rests on.
+    pub effective_documents: f64,
+    /// Ground-truth documents left out because their `--decay-by` value is missing or not a
+    /// number, date or timestamp.
+    pub undated_documents: u32,
+}
+
+/// Overall score over a stream of documents in which each document's contribution halves
+/// with every `half_life` it is older than the newest document added, so recent quality
+/// drift shows sooner than in an all-time average. Each document is scored on its own when
+/// added and only its overall score is kept; ages are measured from the newest document
+/// when the score is read, so documents may arrive out of order.
+#[derive(Debug, Clone)]
+pub struct DecayedAccumulator {
+    options: EvaluationOptions,
+    half_life: f64,
+    scores: Vec<(f64, f64)>,
+}
+
+impl DecayedAccumulator {
+    /// `half_life` is in the same units as the times later passed to
+    /// [`add_document`](Self::add_document).
+    pub fn new(options: EvaluationOptions, half_life: f64) -> Self {
+        Self {
+            options,
+            half_life,
+            scores: Vec::new(),
+        }
+    }
+
+    /// Scores one ground-truth document against its prediction, if any, as ingested at
+    /// `time` (an ingestion index, or days for timestamps).
+    pub fn add_document(
+        &mut self,
+        gt_doc: &Document,
+        pred_doc: Option<&Document>,
+        time: f64,
+    ) -> Result<(), EvaluationError> {
+        let mut contribution = MetricsAccumulator::new(self.options.clone());
+        contribution.add_document(gt_doc, pred_doc)?;
+        let score = contribution.finalize()?.overall_score;
+        self.scores.push((time, score));
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// The decayed score of the documents added so far; [`EvaluationError::EmptyInput`]
+    /// before the first document.
+    pub fn snapshot(&self) -> Result<DecayedScore, EvaluationError> {
+        let newest = self
+            .scores
+            .iter()
+            .map(|(time, _)| *time)
+            .max_by(f64::total_cmp)
+            .ok_or(EvaluationError::EmptyInput)?;
+        let (mut weighted, mut weights) = (0.0, 0.0);
+        for (time, score) in &self.scores {
+            let weight = 0.5_f64.powf((newest - time) / self.half_life);
+            weighted += weight * score;
+            weights += weight;
+        }
+        Ok(DecayedScore {
+            half_life: self.half_life,
+            overall_score: round(weighted / weights),
+            effective_documents: round(weights),
+            undated_documents: 0,
+        })
+    }
+}
+
+/// Parses a `--half-life`: a positive, finite number.
+pub fn parse_half_life(value: &str) -> Result<f64, String> {
+    let half_life: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid half-life '{value}'"))?;
+    if !(half_life.is_finite() && half_life > 0.0) {
+        return Err(format!("half-life must be positive, got '{value}'"));
+    }
+    Ok(half_life)
+}
+
+/// Scores the ground-truth documents with [`DecayedAccumulator`], taking each document's
+/// time from the value at `path` (dot-separated) in its ground truth: numbers, such as an
+/// ingestion index, as written, and dates or timestamps in days, so `half_life` is in days
+/// for them. Documents without such a value are counted in `undated_documents`;
+/// predictions without a ground-truth document have no time and are left out.
+pub fn evaluate_decayed(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+    path: &str,
+    half_life: f64,
+) -> Result<DecayedScore, EvaluationError> {
+    let selection = selected_documents(ground_truth, predictions, options);
+    let (ground_truth, predictions) = (&selection.ground_truth, &selection.predictions);
+    let mut accumulator = DecayedAccumulator::new(options.clone(), half_life);
+    let mut undated_documents = 0;
+    for (doc_id, gt_doc) in ground_truth.iter() {
+        match document_time(&gt_doc.fields, path) {
+            Some(time) => accumulator.add_document(gt_doc, predictions.get(doc_id), time)?,
+            None => undated_documents += 1,
+        }
+    }
+    Ok(DecayedScore {
+        undated_documents,
+        ..accumulator.snapshot()?
+    })
+}
+
+fn document_time(fields: &Value, path: &str) -> Option<f64> {
+    let value = path
+        .split('.')
+        .try_fold(fields, |node, segment| match node {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })?;
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let text = text.trim();
+            if let Ok(number) = text.parse::<f64>() {
+                return number.is_finite().then_some(number);
+            }
+            let millis = parse_timestamp_millis(text).or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| date.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+            })?;
+            Some(millis as f64 / MILLIS_PER_DAY)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,192 @@
+use pdf_eval::evaluator::{
+    evaluate_predictions_with_options, load_ground_truth_from_embed, load_ground_truth_from_path,
+    load_predictions, Document, MetricsAccumulator,
+};
+use pdf_eval::options::{CoverageMode, EvaluationOptions};
+use pdf_eval::window::{DecayedAccumulator, WindowedAccumulator};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/fixtures")
+        .join(name)
+}
+
+/// Feeds documents in reverse id order, to check that the result does not depend on it.
+fn accumulate(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+    options: &EvaluationOptions,
+) -> MetricsAccumulator {
+    let mut accumulator = MetricsAccumulator::new(options.clone());
+    for (doc_id, gt_doc) in ground_truth.iter().rev() {
+        accumulator
+            .add_document(gt_doc, predictions.get(doc_id))
+            .unwrap();
+    }
+    for (doc_id, pred_doc) in predictions.iter().rev() {
+        if !ground_truth.contains_key(doc_id) {
+            accumulator.add_extra_prediction(pred_doc).unwrap();
+        }
+    }
+    accumulator
+}
+
+fn assert_accumulated_matches_batch(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &str,
+    options: &EvaluationOptions,
+) {
+    let predictions = load_predictions(&fixture_path(predictions)).unwrap();
+    let batch = evaluate_predictions_with_options(ground_truth, &predictions, options).unwrap();
+    let accumulated = accumulate(ground_truth, &predictions, options)
+        .finalize()
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&accumulated).unwrap(),
+        serde_json::to_value(&batch).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(&accumulated.documents).unwrap(),
+        serde_json::to_value(&batch.documents).unwrap()
+    );
+}
+
+#[test]
+fn accumulated_metrics_match_batch() {
+    assert_accumulated_matches_batch(
+        &load_ground_truth_from_path(&fixture_path("dummy_ground_truth.json")).unwrap(),
+        "dummy_predictions.json",
+        &EvaluationOptions::default(),
+    );
+    assert_accumulated_matches_batch(
+        &load_ground_truth_from_path(&fixture_path("bias_ground_truth.json")).unwrap(),
+        "bias_predictions.json",
+        &EvaluationOptions::default(),
+    );
+}
+
+#[test]
+fn accumulated_metrics_match_batch_with_missing_documents() {
+    let options = EvaluationOptions {
+        coverage_mode: CoverageMode::Weighted,
+        ..EvaluationOptions::default()
+    };
+    assert_accumulated_matches_batch(
+        &load_ground_truth_from_embed().unwrap(),
+        "text_heavy_predictions.json",
+        &options,
+    );
+}
+
+#[test]
+fn snapshot_reports_documents_added_so_far() {
+    let ground_truth =
+        load_ground_truth_from_path(&fixture_path("dummy_ground_truth.json")).unwrap();
+    let predictions = load_predictions(&fixture_path("dummy_predictions.json")).unwrap();
+    let options = EvaluationOptions::default();
+    let (doc_id, gt_doc) = ground_truth.iter().next().unwrap();
+
+    let mut accumulator = MetricsAccumulator::new(options.clone());
+    assert!(accumulator.snapshot().is_err());
+    accumulator
+        .add_document(gt_doc, predictions.get(doc_id))
+        .unwrap();
+
+    let first_only = BTreeMap::from([(doc_id.clone(), gt_doc.clone())]);
+    let first_predictions: BTreeMap<_, _> = predictions
+        .iter()
+        .filter(|(id, _)| *id == doc_id)
+        .map(|(id, document)| (id.clone(), document.clone()))
+        .collect();
+    let batch =
+        evaluate_predictions_with_options(&first_only, &first_predictions, &options).unwrap();
+    assert_eq!(
+        serde_json::to_value(accumulator.snapshot().unwrap()).unwrap(),
+        serde_json::to_value(&batch).unwrap()
+    );
+}
+
+#[test]
+fn windowed_metrics_match_fresh_accumulation_over_the_window() {
+    let ground_truth =
+        load_ground_truth_from_path(&fixture_path("bias_ground_truth.json")).unwrap();
+    let predictions = load_predictions(&fixture_path("bias_predictions.json")).unwrap();
+    let options = EvaluationOptions::default();
+    // Three documents through a window of two: the last one evicts the first.
+    let documents: Vec<&Document> = ground_truth.values().collect();
+    assert_eq!(documents.len(), 3);
+
+    let mut window = WindowedAccumulator::new(options.clone(), 2);
+    assert!(window.snapshot().is_err());
+    for (added, gt_doc) in documents.iter().enumerate() {
+        window
+            .add_document(gt_doc, predictions.get(&gt_doc.document_id))
+            .unwrap();
+        assert_eq!(window.len(), (added + 1).min(2));
+
+        let mut fresh = MetricsAccumulator::new(options.clone());
+        for gt_doc in &documents[(added + 1).saturating_sub(2)..=added] {
+            fresh
+                .add_document(gt_doc, predictions.get(&gt_doc.document_id))
+                .unwrap();
+        }
+        let windowed = window.snapshot().unwrap();
+        let fresh = fresh.finalize().unwrap();
+        assert_eq!(windowed.num_documents, fresh.num_documents);
+        assert_eq!(
+            serde_json::to_value(&windowed).unwrap(),
+            serde_json::to_value(&fresh).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&windowed.documents).unwrap(),
+            serde_json::to_value(&fresh.documents).unwrap()
+        );
+    }
+}
+
+#[test]
+fn decayed_accumulator_lets_newer_documents_dominate() {
+    let ground_truth =
+        load_ground_truth_from_path(&fixture_path("decay_ground_truth.json")).unwrap();
+    let predictions = load_predictions(&fixture_path("decay_predictions.json")).unwrap();
+    let options = EvaluationOptions::default();
+    let good = &ground_truth["doc-1"];
+    let bad = &ground_truth["doc-4"];
+    let score_of = |gt_doc: &Document| {
+        let mut accumulator = MetricsAccumulator::new(options.clone());
+        accumulator
+            .add_document(gt_doc, predictions.get(&gt_doc.document_id))
+            .unwrap();
+        accumulator.finalize().unwrap().overall_score
+    };
+    let (good_score, bad_score) = (score_of(good), score_of(bad));
+    assert!(good_score > bad_score);
+
+    let mut decayed = DecayedAccumulator::new(options.clone(), 1.0);
+    assert!(decayed.snapshot().is_err());
+    for time in 0..4 {
+        decayed
+            .add_document(good, predictions.get("doc-1"), time as f64)
+            .unwrap();
+    }
+    decayed
+        .add_document(bad, predictions.get("doc-4"), 10.0)
+        .unwrap();
+    let recent_bad = decayed.snapshot().unwrap();
+    // Four good documents against one bad one, but the good ones are 7+ half-lives old.
+    assert!((recent_bad.overall_score - bad_score).abs() < 0.05);
+    assert!(recent_bad.effective_documents < 1.02);
+
+    // Equal ages weigh every document the same, as an all-time average does.
+    let mut flat = DecayedAccumulator::new(options, 1.0);
+    flat.add_document(good, predictions.get("doc-1"), 0.0)
+        .unwrap();
+    flat.add_document(bad, predictions.get("doc-4"), 0.0)
+        .unwrap();
+    let flat = flat.snapshot().unwrap();
+    assert_eq!(flat.effective_documents, 2.0);
+    assert!((flat.overall_score - (good_score + bad_score) / 2.0).abs() < 1e-4);
+}
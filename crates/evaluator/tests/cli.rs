@@ -55,3 +55,2905 @@ fn cli_prints_template() {
         .stdout(predicate::str::contains("\"items\""))
         .stdout(predicate::str::contains("\"pending_description\""));
 }
+
+#[test]
+fn cli_component_weighting_equal_by_default() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("text_heavy_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("text_heavy_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.875"));
+}
+
+#[test]
+fn cli_component_weighting_by_count() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("text_heavy_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("text_heavy_predictions.json"))
+        .arg("--component-weighting")
+        .arg("by-count");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.9643"));
+}
+
+#[test]
+fn cli_reads_predictions_from_stdin() {
+    let payload = std::fs::read_to_string(fixture_path("dummy_predictions.json")).unwrap();
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions").arg("-").write_stdin(payload);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.8518"));
+}
+
+#[test]
+fn cli_requires_predictions_for_scoring() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--predictions (or --predict-json / --runs) is required for scoring",
+        ))
+        .stderr(predicate::str::contains("--list-fields"));
+}
+
+#[test]
+fn cli_rejects_stdin_for_both_inputs() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg("-")
+        .arg("--ground-truth")
+        .arg("-");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("only one of"));
+}
+
+#[test]
+fn cli_reports_systematic_biases() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("bias_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("bias_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"systematic_biases\""))
+        .stdout(predicate::str::contains("\"kind\": \"offset\""))
+        .stdout(predicate::str::contains("\"kind\": \"ratio\""))
+        .stdout(predicate::str::contains("\"field\": \"quantity\"").not());
+}
+
+#[test]
+fn cli_redacts_values_but_keeps_scores() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("bias_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("bias_predictions.json"))
+        .arg("--output-dir")
+        .arg(temp.path())
+        .arg("--redact");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"field\": \"subtotal\""))
+        .stdout(predicate::str::contains("\"mean\": \"****\""))
+        .stdout(predicate::str::contains("\"overall_score\": "));
+    temp.child("aggregate.json")
+        .assert(predicate::str::contains("\"mean\": \"****\""));
+}
+
+#[test]
+fn cli_intersection_only_ignores_unmatched_documents() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("text_heavy_predictions.json"))
+        .arg("--intersection-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"num_documents\": 1,"))
+        .stdout(predicate::str::contains("\"document_coverage\": 1.0"))
+        .stdout(predicate::str::contains("\"missing_documents\": [],"))
+        .stdout(predicate::str::contains("\"missing_documents\": 1,"));
+}
+
+#[test]
+fn cli_depth_decay_discounts_nested_fields() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("depth_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("depth_predictions.json"))
+            .args(extra);
+        cmd.assert()
+    };
+    run(&[]).success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.3333",
+    ));
+    run(&["--depth-decay", "1.0"])
+        .success()
+        .stdout(predicate::str::contains(
+            "\"text_field_similarity\": 0.3333",
+        ));
+    run(&["--depth-decay", "0.5"])
+        .success()
+        .stdout(predicate::str::contains(
+            "\"text_field_similarity\": 0.5714",
+        ));
+}
+
+#[test]
+fn cli_scores_declared_synonyms_as_equal() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("synonym_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("synonym_predictions.json"))
+        .arg("--synonyms")
+        .arg(fixture_path("synonyms.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.881"));
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains("\"score\": 1.0"))
+        .assert(predicate::str::contains("\"score\": 0.7619"));
+}
+
+#[test]
+fn cli_reads_synonyms_from_template() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("synonym_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("synonym_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("synonym_template.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.881"));
+}
+
+#[test]
+fn cli_reports_order_inversions() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("order_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("order_predictions.json"))
+        .arg("--check-order")
+        .arg("items");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"order_violations\""))
+        .stdout(predicate::str::contains("\"document_id\": \"doc-1\""))
+        .stdout(predicate::str::contains("\"matched_elements\": 3"))
+        .stdout(predicate::str::contains("\"document_id\": \"doc-2\"").not());
+}
+
+#[test]
+fn cli_appends_runs_to_jsonl_log() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let log = temp.child("runs.jsonl");
+    for _ in 0..2 {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .arg("--append")
+            .arg(log.path());
+        cmd.assert().success();
+    }
+    let contents = std::fs::read_to_string(log.path()).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(entry["timestamp"].is_string());
+        assert_eq!(entry["metrics"]["overall_score"], 0.8518);
+    }
+}
+
+#[test]
+fn cli_ignores_leading_zeros_in_flagged_identifiers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("identifier_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("identifier_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("identifier_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.8333",
+    ));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"customer_id\",\n      \"kind\": \"text\",\n      \"score\": 1.0",
+    ));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"zip_code\",\n      \"kind\": \"text\",\n      \"score\": 0.5",
+    ));
+}
+
+#[test]
+fn cli_compares_percent_fields_as_fractions() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("percent_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("percent_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("percent_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"normalized_percent_fields\""))
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 0.9975",
+        ));
+    // 0.2 vs "20%" and "22%" vs 0.22.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"numeric_field_similarity\": 1.0",
+    ));
+    // 0.2 vs "19%".
+    temp.child("doc-2.json").assert(predicate::str::contains(
+        "\"path\": \"discount\",\n      \"kind\": \"numeric\",\n      \"score\": 0.99",
+    ));
+}
+
+#[test]
+fn cli_applies_field_transform_pipelines() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("transform_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("transform_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("transform_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    // " USD 1,234.50 " and "$1234.5" both end up as 1234.5 after trim, strip-currency and
+    // parse-number, so the field is scored as a number.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"total\",\n      \"kind\": \"numeric\",\n      \"score\": 1.0",
+    ));
+    // The same values without a pipeline are compared as text.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"raw_total\",\n      \"kind\": \"text\",\n      \"score\": 0.5714",
+    ));
+}
+
+#[test]
+fn cli_scores_set_fields_by_overlap() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("set_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("set_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("set_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"num_fields\": 4,"));
+    let field = |path: &str, score: &str| {
+        format!("\"path\": \"{path}\",\n      \"kind\": \"text\",\n      \"score\": {score}")
+    };
+    // Two of the three tags; pallets A,A,B against A,B,B share two of four counted elements.
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains(field("tags", "0.6667")))
+        .assert(predicate::str::contains(field("pallets", "0.5")));
+    // Order never matters, and duplicates only count for multisets.
+    temp.child("doc-2.json")
+        .assert(predicate::str::contains(field("tags", "1.0")))
+        .assert(predicate::str::contains(field("pallets", "1.0")));
+}
+
+#[test]
+fn cli_scores_unit_bearing_fields() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("unit_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("unit_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("unit_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    let field = |path: &str, score: &str| {
+        format!("\"path\": \"{path}\",\n      \"kind\": \"numeric\",\n      \"score\": {score}")
+    };
+    // `1.5 t` converts to 1500 kg, and `meters` is a declared synonym of `m`.
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains(field("weight", "1.0")))
+        .assert(predicate::str::contains(field("length", "1.0")));
+    // The numbers match but `lb` has no conversion and `ft` is a different unit.
+    temp.child("doc-2.json")
+        .assert(predicate::str::contains(field("weight", "0.75")))
+        .assert(predicate::str::contains(field("length", "0.75")));
+}
+
+#[test]
+fn cli_reports_best_text_metric_per_field() {
+    let run = |extra: &[&str]| {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("metric_comparison_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("metric_comparison_predictions.json"))
+            .arg("--output-dir")
+            .arg(temp.path())
+            .args(extra);
+        cmd.assert().success();
+        temp
+    };
+    let compared = |path: &str, score: &str, path_score: &str, winner: &str| {
+        format!(
+            "\"path\": \"{path}\",\n      \"kind\": \"text\",\n      \"score\": {score},\n      \
+             \"text_metric_scores\": {{\n        \"gestalt\": {score},\n        \
+             \"hierarchical-path\": {path_score}\n      }},\n      \"best_text_metric\": \"{winner}\""
+        )
+    };
+    // Spaces around the separators only hurt gestalt; a plural last segment only hurts
+    // the path metric. Field scores still use the default gestalt metric.
+    let temp = run(&["--compare-text-metrics", "gestalt,hierarchical-path"]);
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains(compared(
+            "category",
+            "0.913",
+            "1.0",
+            "hierarchical-path",
+        )))
+        .assert(predicate::str::contains(compared(
+            "product", "0.9767", "0.6667", "gestalt",
+        )));
+    run(&[])
+        .child("doc-1.json")
+        .assert(predicate::str::contains("best_text_metric").not());
+}
+
+#[test]
+fn cli_excludes_ignore_sentinel_fields_from_metrics() {
+    let run = |ground_truth: &str, predictions: &str, extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path(ground_truth))
+            .arg("--predictions")
+            .arg(fixture_path(predictions))
+            .args(extra)
+            .args(["--run-id", "ignore"]);
+        let output = cmd.assert().success();
+        String::from_utf8(output.get_output().stdout.clone()).unwrap()
+    };
+    // Sentinel fields, and predictions at or below them, count exactly as if neither side
+    // had them.
+    let ignored = run(
+        "ignore_ground_truth.json",
+        "ignore_predictions.json",
+        &["--ignore-value", "__IGNORE__"],
+    );
+    let annotated = run(
+        "ignore_ground_truth_annotated.json",
+        "ignore_predictions_annotated.json",
+        &[],
+    );
+    assert_eq!(ignored, annotated);
+    assert!(ignored.contains("\"num_fields\": 4,"));
+    assert!(ignored.contains("\"extra_field_count\": 0,"));
+    // Without the flag the sentinel is an ordinary expected value.
+    let literal = run("ignore_ground_truth.json", "ignore_predictions.json", &[]);
+    assert!(literal.contains("\"num_fields\": 7,"));
+}
+
+#[test]
+fn cli_decodes_json_string_fields() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("json_string_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("json_string_predictions.json"))
+            .args(extra);
+        cmd.assert()
+    };
+    // The encoded `payment` is an extra text field and its parsed paths are missing.
+    run(&[])
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.4583"));
+    // `reference` looks like JSON but does not parse, so it stays text.
+    run(&["--parse-json-strings"])
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+    run(&[
+        "--template-file",
+        &fixture_path("json_string_template.json"),
+    ])
+    .success()
+    .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+}
+
+#[test]
+fn cli_scores_coordinates_by_distance() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("geo_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("geo_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("geo_template.json"))
+        .arg("--geo-tolerance-km")
+        .arg("0.5")
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"malformed_coordinate_fields\": {\n    \"doc-3\": [\n      \"site\"",
+    ));
+    // About 200 m away, within the tolerance.
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains("\"score\": 1.0"));
+    // Milan against Rome.
+    temp.child("doc-2.json")
+        .assert(predicate::str::contains("\"score\": 0.0021"));
+    // Missing longitude.
+    temp.child("doc-3.json")
+        .assert(predicate::str::contains("\"score\": 0.0"));
+}
+
+#[test]
+fn cli_remaps_predicted_paths_before_matching() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("remap_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("remap_predictions.json"))
+        .arg("--remap")
+        .arg("customer_name=customer.name")
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"))
+        .stdout(predicate::str::contains("\"extra_field_count\": 0"));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"remapped_fields\": {\n    \"customer_name\": \"customer.name\"\n  }",
+    ));
+}
+
+#[test]
+fn cli_resolves_key_aliases_before_matching() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("alias_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("alias_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("alias_template.json"))
+        .arg("--alias")
+        .arg("amount=total")
+        .arg("--output-dir")
+        .arg(temp.path());
+    // `amount` is aliased at every level; `supplier` through the template's x-aliases.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"))
+        .stdout(predicate::str::contains("\"missing_field_count\": 0"))
+        .stdout(predicate::str::contains("\"extra_field_count\": 0"));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"aliased_fields\": {\n    \"invoice.total\": \"invoice.amount\",\n    \"invoice.vendor\": \"invoice.supplier\",\n    \"items.0.total\": \"items.0.amount\"\n  }",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("alias_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("alias_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"missing_field_count\": 3"))
+        .stdout(predicate::str::contains("\"extra_field_count\": 3"));
+}
+
+#[test]
+fn cli_writes_field_coverage_matrix() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let matrix = temp.child("coverage.csv");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("coverage_matrix_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("coverage_matrix_predictions.json"))
+        .arg("--coverage-matrix")
+        .arg(matrix.path());
+    cmd.assert().success();
+    // Three field rows by three document columns; doc-3 has no prediction, and blank cells
+    // are fields that document's ground truth lacks.
+    let csv = std::fs::read_to_string(matrix.path()).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], "field,doc-1,doc-2,doc-3");
+    assert_eq!(rows[1], "invoice_number,1,1,0");
+    assert_eq!(rows[2], "total,0,1,");
+    assert_eq!(rows[3], "vendor,1,,0");
+}
+
+#[test]
+fn cli_coverage_matrix_keeps_fields_of_a_skipped_bucket() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let matrix = |flags: &[&str]| {
+        let matrix = temp.child("coverage.csv");
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("coverage_matrix_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("coverage_matrix_predictions.json"))
+            .arg("--coverage-matrix")
+            .arg(matrix.path())
+            .args(flags);
+        cmd.assert().success();
+        std::fs::read_to_string(matrix.path()).unwrap()
+    };
+    // Text fields under --only-numeric (and `total` under --only-text) are still present in
+    // the ground truth, so their cells must not read as blank.
+    let all = matrix(&[]);
+    assert_eq!(matrix(&["--only-numeric"]), all);
+    assert_eq!(matrix(&["--only-text"]), all);
+}
+
+#[test]
+fn cli_scores_null_numeric_fields_as_zero_when_requested() {
+    // `discount` is null vs 0 and `shipping` 0 vs null.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("null_zero_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("null_zero_predictions.json"))
+        .arg("--null-numeric-as-zero");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 1.0",
+        ))
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("null_zero_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("null_zero_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 0.0",
+        ))
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.0"));
+}
+
+#[test]
+fn cli_reports_phase_profile_only_when_requested() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("coverage_matrix_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("coverage_matrix_predictions.json"))
+        .arg("--profile");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let metrics: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let profile = metrics["profile"].as_object().unwrap();
+    let phases: Vec<&str> = profile.keys().map(String::as_str).collect();
+    assert_eq!(
+        phases,
+        ["flatten_ms", "numeric_ms", "set_diff_ms", "text_ms"]
+    );
+    assert!(profile.values().all(|ms| ms.as_f64().unwrap() >= 0.0));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("coverage_matrix_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("coverage_matrix_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"profile\"").not());
+}
+
+#[test]
+fn cli_warns_about_mojibake_predictions_without_failing() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("mojibake_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("mojibake_predictions.json"));
+    // `CafÃ©` and `lâ€™ordine` are UTF-8 read as Windows-1252; `lunedì` is genuine.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"mojibake_fields\": {\n    \"doc-1\": [\n      \"reference\",\n      \"vendor\"\n    ]\n  }",
+        ))
+        .stderr(predicate::str::contains(
+            "warning: doc-1: predicted field vendor looks mis-encoded (mojibake)",
+        ))
+        .stderr(predicate::str::contains("field reference looks mis-encoded"))
+        .stderr(predicate::str::contains("notes").not());
+}
+
+#[test]
+fn cli_matches_codes_with_their_labels_through_a_code_table() {
+    // doc-1 has code 2 vs "Paid", doc-2 "Cancelled" vs code "3".
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("code_table_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("code_table_predictions.json"))
+        .arg("--code-table")
+        .arg(format!("status={}", fixture_path("code_table_status.json")));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("code_table_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("code_table_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.0"))
+        .stdout(predicate::str::contains("\"overall_score\": 1.0").not());
+}
+
+#[test]
+fn cli_caps_scoring_to_the_first_documents_by_id() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("coverage_matrix_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("coverage_matrix_predictions.json"))
+        .arg("--max-documents")
+        .arg("2")
+        .arg("--output-dir")
+        .arg(temp.path());
+    // doc-3, which has no prediction, is past the cap, so nothing is missing.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"num_documents\": 2,"))
+        .stdout(predicate::str::contains("\"num_fields\": 5,"))
+        .stdout(predicate::str::contains("\"missing_documents\": [],"))
+        .stdout(predicate::str::contains(
+            "\"document_cap\": {\n    \"max_documents\": 2,\n    \"ground_truth_documents\": 3\n  }",
+        ));
+    temp.child("doc-1.json").assert(predicate::path::exists());
+    temp.child("doc-2.json").assert(predicate::path::exists());
+    temp.child("doc-3.json").assert(predicate::path::missing());
+}
+
+#[test]
+fn cli_scores_number_sequence_fields_by_their_numbers() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("number_sequence_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("number_sequence_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("number_sequence_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    // `package` is x-number-sequence, `label` holds the same values as plain text.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"package\",\n      \"kind\": \"text\",\n      \"score\": 1.0",
+    ));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"label\",\n      \"kind\": \"text\",\n      \"score\": 0.8235",
+    ));
+    // 6 vs 4 packs of 1.5 l: (1 - 2/6 + 1) / 2.
+    temp.child("doc-2.json").assert(predicate::str::contains(
+        "\"path\": \"package\",\n      \"kind\": \"text\",\n      \"score\": 0.8333",
+    ));
+}
+
+#[test]
+fn cli_verify_passes_when_stored_metrics_meet_thresholds() {
+    // overall_score 0.8518, document_coverage 1.0, subtotal mean 0.9333.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("verify")
+        .arg(fixture_path("verify_metrics.json"))
+        .arg("--min-score")
+        .arg("0.85")
+        .arg("--min-coverage")
+        .arg("1")
+        .arg("--field-min")
+        .arg("invoice.amounts.subtotal=0.9");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"name\": \"overall_score\",\n    \"threshold\": 0.85,\n    \"value\": 0.8518,\n    \"passed\": true",
+        ))
+        .stdout(predicate::str::contains(
+            "\"name\": \"invoice.amounts.subtotal\",\n    \"threshold\": 0.9,\n    \"value\": 0.9333,\n    \"passed\": true",
+        ));
+}
+
+#[test]
+fn cli_verify_fails_when_stored_metrics_miss_thresholds() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("verify")
+        .arg(fixture_path("verify_metrics.json"))
+        .arg("--min-score")
+        .arg("0.9")
+        .arg("--min-coverage")
+        .arg("1")
+        .arg("--field-min")
+        .arg("customer.name=0.5");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("\"passed\": false"))
+        .stderr(predicate::str::contains(
+            "thresholds not met: overall_score (0.8518 < 0.9), customer.name (not recorded)",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("verify").arg(fixture_path("verify_metrics.json"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "needs at least one of --min-score",
+    ));
+}
+
+#[test]
+fn cli_scores_reordered_address_lines_with_the_address_metric() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("address_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("address_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("address_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    // Both fields hold the same address with the company line moved; only
+    // `shipping_address` uses `x-text-metric: address`.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"shipping_address\",\n      \"kind\": \"text\",\n      \"score\": 1.0",
+    ));
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"billing_address\",\n      \"kind\": \"text\",\n      \"score\": 0.7805",
+    ));
+}
+
+#[test]
+fn cli_prints_empty_collections_as_null_when_requested() {
+    let run = |empty_as_null: bool| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("remap_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("remap_predictions.json"))
+            .arg("--remap")
+            .arg("customer_name=customer.name");
+        if empty_as_null {
+            cmd.arg("--empty-as-null");
+        }
+        let output = cmd.assert().success().get_output().stdout.clone();
+        serde_json::from_slice::<serde_json::Value>(&output).unwrap()
+    };
+
+    let default = run(false);
+    assert_eq!(default["missing_fields"], serde_json::json!({}));
+    assert_eq!(default["extra_fields"], serde_json::json!({}));
+    assert_eq!(default["missing_documents"], serde_json::json!([]));
+
+    let nulls = run(true);
+    for key in [
+        "missing_fields",
+        "extra_fields",
+        "missing_documents",
+        "extra_documents",
+    ] {
+        assert!(nulls[key].is_null(), "{key} should be null");
+    }
+    assert_eq!(nulls["overall_score"], default["overall_score"]);
+}
+
+#[test]
+fn cli_derives_numeric_tolerance_from_error_percentile() {
+    // Both fields have historical errors 0.1..=1.0; `subtotal` is off by 0.8, `total` by 0.9.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("error_baseline_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("error_baseline_predictions.json"))
+        .arg("--error-baseline")
+        .arg(fixture_path("error_baseline.json"))
+        .arg("--error-percentile")
+        .arg("80");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 0.9955",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("error_baseline_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("error_baseline_predictions.json"))
+        .arg("--error-baseline")
+        .arg(fixture_path("error_baseline.json"));
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 1.0",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("error_baseline_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("error_baseline_predictions.json"))
+        .arg("--error-baseline")
+        .arg(fixture_path("error_baseline.json"))
+        .arg("--error-percentile")
+        .arg("0");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "percentile must be greater than 0",
+    ));
+}
+
+#[test]
+fn cli_keeps_automatic_scores_when_interactive_review_has_no_terminal() {
+    // `vendor` scores 0.72, inside the default ambiguous band, but stdin is not a terminal.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let decisions = temp.child("decisions.json");
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("review_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("review_predictions.json"))
+        .arg("--interactive")
+        .arg("--review-decisions")
+        .arg(decisions.path())
+        .write_stdin("a\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.86"))
+        .stderr(predicate::str::contains(
+            "warning: --interactive needs a terminal; keeping automatic scores",
+        ))
+        .stderr(predicate::str::contains("[a/r/s]").not());
+    decisions.assert(predicate::str::contains("[]"));
+}
+
+#[test]
+fn cli_scores_scientific_notation_strings_as_numbers() {
+    // `"1.2e3"` vs `1200` and `"2.5E-3"` vs `0.0025`.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("scientific_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("scientific_predictions.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 1.0",
+        ))
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains("\"kind\": \"text\"").not());
+}
+
+#[test]
+fn cli_scores_scientific_strings_numerically_only_in_number_fields() {
+    // `reading` is typed `number`; `code` and `batch` are strings that only look like
+    // scientific notation, and `1e400` is out of range.
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("scientific_typed_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("scientific_typed_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("scientific_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let metrics: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(metrics["numeric_field_similarity"], 1.0);
+    assert_eq!(metrics["text_field_similarity"], 1.0);
+    let document: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(temp.child("doc-1.json").path()).unwrap())
+            .unwrap();
+    let kinds: Vec<(&str, &str)> = document["field_scores"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| {
+            (
+                field["path"].as_str().unwrap(),
+                field["kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [("batch", "text"), ("code", "text"), ("reading", "numeric")]
+    );
+}
+
+#[test]
+fn cli_reports_metrics_per_group_by_value() {
+    // Two Acme documents are exact, the Globex total is 40 for 50 and doc-4 has no vendor.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("group_by_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("group_by_predictions.json"))
+        .arg("--group-by")
+        .arg("vendor");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let metrics: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(metrics["overall_score"], 0.9875);
+    let groups = metrics["groups"].as_object().unwrap();
+    let names: Vec<&str> = groups.keys().map(String::as_str).collect();
+    assert_eq!(names, ["Acme", "Globex", "__ungrouped__"]);
+    assert_eq!(groups["Acme"]["num_documents"], 2);
+    assert_eq!(groups["Acme"]["overall_score"], 1.0);
+    assert_eq!(groups["Globex"]["num_documents"], 1);
+    assert_eq!(groups["Globex"]["numeric_field_similarity"], 0.8);
+    assert_eq!(groups["Globex"]["overall_score"], 0.95);
+    assert_eq!(groups["__ungrouped__"]["num_documents"], 1);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("group_by_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("group_by_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"groups\"").not());
+}
+
+#[test]
+fn cli_redacts_group_by_values() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("group_by_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("group_by_predictions.json"))
+        .arg("--group-by")
+        .arg("vendor")
+        .arg("--redact");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(!stdout.contains("Acme") && !stdout.contains("Globex"));
+    let metrics: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let groups = metrics["groups"].as_object().unwrap();
+    let acme = pdf_eval::redact::redacted_group("Acme");
+    assert!(acme.starts_with("group-"));
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups[&acme]["num_documents"], 2);
+    assert_eq!(groups["__ungrouped__"]["num_documents"], 1);
+}
+
+#[test]
+fn cli_bootstrap_interval_brackets_the_overall_score() {
+    let run = || {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("dummy_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .arg("--bootstrap")
+            .arg("200")
+            .arg("--seed")
+            .arg("7");
+        let output = cmd.assert().success().get_output().stdout.clone();
+        serde_json::from_slice::<serde_json::Value>(&output).unwrap()
+    };
+    let metrics = run();
+    let overall = metrics["overall_score"].as_f64().unwrap();
+    let interval = &metrics["confidence_interval"];
+    let (lower, upper) = (
+        interval["lower"].as_f64().unwrap(),
+        interval["upper"].as_f64().unwrap(),
+    );
+    assert!(lower < upper, "{interval}");
+    assert!(
+        lower <= overall && overall <= upper,
+        "{overall} outside {interval}"
+    );
+    assert_eq!(interval["level"], 0.95);
+    assert_eq!(interval["resamples"], 200);
+    assert_eq!(interval["seed"], 7);
+    assert_eq!(run()["confidence_interval"], *interval);
+}
+
+#[test]
+fn cli_infers_field_types_from_ground_truth_values() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("infer_types_ground_truth.json"))
+        .arg("--infer-types");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let fields: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let field = |path: &str| {
+        fields
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|field| field["path"] == path)
+            .unwrap_or_else(|| panic!("{path} not inferred"))
+            .clone()
+    };
+
+    let quantity = field("items.*.quantity");
+    assert_eq!(quantity["inferred_type"], "number");
+    assert_eq!(quantity["confidence"], 1.0);
+    assert_eq!(quantity["sampled"], 3);
+    assert_eq!(
+        quantity["suggested_annotation"],
+        serde_json::json!({"type": "number"})
+    );
+
+    // Two ISO dates and one "unknown".
+    let issue_date = field("issue_date");
+    assert_eq!(issue_date["inferred_type"], "date");
+    assert_eq!(issue_date["confidence"], 0.6667);
+    assert_eq!(
+        issue_date["types"],
+        serde_json::json!({"date": 2, "text": 1})
+    );
+
+    assert_eq!(field("total")["inferred_type"], "currency");
+    assert_eq!(field("paid")["sampled"], 2);
+    assert_eq!(field("vendor")["inferred_type"], "text");
+}
+
+#[test]
+fn cli_aligns_sequence_fields_around_an_inserted_element() {
+    // The prediction inserts a "Shipping" row after the first of four line items.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("sequence_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("sequence_predictions.json"))
+        .arg("--sequence")
+        .arg("items");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let metrics: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(metrics["text_field_similarity"], 0.8);
+    assert_eq!(metrics["extra_field_count"], 0);
+    assert_eq!(
+        metrics["sequence_operations"]["items"],
+        serde_json::json!({"matches": 4, "substitutions": 0, "insertions": 1, "deletions": 0})
+    );
+
+    // Positionally, every row after the insertion is compared with the wrong one.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("sequence_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("sequence_predictions.json"));
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let metrics: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(metrics["overall_score"].as_f64().unwrap() < 0.8);
+    assert!(metrics.get("sequence_operations").is_none());
+}
+
+#[test]
+fn cli_explains_the_overall_score_arithmetic() {
+    for weighting in ["equal", "by-count"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("dummy_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .arg("--component-weighting")
+            .arg(weighting)
+            .arg("--explain-score");
+        let assert = cmd.assert().success();
+        let output = assert.get_output();
+        let metrics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let explanation = String::from_utf8(output.stderr.clone()).unwrap();
+
+        // Lines read `  <component> <value> * <weight> = <product>`.
+        let mut sum = 0.0;
+        let mut components = 0;
+        for line in explanation.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if let [component, value, "*", weight, "=", _] = words[..] {
+                let value: f64 = value.parse().unwrap();
+                assert!((value - metrics[component].as_f64().unwrap()).abs() < 5e-5);
+                sum += value * weight.parse::<f64>().unwrap();
+                components += 1;
+            }
+        }
+        assert_eq!(components, 4, "{explanation}");
+        let overall = metrics["overall_score"].as_f64().unwrap();
+        assert!((sum - overall).abs() < 5e-5, "{sum} vs {overall}");
+        assert!(explanation.contains(&format!("reported as {overall}")));
+    }
+}
+
+#[test]
+fn cli_strips_markup_before_comparing_flagged_fields() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("markup_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("markup_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("markup_template.json"))
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    // "<b>Total</b>" vs "Total" with strip-html.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"label\",\n      \"kind\": \"text\",\n      \"score\": 1.0",
+    ));
+    // "**Net 30**, see [terms](https://example.com/terms)" vs "Net 30, see terms" with
+    // strip-markdown.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"notes\",\n      \"kind\": \"text\",\n      \"score\": 1.0",
+    ));
+    // The same HTML without a pipeline keeps its tags.
+    temp.child("doc-1.json").assert(predicate::str::contains(
+        "\"path\": \"raw_label\",\n      \"kind\": \"text\",\n      \"score\": 0.5882",
+    ));
+}
+
+#[test]
+fn cli_writes_msgpack_metrics_that_verify_reads() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let json_output = temp.child("metrics.json");
+    let msgpack_output = temp.child("metrics.msgpack");
+    for (format, output) in [("json", &json_output), ("msgpack", &msgpack_output)] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .arg("--format")
+            .arg(format)
+            .arg("--run-id")
+            .arg("msgpack")
+            .arg("--output")
+            .arg(output.path());
+        cmd.assert().success();
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_output.path()).unwrap()).unwrap();
+    let encoded = std::fs::read(msgpack_output.path()).unwrap();
+    assert_eq!(pdf_eval::msgpack::from_msgpack(&encoded).unwrap(), json);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("verify")
+        .arg(msgpack_output.path())
+        .arg("--min-score")
+        .arg("0.85");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"name\": \"overall_score\",\n    \"threshold\": 0.85,\n    \"value\": 0.8518,\n    \"passed\": true",
+    ));
+}
+
+#[test]
+fn cli_reports_recency_decayed_score() {
+    // Only the newest batch is mispredicted, so it drags the decayed score well below the
+    // all-time one; doc-5 has no batch.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("decay_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("decay_predictions.json"))
+        .arg("--half-life")
+        .arg("1")
+        .arg("--decay-by")
+        .arg("batch");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.9334"))
+        .stdout(predicate::str::contains(
+            "\"decayed_score\": {\n    \"half_life\": 1.0,\n    \"overall_score\": 0.8289,\n    \"effective_documents\": 1.875,\n    \"undated_documents\": 1\n  }",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--half-life")
+        .arg("0");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("half-life must be positive"));
+}
+
+#[test]
+fn cli_only_numeric_and_only_text_ignore_the_skipped_bucket() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let original = std::fs::read_to_string(fixture_path("dummy_predictions.json")).unwrap();
+    let text_changed = temp.child("text_changed.json");
+    text_changed
+        .write_str(&original.replace("Acme Corporation", "Wrong Name Ltd"))
+        .unwrap();
+    let numbers_changed = temp.child("numbers_changed.json");
+    numbers_changed
+        .write_str(&original.replace("95.0", "12.0"))
+        .unwrap();
+
+    let run = |predictions: &str, flag: &str| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(predictions)
+            .arg(flag)
+            .args(["--run-id", "buckets"]);
+        String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+    };
+    let numeric = run(&fixture_path("dummy_predictions.json"), "--only-numeric");
+    assert_eq!(
+        run(text_changed.path().to_str().unwrap(), "--only-numeric"),
+        numeric
+    );
+    // Coverage and completeness keep their quarters; numeric similarity takes the half.
+    assert!(numeric.contains("\"numeric_field_similarity\": 0.7167"));
+    assert!(numeric.contains("\"overall_score\": 0.8333"));
+    assert!(numeric.contains("\"evaluated_buckets\": \"numeric\""));
+
+    let text = run(&fixture_path("dummy_predictions.json"), "--only-text");
+    assert_eq!(
+        run(numbers_changed.path().to_str().unwrap(), "--only-text"),
+        text
+    );
+    assert!(text.contains("\"evaluated_buckets\": \"text\""));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--only-numeric")
+        .arg("--only-text");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn cli_reports_template_required_fields_missing_from_predictions() {
+    // doc-1 lacks invoice.date on both sides, so only the template flags it; doc-2 is a
+    // receipt, which the typed template also requires a merchant.name for.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("required_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("required_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("required_template.json"))
+        .arg("--check-required");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"missing_field_count\": 1,"))
+        .stdout(predicate::str::contains(
+            "\"missing_required_fields\": {\n    \"doc-1\": [\n      \"invoice.date\"\n    ],\n    \"doc-2\": [\n      \"merchant.name\"\n    ]\n  }",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("required_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("required_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("required_template.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("missing_required_fields").not());
+}
+
+#[test]
+fn cli_lint_template_reports_authoring_problems() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--lint-template")
+        .arg("--template-file")
+        .arg(fixture_path("lint_bad_template.json"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("template has 6 problem(s)"))
+        .stderr(predicate::str::contains(
+            "items.*.code: x-text-metric must be a text metric name, got \"levenshtein\"",
+        ))
+        .stderr(predicate::str::contains(
+            "total: unknown annotation x-numric-tolerance",
+        ))
+        .stderr(predicate::str::contains(
+            "items.*.code: unknown type \"text\"",
+        ))
+        .stderr(predicate::str::contains(
+            "(root): required names unknown property vendor",
+        ))
+        .stderr(predicate::str::contains(
+            "(root): properties amount and total both resolve to the field total",
+        ))
+        .stderr(predicate::str::contains(
+            "x-text-metric at properties.customer.propertes.name is not inside a property schema",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--lint-template");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("[]"));
+}
+
+#[test]
+fn cli_scores_money_fields_by_amount_and_currency() {
+    let field_score = |score: &str| {
+        predicate::str::contains(format!(
+            "\"path\": \"total\",\n      \"kind\": \"numeric\",\n      \"score\": {score}"
+        ))
+    };
+    let run = |fx_rates: Option<&str>| {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("money_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("money_predictions.json"))
+            .arg("--template-file")
+            .arg(fixture_path("money_template.json"))
+            .arg("--output-dir")
+            .arg(temp.path());
+        if let Some(fx_rates) = fx_rates {
+            cmd.arg("--fx-rates").arg(fixture_path(fx_rates));
+        }
+        cmd.assert().success();
+        temp
+    };
+
+    // doc-1 matches after trimming and case-folding the code; doc-2 has the right amount in
+    // the wrong currency; doc-3 is 108 USD written as 100 EUR.
+    let temp = run(None);
+    temp.child("doc-1.json").assert(field_score("1.0"));
+    temp.child("doc-2.json").assert(field_score("0.75"));
+    temp.child("doc-3.json").assert(field_score("0.6944"));
+
+    // At EUR = 1.08, 100 EUR is 108 USD, while 250 EUR is no longer 250 USD.
+    let temp = run(Some("money_fx_rates.json"));
+    temp.child("doc-1.json").assert(field_score("1.0"));
+    temp.child("doc-2.json").assert(field_score("0.9444"));
+    temp.child("doc-3.json").assert(field_score("1.0"));
+}
+
+#[test]
+fn cli_diff_only_prints_just_the_mismatched_fields() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--diff-only");
+    let stdout = cmd.assert().success().get_output().stdout.clone();
+    let output: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+
+    let keys: Vec<&String> = output.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["field_diffs"]);
+    let documents = output["field_diffs"].as_array().unwrap();
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0]["document_id"], "doc-1");
+    assert_eq!(
+        documents[0]["fields"][0],
+        serde_json::json!({
+            "path": "customer.address.country",
+            "kind": "text",
+            "score": 0.25,
+            "expected": "USA",
+            "predicted": "United States",
+        })
+    );
+    // A field the prediction lacks has no predicted value; exact matches are left out.
+    let tax = &documents[1]["fields"][1];
+    assert_eq!(tax["path"], "invoice.amounts.tax");
+    assert!(tax.get("predicted").is_none());
+    assert!(documents
+        .iter()
+        .flat_map(|document| document["fields"].as_array().unwrap())
+        .all(|field| field["score"].as_f64().unwrap() < 1.0));
+    let stdout = String::from_utf8(stdout).unwrap();
+    assert!(!stdout.contains("overall_score"));
+    assert!(!stdout.contains("document_coverage"));
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn cli_loads_ground_truth_from_url() {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/truth.json", listener.local_addr().unwrap());
+    let body = std::fs::read_to_string(fixture_path("category_ground_truth.json")).unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0_u8; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let read = stream.read(&mut buffer).unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let response = format!(
+            "HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(&url)
+        .arg("--predictions")
+        .arg(fixture_path("category_ground_truth.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /truth.json HTTP/1.0"));
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn cli_reports_unreachable_ground_truth_url() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/truth.json", listener.local_addr().unwrap());
+    drop(listener);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(&url)
+        .arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "failed to load ground truth from http://",
+        ))
+        .stderr(predicate::str::contains("failed:"));
+}
+
+#[cfg(feature = "remote")]
+#[test]
+fn cli_rejects_https_ground_truth_without_tls() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg("https://127.0.0.1:9/truth.json")
+        .arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("https:// needs TLS"))
+        .stderr(predicate::str::contains("No such file").not());
+}
+
+#[cfg(not(feature = "remote"))]
+#[test]
+fn cli_ground_truth_url_requires_feature() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg("http://127.0.0.1:9/truth.json")
+        .arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("built with the `remote` feature"));
+}
+
+#[test]
+fn cli_trims_null_array_padding() {
+    let truth = r#"{"totals": [1, 2, 3, 4, 5]}"#;
+    let padded = r#"{"totals": [1, 2, 3, 4, 5, null, null]}"#;
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(truth)
+        .arg("--predict-json")
+        .arg(padded);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"extra_field_count\": 2,"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(truth)
+        .arg("--predict-json")
+        .arg(padded)
+        .arg("--trim-null-array-elements");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"extra_field_count\": 0,"))
+        .stdout(predicate::str::contains("\"overall_score\": 1.0,"));
+}
+
+#[test]
+fn cli_scores_rounded_embedded_numbers_numerically() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(r#"{"rate": "12.5%"}"#)
+        .arg("--predict-json")
+        .arg(r#"{"rate": "12.53%"}"#);
+    // Gestalt alone: 5 matching characters out of 5 + 6.
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.9091,",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(r#"{"rate": "12.5%"}"#)
+        .arg("--predict-json")
+        .arg(r#"{"rate": "12.53%"}"#)
+        .arg("--embedded-numbers");
+    // Half of 1 - 0.03 / 12.53 for the number, half of 1.0 for "#%".
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.9988,",
+    ));
+}
+
+#[test]
+fn cli_strict_coverage_fails_on_a_missing_document() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let truth = temp.child("truth.json");
+    truth
+        .write_str(
+            r#"[{"document_id": "doc-1", "fields": {"total": 1}},
+                {"document_id": "doc-2", "fields": {"total": 2}}]"#,
+        )
+        .unwrap();
+    let predictions = temp.child("predictions.json");
+    predictions
+        .write_str(r#"[{"document_id": "doc-1", "fields": {"total": 1}}]"#)
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(truth.path())
+        .arg("--predictions")
+        .arg(predictions.path());
+    cmd.assert().success();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(truth.path())
+        .arg("--predictions")
+        .arg(predictions.path())
+        .arg("--strict-coverage");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("\"document_coverage\": 0.5,"))
+        .stderr(predicate::str::contains(
+            "--strict-coverage: document_coverage is 0.5, missing documents: doc-2",
+        ));
+}
+
+#[test]
+fn cli_scores_duration_representations_alike() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("duration_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("duration_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("duration_template.json"));
+    // Each document pairs two of "2h30m", 9000 and "150 minutes".
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 1.0,",
+        ))
+        .stdout(predicate::str::contains("\"overall_score\": 1.0,"));
+
+    // Without the annotation the strings are unrelated text.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("duration_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("duration_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0,").not());
+}
+
+#[test]
+fn cli_reports_run_ids() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--run-id")
+        .arg("nightly-2024-05-01");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"run_id\": \"nightly-2024-05-01\",",
+    ));
+
+    let run_id = || {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"));
+        let stdout = cmd.assert().success().get_output().stdout.clone();
+        let metrics: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+        metrics["run_id"].as_str().unwrap().to_string()
+    };
+    let (first, second) = (run_id(), run_id());
+    assert_eq!(first.len(), 36);
+    assert_eq!(&first[14..15], "8");
+    assert_ne!(first, second);
+}
+
+#[test]
+fn cli_reports_numeric_outliers() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("outlier_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("outlier_predictions.json"))
+        .arg("--outlier-factor")
+        .arg("10");
+    // Both amounts are off by a decimal shift; quantity 3 vs 4 is a plain miss.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"predicted\": 123400.0,\n      \"factor\": 100.0",
+        ))
+        .stdout(predicate::str::contains(
+            "\"predicted\": 5.67,\n      \"factor\": 10.0",
+        ))
+        .stdout(predicate::str::contains("\"field\": \"quantity\"").not());
+}
+
+#[test]
+fn cli_treats_absent_and_empty_arrays_alike() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("empty_array_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("empty_array_predictions.json"));
+    // `items: []` on one side and no `items` on the other flatten to the same fields.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 1.0"))
+        .stdout(predicate::str::contains("\"missing_field_count\": 0"))
+        .stdout(predicate::str::contains("\"extra_field_count\": 0"));
+}
+
+#[test]
+fn cli_checks_output_against_snapshot() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let snapshot = temp.child("metrics.snapshot.json");
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("stop_words_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("stop_words_predictions.json"))
+            .arg("--template-file")
+            .arg(fixture_path("stop_words_template.json"))
+            .arg("--snapshot")
+            .arg(snapshot.path())
+            .args(extra);
+        cmd.assert()
+    };
+
+    run(&[])
+        .failure()
+        .stderr(predicate::str::contains("rerun with --update-snapshots"));
+    snapshot.assert(predicate::path::missing());
+
+    let written = run(&["--update-snapshots"]).success();
+    let stdout = String::from_utf8(written.get_output().stdout.clone()).unwrap();
+    assert_eq!(std::fs::read_to_string(snapshot.path()).unwrap(), stdout);
+
+    run(&[]).success();
+
+    // Dropping stop words raises the description score, so the snapshot no longer matches.
+    run(&["--stop-words", "english"])
+        .failure()
+        .stderr(predicate::str::contains("output differs from snapshot"))
+        .stderr(predicate::str::contains(
+            "-    6 |   \"text_field_similarity\": 0.8667,",
+        ))
+        .stderr(predicate::str::contains(
+            "+    6 |   \"text_field_similarity\": 0.9333,",
+        ));
+}
+
+#[test]
+fn cli_drops_stop_words_from_flagged_fields() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let custom = temp.child("stop_words.txt");
+    custom.write_str("the\nof\n").unwrap();
+    let run = |stop_words: &str, name: &str| {
+        let dir = temp.child(name);
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("stop_words_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("stop_words_predictions.json"))
+            .arg("--template-file")
+            .arg(fixture_path("stop_words_template.json"))
+            .args(["--stop-words", stop_words])
+            .arg("--output-dir")
+            .arg(dir.path());
+        cmd.assert().success();
+        std::fs::read_to_string(dir.child("doc-1.json").path()).unwrap()
+    };
+    let field = |path: &str, score: &str| {
+        format!("\"path\": \"{path}\",\n      \"kind\": \"text\",\n      \"score\": {score}")
+    };
+
+    // "Repair of the roof and the gutters" vs "Repair of roof and gutters".
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("stop_words_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("stop_words_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("stop_words_template.json"))
+        .arg("--explain-text")
+        .arg("doc-1:description");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"score\": 0.8667"));
+
+    let english = run("english", "english");
+    assert!(english.contains(&field("description", "1.0")));
+    // Only `description` carries `x-stop-words`.
+    assert!(english.contains(&field("notes", "0.8667")));
+
+    let listed = run(custom.path().to_str().unwrap(), "custom");
+    assert!(listed.contains(&field("description", "1.0")));
+}
+
+#[test]
+fn cli_credits_missing_optional_fields_only() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("optional_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("optional_predictions.json"))
+            .arg("--template-file")
+            .arg(fixture_path("optional_template.json"))
+            .args(extra);
+        cmd.assert()
+    };
+    run(&[])
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.0"));
+    // Only the optional `notes` field earns credit; the required `invoice_number` still scores 0.
+    run(&["--optional-missing-credit", "0.5"])
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.25"));
+}
+
+#[test]
+fn cli_compares_binary_fields_by_decoded_hash() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("binary_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("binary_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("binary_template.json"));
+    // `signature` decodes to the same bytes despite wrapping and missing padding, `image`
+    // differs, and `stamp` is not base64 at all.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"text_field_similarity\": 0.3333",
+        ))
+        .stdout(predicate::str::contains(
+            "\"malformed_binary_fields\": {\n    \"doc-1\": [\n      \"stamp\"\n    ]",
+        ));
+}
+
+#[test]
+fn cli_strict_numbers_distinguishes_large_integers() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("large_int_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("large_int_predictions.json"))
+            .args(extra);
+        cmd.assert()
+    };
+    // The two 19-digit ids collapse to the same f64, so float comparison calls them equal.
+    run(&[]).success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 1.0",
+    ));
+    run(&["--strict-numbers"])
+        .success()
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 0.5",
+        ));
+}
+
+#[test]
+fn cli_weights_text_fields_by_length() {
+    let run = |weighting: &str| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("depth_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("depth_predictions.json"))
+            .arg("--text-length-weighting")
+            .arg(weighting);
+        cmd.assert()
+    };
+    // Only the 4-character `vendor` matches; the 5-character `city` weighs a little more.
+    run("none")
+        .success()
+        .stdout(predicate::str::contains(
+            "\"text_field_similarity\": 0.3333",
+        ))
+        .stdout(predicate::str::contains("text_length_weighting").not());
+    run("length")
+        .success()
+        .stdout(predicate::str::contains(
+            "\"text_field_similarity\": 0.3077",
+        ))
+        .stdout(predicate::str::contains(
+            "\"text_length_weighting\": \"length\"",
+        ));
+    run("sqrt").success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.3207",
+    ));
+}
+
+#[test]
+fn cli_verifies_embedded_ground_truth() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--verify-ground-truth");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"verified\":true"))
+        .stdout(predicate::str::contains("\"ground_truth_sha256\""));
+}
+
+#[test]
+fn cli_digest_matches_build_info() {
+    let run = |args: &[&str]| {
+        let output = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    // The default build embeds the dummy ground truth.
+    let digest = run(&["digest", &fixture_path("dummy_ground_truth.json")]);
+    let info = run(&["--info"]);
+    assert_eq!(digest["ground_truth_sha256"], info["ground_truth_sha256"]);
+    assert_eq!(digest["document_count"], info["document_count"]);
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let empty = temp.child("empty.json");
+    empty.write_str("[]").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("digest").arg(empty.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Ground truth cannot be empty"));
+}
+
+#[test]
+fn cli_paranoid_mode_still_scores() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--paranoid");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.8518"));
+}
+
+#[test]
+fn cli_value_key_scores_wrapped_fields_like_bare_ones() {
+    let run = |predictions: &str, extra: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+            .arg("--predictions")
+            .arg(fixture_path(predictions))
+            .args(extra)
+            .args(["--run-id", "value-key"])
+            .output()
+            .unwrap()
+    };
+    let bare = run("dummy_predictions.json", &[]);
+    let wrapped = run("wrapped_predictions.json", &["--value-key", "value"]);
+    assert!(bare.status.success() && wrapped.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&wrapped.stdout),
+        String::from_utf8_lossy(&bare.stdout)
+    );
+}
+
+#[test]
+fn cli_parallel_scoring_matches_sequential() {
+    let run = |threads: &str| {
+        Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+            .arg("--predictions")
+            .arg(fixture_path("text_heavy_predictions.json"))
+            .args(["--coverage-mode", "weighted", "--parallel", threads])
+            .args(["--run-id", "parallel"])
+            .output()
+            .unwrap()
+    };
+    let sequential = run("1");
+    assert!(sequential.status.success());
+    for threads in ["2", "3", "0"] {
+        let parallel = run(threads);
+        assert!(parallel.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&parallel.stdout),
+            String::from_utf8_lossy(&sequential.stdout)
+        );
+    }
+}
+
+#[test]
+fn cli_reports_worst_fields_below_threshold() {
+    let run = |extra: &[&str]| {
+        let output = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+            .arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .arg("--worst-fields")
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    // Means: country 0.25, tax 0.5, name 0.72; the default threshold is 0.5.
+    let default = run(&["3"]);
+    assert!(default.contains("\"field\": \"customer.address.country\""));
+    assert!(!default.contains("\"field\": \"invoice.amounts.tax\""));
+
+    let strict = run(&["2", "--worst-field-threshold", "1"]);
+    let country = strict
+        .find("\"field\": \"customer.address.country\"")
+        .unwrap();
+    let tax = strict.find("\"field\": \"invoice.amounts.tax\"").unwrap();
+    assert!(country < tax);
+    assert!(!strict.contains("\"field\": \"customer.name\""));
+}
+
+#[test]
+fn cli_resumes_from_checkpoint_with_same_metrics() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let checkpoint = temp.child("checkpoint.json");
+    let ground_truth: Vec<serde_json::Value> = serde_json::from_str(
+        &std::fs::read_to_string(fixture_path("dummy_ground_truth.json")).unwrap(),
+    )
+    .unwrap();
+    let first_document = temp.child("first_document.json");
+    first_document
+        .write_str(&serde_json::to_string(&ground_truth[..1]).unwrap())
+        .unwrap();
+    let run = |ground_truth: &str, predictions: &str, extra: &[&str]| {
+        let output = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+            .arg("--ground-truth")
+            .arg(ground_truth)
+            .arg("--predictions")
+            .arg(fixture_path(predictions))
+            .args(extra)
+            .args(["--run-id", "checkpoint"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let full_ground_truth = fixture_path("dummy_ground_truth.json");
+    let checkpoint_path = checkpoint.path().to_str().unwrap();
+    let uninterrupted = run(&full_ground_truth, "dummy_predictions.json", &[]);
+
+    // A run interrupted after the first document.
+    run(
+        first_document.path().to_str().unwrap(),
+        "dummy_predictions.json",
+        &["--checkpoint", checkpoint_path],
+    );
+    let resumed = run(
+        &full_ground_truth,
+        "dummy_predictions.json",
+        &[
+            "--checkpoint",
+            checkpoint_path,
+            "--resume",
+            "--checkpoint-every",
+            "1",
+        ],
+    );
+    assert_eq!(resumed, uninterrupted);
+
+    // Checkpointed documents are not scored again: doc-1 keeps its revised-prediction score.
+    run(
+        first_document.path().to_str().unwrap(),
+        "dummy_predictions_revised.json",
+        &["--checkpoint", checkpoint_path],
+    );
+    let mixed = run(
+        &full_ground_truth,
+        "dummy_predictions.json",
+        &["--checkpoint", checkpoint_path, "--resume"],
+    );
+    assert_ne!(mixed, uninterrupted);
+}
+
+#[test]
+fn cli_fails_when_critical_field_is_below_minimum() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(fixture_path("dummy_predictions.json"))
+            .args(extra);
+        cmd.assert()
+    };
+    // Mean scores: customer.name 0.72, invoice.amounts.tax 0.5.
+    run(&["--field-min", "invoice.amounts.tax=0.5"])
+        .success()
+        .stdout(predicate::str::contains("\"passed\": true"));
+    run(&[
+        "--field-min",
+        "invoice.amounts.tax=0.5",
+        "--field-min",
+        "customer.name=0.9",
+    ])
+    .code(1)
+    .stdout(predicate::str::contains(
+        "\"field\": \"customer.name\",\n      \"min_score\": 0.9,\n      \"mean_score\": 0.72",
+    ))
+    .stderr(predicate::str::contains(
+        "critical fields below their minimum score: customer.name (0.72 < 0.9)",
+    ));
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let template = temp.child("template.json");
+    template
+        .write_str(
+            r#"{"properties": {"customer": {"properties": {"name": {"x-min-score": 0.8}}}}}"#,
+        )
+        .unwrap();
+    let template_path = template.path().to_str().unwrap();
+    run(&["--template-file", template_path])
+        .code(1)
+        .stderr(predicate::str::contains("customer.name (0.72 < 0.8)"));
+    // `--field-min` overrides the template minimum.
+    run(&[
+        "--template-file",
+        template_path,
+        "--field-min",
+        "customer.name=0.7",
+    ])
+    .success();
+}
+
+#[test]
+fn cli_skips_terminal_summary_when_piped() {
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"))
+        .arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("Evaluation summary"));
+}
+
+#[test]
+fn cli_compact_prints_single_line_json() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let output = temp.child("metrics.json");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--compact")
+        .arg("--run-id")
+        .arg("compact")
+        .arg("--output")
+        .arg(output.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "{\"run_id\":\"compact\",\"num_documents\":2,",
+        ))
+        .stdout(predicate::str::contains("\"overall_score\":0.8518"))
+        .stdout(predicate::function(|stdout: &str| {
+            stdout.lines().count() == 1
+        }));
+    output.assert(predicate::str::contains("\"overall_score\":0.8518"));
+}
+
+#[test]
+fn cli_explains_text_field_matching_blocks() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--explain-text")
+        .arg("doc-1:customer.address.country")
+        .arg("--compact");
+    // "USA" vs "United States": "U" at the start, then "S" of "States".
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"score\":0.25,\"gestalt_score\":0.25,\"matching_blocks\":[\
+         {\"expected_start\":0,\"predicted_start\":0,\"length\":1,\"matched_text\":\"U\"},\
+         {\"expected_start\":1,\"predicted_start\":7,\"length\":1,\"matched_text\":\"S\"}]",
+    ));
+}
+
+#[test]
+fn cli_explain_text_rejects_unknown_field() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--explain-text")
+        .arg("doc-1:customer.nickname");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "ground-truth document 'doc-1' has no field 'customer.nickname'",
+    ));
+}
+
+#[test]
+fn cli_summary_only_lists_documents_by_score() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("text_heavy_predictions.json"))
+        .arg("--summary-only");
+    cmd.assert().success().stdout(predicate::str::diff(
+        "document_id,overall_score\ndoc-2,0.0\ndoc-1,0.25\n",
+    ));
+}
+
+#[test]
+fn cli_applies_score_formula() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--score-formula")
+        .arg("0.5*text + 0.5*numeric");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"overall_score\": 0.7536"));
+}
+
+#[test]
+fn cli_rejects_unknown_formula_variable() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--score-formula")
+        .arg("0.5*txt + 0.5*numeric");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown variable 'txt'"));
+}
+
+#[test]
+fn cli_rejects_score_formula_dividing_by_zero() {
+    // A perfect prediction has coverage 1.0, so the denominator is zero.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(r#"{"total": 100}"#)
+        .arg("--predict-json")
+        .arg(r#"{"total": 100}"#)
+        .arg("--score-formula")
+        .arg("numeric / (1 - coverage)");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "invalid score formula: 'numeric / (1 - coverage)' divides by zero (coverage=1,",
+        ));
+}
+
+#[test]
+fn cli_previews_invalid_json() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let predictions = temp.child("predictions.json");
+    predictions
+        .write_str("[\n  {\n    \"document_id\": \"doc-1\",\n    \"fields\": {\"a\": 1,}\n  }\n]\n")
+        .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions").arg(predictions.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "trailing comma at line 4 column 23",
+        ))
+        .stderr(predicate::str::contains(
+            "> 4 |     \"fields\": {\"a\": 1,}",
+        ));
+}
+
+#[test]
+fn cli_points_at_invalid_json_after_multibyte_text() {
+    let line = "    \"fields\": {\"vendor\": \"Café Zürich – 東京\",}";
+    let temp = assert_fs::TempDir::new().unwrap();
+    let predictions = temp.child("predictions.json");
+    predictions
+        .write_str(&format!(
+            "[\n  {{\n    \"document_id\": \"doc-1\",\n{line}\n  }}\n]\n"
+        ))
+        .unwrap();
+    // The caret sits under the closing brace, the last character of the line.
+    let caret = format!(" | {}^\n", " ".repeat(line.chars().count() - 1));
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions").arg(predictions.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(format!("> 4 | {line}")))
+        .stderr(predicate::str::contains(caret));
+}
+
+#[test]
+fn cli_reports_changes_since_previous_predictions() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions_revised.json"))
+        .arg("--previous-predictions")
+        .arg(fixture_path("dummy_predictions.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"changed_fields\": 2"))
+        .stdout(predicate::str::contains("\"improved\": 1"))
+        .stdout(predicate::str::contains("\"worsened\": 1"))
+        .stdout(predicate::str::contains(
+            "\"field\": \"invoice.amounts.subtotal\"",
+        ));
+}
+
+#[test]
+fn cli_writes_per_document_output_dir() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let out_dir = temp.child("results");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--output-dir")
+        .arg(out_dir.path());
+    cmd.assert().success();
+
+    out_dir
+        .child("doc-1.json")
+        .assert(predicate::str::contains("\"document_id\": \"doc-1\""));
+    out_dir.child("doc-2.json").assert(predicate::str::contains(
+        "\"missing_fields\": [\n    \"invoice.amounts.tax\"",
+    ));
+    out_dir
+        .child("aggregate.json")
+        .assert(predicate::str::contains("\"overall_score\": 0.8518"));
+}
+
+#[test]
+fn cli_sanitizes_output_dir_file_names() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let ground_truth = temp.child("gt.json");
+    ground_truth
+        .write_str(r#"[{"document_id": "batch/01:a", "fields": {"total": 1}}]"#)
+        .unwrap();
+    let out_dir = temp.child("results");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(ground_truth.path())
+        .arg("--predictions")
+        .arg(ground_truth.path())
+        .arg("--output-dir")
+        .arg(out_dir.path());
+    cmd.assert().success();
+
+    out_dir
+        .child("batch_01_a.json")
+        .assert(predicate::str::contains("\"document_id\": \"batch/01:a\""));
+}
+
+#[test]
+fn cli_fails_when_output_dir_cannot_be_created() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let blocker = temp.child("not-a-dir");
+    blocker.write_str("").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--output-dir")
+        .arg(blocker.path().join("results"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "failed to write per-document results",
+    ));
+}
+
+#[test]
+fn cli_hierarchical_path_metric_gives_prefix_credit() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("category_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("category_predictions.json"))
+        .arg("--text-metric")
+        .arg("hierarchical-path");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.6667",
+    ));
+}
+
+#[test]
+fn cli_hierarchical_path_metric_from_template_annotation() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("category_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("category_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("category_template.json"));
+    // `category` is annotated (2/3), `label` keeps gestalt matching (0.8).
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"text_field_similarity\": 0.7333",
+    ));
+}
+
+#[test]
+fn cli_summarizes_repeated_runs() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--runs")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg(fixture_path("dummy_predictions_revised.json"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"num_runs\": 2"))
+        .stdout(predicate::str::contains("\"mean\": 0.8433"))
+        .stdout(predicate::str::contains("\"std_dev\": 0.0086"))
+        .stdout(predicate::str::contains("\"doc-2\""));
+}
+
+#[test]
+fn cli_lists_ground_truth_fields() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--list-fields");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"path\": \"invoice.amounts.subtotal\",\n    \"types\": [\n      \"number\"\n    ],\n    \"documents\": 2",
+        ))
+        .stdout(predicate::str::contains("\"path\": \"notes\""))
+        .stdout(predicate::str::contains("overall_score").not());
+}
+
+#[test]
+fn cli_dumps_resolved_field_types() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("field_types_ground_truth.json"))
+        .arg("--template-file")
+        .arg(fixture_path("field_types_template.json"))
+        .arg("--dump-field-types")
+        .arg("--compact");
+    let field = |path: &str, comparisons: &str, documents: u32| {
+        format!("{{\"path\":\"{path}\",\"comparisons\":[{comparisons}],\"documents\":{documents}}}")
+    };
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(field("vendor", "\"gestalt\"", 2)))
+        .stdout(predicate::str::contains(field(
+            "issued",
+            "\"timestamp\"",
+            2,
+        )))
+        .stdout(predicate::str::contains(field("tags", "\"set\"", 2)))
+        .stdout(predicate::str::contains(field("total", "\"number\"", 2)))
+        .stdout(predicate::str::contains(field("weight", "\"unit\"", 2)))
+        .stdout(predicate::str::contains(field(
+            "category",
+            "\"hierarchical-path\"",
+            1,
+        )))
+        // `n/a` is not a percentage, so doc-2's discount falls back to text.
+        .stdout(predicate::str::contains(field(
+            "discount",
+            "\"gestalt\",\"percent\"",
+            2,
+        )))
+        .stdout(predicate::str::contains("overall_score").not());
+}
+
+#[test]
+fn cli_penalizes_numeric_over_predictions_more_with_asymmetry() {
+    let run = |extra: &[&str]| {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("asymmetry_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("asymmetry_predictions.json"))
+            .arg("--output-dir")
+            .arg(temp.path())
+            .args(extra);
+        cmd.assert().success();
+        temp
+    };
+    let total = |score: &str| {
+        predicate::str::contains(format!(
+            "\"path\": \"total\",\n      \"kind\": \"numeric\",\n      \"score\": {score}"
+        ))
+    };
+    // 110 and 90 are both 10 away from 100; only the scale (the larger magnitude) differs.
+    let symmetric = run(&[]);
+    symmetric.child("doc-over.json").assert(total("0.9091"));
+    symmetric.child("doc-under.json").assert(total("0.9"));
+    // Over-predicting doubles the deviation, under-predicting is unchanged.
+    let asymmetric = run(&["--numeric-asymmetry", "over=2,under=1"]);
+    asymmetric.child("doc-over.json").assert(total("0.8182"));
+    asymmetric.child("doc-under.json").assert(total("0.9"));
+}
+
+#[test]
+fn cli_rejects_invalid_numeric_asymmetry() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--numeric-asymmetry")
+        .arg("above=2");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid numeric asymmetry 'above=2'",
+    ));
+}
+
+#[test]
+fn cli_includes_raw_values_in_field_breakdown() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--include-raw-values")
+        .arg("--max-field-length")
+        .arg("4")
+        .arg("--output-dir")
+        .arg(temp.path());
+    cmd.assert().success();
+    // Strings are cut to --max-field-length; numbers are kept as they are.
+    temp.child("doc-1.json")
+        .assert(predicate::str::contains(
+            "\"path\": \"customer.address.country\",\n      \"kind\": \"text\",\n      \
+             \"score\": 0.2857,\n      \"expected\": \"USA\",\n      \"predicted\": \"Unit\"",
+        ))
+        .assert(predicate::str::contains(
+            "\"score\": 0.95,\n      \"expected\": 100.0,\n      \"predicted\": 95.0",
+        ));
+}
+
+#[test]
+fn cli_rejects_raw_values_without_output_dir() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--include-raw-values");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("--output-dir"));
+}
+
+#[test]
+fn cli_rejects_raw_values_with_redaction() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--include-raw-values")
+        .arg("--redact");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn cli_scores_inline_single_document() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(r#"{"total": 100, "vendor": "Acme"}"#)
+        .arg("--predict-json")
+        .arg(r#"{"total": 95, "vendor": "Acme"}"#);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"num_documents\": 1,"))
+        .stdout(predicate::str::contains(
+            "\"numeric_field_similarity\": 0.95,",
+        ))
+        .stdout(predicate::str::contains("\"text_field_similarity\": 1.0,"));
+}
+
+#[test]
+fn cli_reports_malformed_inline_json() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--truth-json")
+        .arg(r#"{"total": 100}"#)
+        .arg("--predict-json")
+        .arg(r#"{"total": 95,"#);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--predict-json is not a valid fields object",
+        ))
+        .stderr(predicate::str::contains("EOF while parsing"));
+}
+
+#[test]
+fn cli_counts_near_miss_numeric_fields_within_band() {
+    let run = |extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--ground-truth")
+            .arg(fixture_path("near_miss_ground_truth.json"))
+            .arg("--predictions")
+            .arg(fixture_path("near_miss_predictions.json"))
+            .args(extra);
+        cmd.assert().success()
+    };
+    // 90 scores exactly 0.9 and is in the band; 89 (0.89) is below it and an exact match
+    // (1.0) is above it, leaving 90 and 99.99.
+    run(&["--near-miss-band", "0.9"])
+        .stdout(predicate::str::contains("\"near_miss_numeric_count\": 2"));
+    // Lowering the band to 0.89 takes in 89 as well.
+    run(&["--near-miss-band", "0.89"])
+        .stdout(predicate::str::contains("\"near_miss_numeric_count\": 3"));
+    run(&[]).stdout(predicate::str::contains("near_miss_numeric_count").not());
+}
+
+#[test]
+fn cli_compares_against_latest_run_in_baseline_dir() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let newer = temp.child("a-newer.json");
+    newer
+        .write_str(r#"{"overall_score": 0.8, "document_coverage": 1.0}"#)
+        .unwrap();
+    let older = temp.child("z-older.json");
+    older.write_str(r#"{"overall_score": 0.5}"#).unwrap();
+    // Modification time decides, not the name.
+    let day_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(86_400);
+    std::fs::File::options()
+        .write(true)
+        .open(older.path())
+        .unwrap()
+        .set_modified(day_ago)
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--baseline-dir")
+        .arg(temp.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("a-newer.json"))
+        .stdout(predicate::str::contains(
+            "\"deltas\": {\n      \"document_coverage\": 0.0,\n      \"overall_score\": 0.0518\n    }",
+        ));
+}
+
+#[test]
+fn cli_fails_on_empty_baseline_dir() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("notes.txt").write_str("not a run").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--baseline-dir")
+        .arg(temp.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "no *.json metrics files in baseline directory",
+    ));
+}
+
+#[test]
+fn cli_reads_object_map_predictions_like_records() {
+    let run = |predictions: &str, extra: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+        cmd.arg("--predictions")
+            .arg(fixture_path(predictions))
+            .args(extra)
+            .args(["--run-id", "object-map"]);
+        let output = cmd.assert().success();
+        String::from_utf8(output.get_output().stdout.clone()).unwrap()
+    };
+    let records = run("dummy_predictions.json", &[]);
+    let object_map = run(
+        "dummy_predictions_object_map.json",
+        &["--input-format", "object-map"],
+    );
+    assert_eq!(object_map, records);
+
+    // A records array is not an object map.
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--input-format")
+        .arg("object-map");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "object-map input must be a JSON object of document id to fields",
+    ));
+}
+
+#[test]
+fn cli_weighted_coverage_counts_present_fields() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--coverage-mode")
+        .arg("weighted");
+    // doc-1 has all 6 fields, doc-2 is missing 1 of 4.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"document_coverage\": 0.875"));
+}
+
+#[test]
+fn cli_timestamps_in_different_zones_match() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("timestamp_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("timestamp_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("timestamp_template.json"))
+        .arg("--timestamp-tolerance")
+        .arg("1h");
+    // issued_at is the same instant (1.0); shipped_at is 30 minutes off (0.5).
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.75"));
+}
+
+#[test]
+fn cli_timestamp_metric_requires_same_instant_without_tolerance() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("timestamp_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("timestamp_predictions.json"))
+        .arg("--text-metric")
+        .arg("timestamp");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"text_field_similarity\": 0.5"));
+}
+
+#[test]
+fn cli_rejects_out_of_range_timestamp_tolerance() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--timestamp-tolerance")
+        .arg("100000000000000000000000s");
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "duration '100000000000000000000000s' is out of range",
+    ));
+}
+
+#[test]
+fn cli_fail_fast_reports_first_bad_record() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("malformed_predictions.json"))
+        .arg("--fail-fast");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "record 1 (document 'doc-2'): 'fields' must be a JSON object",
+        ))
+        .stderr(predicate::str::contains("record 2").not());
+}
+
+#[test]
+fn cli_collect_errors_reports_every_bad_record() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("malformed_predictions.json"))
+        .arg("--collect-errors");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("4 invalid records:"))
+        .stderr(predicate::str::contains(
+            "record 1 (document 'doc-2'): 'fields' must be a JSON object",
+        ))
+        .stderr(predicate::str::contains(
+            "record 2 (document 'doc-1'): duplicate document_id (first seen in record 0)",
+        ))
+        .stderr(predicate::str::contains(
+            "record 3: missing or non-string 'document_id'",
+        ))
+        .stderr(predicate::str::contains(
+            "record 4 (document '  '): 'document_id' is blank",
+        ));
+}
+
+#[test]
+fn cli_european_locale_reads_decimal_commas() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let ground_truth = temp.child("gt.json");
+    ground_truth
+        .write_str(r#"[{"document_id": "doc-1", "fields": {"total": 1234.56}}]"#)
+        .unwrap();
+    let predictions = temp.child("predictions.json");
+    predictions
+        .write_str(r#"[{"document_id": "doc-1", "fields": {"total": "1.234,56"}}]"#)
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(ground_truth.path())
+        .arg("--predictions")
+        .arg(predictions.path())
+        .arg("--locale")
+        .arg("eu");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 1.0",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(ground_truth.path())
+        .arg("--predictions")
+        .arg(predictions.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 0.0",
+    ));
+}
+
+#[test]
+fn cli_truncates_oversized_text_fields() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let ground_truth = temp.child("gt.json");
+    ground_truth
+        .write_str(r#"[{"document_id": "doc-1", "fields": {"notes": "abcd"}}]"#)
+        .unwrap();
+    let predictions = temp.child("predictions.json");
+    let runaway = format!("abcd{}", "x".repeat(200_000));
+    predictions
+        .write_str(&format!(
+            r#"[{{"document_id": "doc-1", "fields": {{"notes": "{runaway}"}}}}]"#
+        ))
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(ground_truth.path())
+        .arg("--predictions")
+        .arg(predictions.path())
+        .arg("--max-field-length")
+        .arg("4");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"truncated_field_count\": 1"))
+        .stdout(predicate::str::contains("\"text_field_similarity\": 1.0"));
+}
+
+#[test]
+fn cli_applies_template_numeric_tolerances() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let out_dir = temp.child("results");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--template-file")
+        .arg(fixture_path("dummy_template.json"))
+        .arg("--numeric-tolerance")
+        .arg("0.01")
+        .arg("--output-dir")
+        .arg(out_dir.path());
+    // Both subtotals fall within the annotated 10%; doc-2's tax is still missing.
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"numeric_field_similarity\": 0.75",
+    ));
+
+    out_dir
+        .child("doc-1.json")
+        .assert(predicate::str::contains("\"tolerance\": \"10%\""))
+        .assert(predicate::str::contains("\"tolerance\": \"0.01\""));
+}
+
+#[cfg(feature = "webhook")]
+#[test]
+fn cli_posts_metrics_to_webhook() {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0_u8; 4096];
+        while !String::from_utf8_lossy(&request).trim_end().ends_with('}') {
+            let read = stream.read(&mut buffer).unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--webhook")
+        .arg(&url);
+    cmd.assert().success();
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /metrics HTTP/1.1"));
+    assert!(request.contains("\"overall_score\": 0.8518"));
+}
+
+#[test]
+fn cli_reports_edit_operations() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--ground-truth")
+        .arg(fixture_path("category_ground_truth.json"))
+        .arg("--predictions")
+        .arg(fixture_path("category_predictions.json"))
+        .arg("--edit-operations");
+    // "A/B/C" -> "A/B/X" in both fields: one substitution each.
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"fields\": 2"))
+        .stdout(predicate::str::contains("\"insertions\": 0"))
+        .stdout(predicate::str::contains("\"substitutions\": 2"));
+}
+
+#[cfg(feature = "webhook")]
+#[test]
+fn cli_reports_failed_webhook_post() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/metrics", listener.local_addr().unwrap());
+    drop(listener);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--webhook")
+        .arg(&url);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("failed after 3 attempts"));
+}
+
+#[cfg(not(feature = "webhook"))]
+#[test]
+fn cli_webhook_requires_feature() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("pdf_eval"));
+    cmd.arg("--predictions")
+        .arg(fixture_path("dummy_predictions.json"))
+        .arg("--webhook")
+        .arg("http://127.0.0.1:9/metrics");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("`webhook` feature"));
+}
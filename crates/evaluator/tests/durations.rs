@@ -0,0 +1,41 @@
+use pdf_eval::durations::parse_duration;
+use serde_json::json;
+
+#[test]
+fn common_duration_forms_read_as_seconds() {
+    for value in [
+        json!(9000),
+        json!("9000"),
+        json!("2h30m"),
+        json!("2h 30m"),
+        json!("150 minutes"),
+        json!("2 hours and 30 mins"),
+        json!("2.5 hrs"),
+        json!("2:30"),
+        json!("02:30:00"),
+        json!("PT2H30M"),
+        json!("pt9000s"),
+    ] {
+        assert_eq!(parse_duration(&value), Some(9000.0), "{value}");
+    }
+    assert_eq!(parse_duration(&json!("1w 1d")), Some(8.0 * 86400.0));
+    assert_eq!(parse_duration(&json!("P1DT12H")), Some(1.5 * 86400.0));
+}
+
+#[test]
+fn non_durations_are_rejected() {
+    for value in [
+        json!("soon"),
+        json!("2 parsecs"),
+        json!("2h30"),
+        json!("1:75"),
+        json!("PT"),
+        json!("PT30M2H"),
+        json!("P1Y"),
+        json!(-5),
+        json!(""),
+        json!(null),
+    ] {
+        assert_eq!(parse_duration(&value), None, "{value}");
+    }
+}
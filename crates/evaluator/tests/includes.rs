@@ -0,0 +1,42 @@
+use pdf_eval::error::EvaluationError;
+use pdf_eval::evaluator::load_ground_truth_from_path;
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/fixtures/includes")
+        .join(name)
+}
+
+#[test]
+fn includes_are_inlined_before_parsing() {
+    let included = load_ground_truth_from_path(&fixture_path("ground_truth.json")).unwrap();
+    let resolved =
+        load_ground_truth_from_path(&fixture_path("ground_truth_resolved.json")).unwrap();
+    assert_eq!(included, resolved);
+    assert_eq!(
+        included["doc-2"].fields["vendor"]["address"]["city"],
+        "New York"
+    );
+}
+
+#[test]
+fn cyclic_includes_are_reported() {
+    let err = load_ground_truth_from_path(&fixture_path("cycle_ground_truth.json")).unwrap_err();
+    let EvaluationError::IncludeCycle(chain) = &err else {
+        panic!("expected an include cycle, got {err}");
+    };
+    let names: Vec<_> = chain
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "cycle_ground_truth.json",
+            "cycle_vendor.json",
+            "cycle_vendor.json"
+        ]
+    );
+    assert!(err.to_string().starts_with("cyclic $include: "));
+}
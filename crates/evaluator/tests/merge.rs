@@ -0,0 +1,94 @@
+use pdf_eval::evaluator::{
+    evaluate_predictions_with_options, load_ground_truth_from_embed, load_ground_truth_from_path,
+    load_predictions, Document,
+};
+use pdf_eval::options::{ComponentWeighting, CoverageMode, EvaluationOptions};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/fixtures")
+        .join(name)
+}
+
+/// Ground truth and predictions of one shard.
+type Shard = (BTreeMap<String, Document>, BTreeMap<String, Document>);
+
+/// Splits the ground truth into two shards by alternating document ids; predictions follow
+/// their ground-truth document, and predictions without one go to the first shard.
+fn shard(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &BTreeMap<String, Document>,
+) -> (Shard, Shard) {
+    let mut shards: [Shard; 2] = Default::default();
+    for (index, (doc_id, document)) in ground_truth.iter().enumerate() {
+        let (gt, preds) = &mut shards[index % 2];
+        gt.insert(doc_id.clone(), document.clone());
+        if let Some(prediction) = predictions.get(doc_id) {
+            preds.insert(doc_id.clone(), prediction.clone());
+        }
+    }
+    for (doc_id, prediction) in predictions {
+        if !ground_truth.contains_key(doc_id) {
+            shards[0].1.insert(doc_id.clone(), prediction.clone());
+        }
+    }
+    let [first, second] = shards;
+    (first, second)
+}
+
+fn assert_merge_matches_single_pass(
+    ground_truth: &BTreeMap<String, Document>,
+    predictions: &str,
+    options: &EvaluationOptions,
+) {
+    let predictions = load_predictions(&fixture_path(predictions)).unwrap();
+    let single = evaluate_predictions_with_options(ground_truth, &predictions, options).unwrap();
+
+    let ((first_gt, first_preds), (second_gt, second_preds)) = shard(ground_truth, &predictions);
+    let first = evaluate_predictions_with_options(&first_gt, &first_preds, options).unwrap();
+    let second = evaluate_predictions_with_options(&second_gt, &second_preds, options).unwrap();
+    let merged = first.merge(second).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&merged).unwrap(),
+        serde_json::to_value(&single).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(&merged.documents).unwrap(),
+        serde_json::to_value(&single.documents).unwrap()
+    );
+}
+
+#[test]
+fn merged_shards_match_single_pass() {
+    assert_merge_matches_single_pass(
+        &load_ground_truth_from_path(&fixture_path("dummy_ground_truth.json")).unwrap(),
+        "dummy_predictions.json",
+        &EvaluationOptions::default(),
+    );
+}
+
+#[test]
+fn merged_shards_match_single_pass_with_missing_documents() {
+    let options = EvaluationOptions {
+        component_weighting: ComponentWeighting::ByCount,
+        coverage_mode: CoverageMode::Weighted,
+        ..EvaluationOptions::default()
+    };
+    assert_merge_matches_single_pass(
+        &load_ground_truth_from_embed().unwrap(),
+        "text_heavy_predictions.json",
+        &options,
+    );
+}
+
+#[test]
+fn merged_shards_redetect_systematic_biases() {
+    assert_merge_matches_single_pass(
+        &load_ground_truth_from_path(&fixture_path("bias_ground_truth.json")).unwrap(),
+        "bias_predictions.json",
+        &EvaluationOptions::default(),
+    );
+}
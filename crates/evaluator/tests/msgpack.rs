@@ -0,0 +1,83 @@
+use pdf_eval::msgpack::{from_msgpack, is_msgpack, to_msgpack, MsgpackError};
+use serde_json::{json, Value};
+
+fn round_trip(value: &Value) -> Value {
+    from_msgpack(&to_msgpack(value)).unwrap()
+}
+
+#[test]
+fn msgpack_round_trips_every_json_value_kind() {
+    let value = json!({
+        "num_documents": 2,
+        "overall_score": 0.8518,
+        "tolerance": 1e-12,
+        "offsets": [-1, -33, -200, -40_000, -3_000_000_000i64, i64::MIN],
+        "counts": [0, 127, 128, 255, 256, 65_535, 65_536, 4_294_967_296u64, u64::MAX],
+        "flags": [true, false, null],
+        "names": ["", "ÄÖÜ", "x".repeat(31), "y".repeat(32), "z".repeat(300)],
+        "nested": {"empty_object": {}, "empty_array": []},
+    });
+    assert_eq!(round_trip(&value), value);
+}
+
+#[test]
+fn msgpack_round_trips_long_collections() {
+    let items: Vec<Value> = (0..70_000).map(Value::from).collect();
+    let map: serde_json::Map<String, Value> = (0..20)
+        .map(|i| (format!("field_{i}"), Value::from(i as f64 / 3.0)))
+        .collect();
+    let value = json!({"items": items, "map": map});
+    assert_eq!(round_trip(&value), value);
+}
+
+#[test]
+fn msgpack_documents_are_told_apart_from_json() {
+    let value = json!({"overall_score": 0.5});
+    let encoded = to_msgpack(&value);
+    assert!(is_msgpack(&encoded));
+    assert!(!is_msgpack(
+        serde_json::to_string(&value).unwrap().as_bytes()
+    ));
+    assert!(!is_msgpack(b""));
+}
+
+#[test]
+fn msgpack_rejects_malformed_input() {
+    let mut encoded = to_msgpack(&json!({"overall_score": 0.5}));
+    assert!(matches!(
+        from_msgpack(&encoded[..encoded.len() - 1]),
+        Err(MsgpackError::UnexpectedEnd)
+    ));
+    encoded.push(0xc0);
+    assert!(matches!(
+        from_msgpack(&encoded),
+        Err(MsgpackError::TrailingBytes(1))
+    ));
+    assert!(matches!(
+        from_msgpack(&[0x81, 0x01, 0xc0]),
+        Err(MsgpackError::NonStringKey)
+    ));
+    assert!(matches!(
+        from_msgpack(&[0xc4, 0x00]),
+        Err(MsgpackError::UnsupportedType(0xc4))
+    ));
+}
+
+#[test]
+fn msgpack_rejects_deeply_nested_input() {
+    // `{"a": [[[...]]]}` nested far deeper than the stack would allow.
+    let mut nested = vec![0x81, 0xa1, b'a'];
+    nested.resize(2_000_000, 0x91);
+    nested.push(0xc0);
+    assert!(matches!(from_msgpack(&nested), Err(MsgpackError::TooDeep)));
+
+    // 128 levels, the map included, still decode.
+    let mut shallow = vec![0x81, 0xa1, b'a'];
+    shallow.extend(std::iter::repeat_n(0x91, 127));
+    shallow.push(0xc0);
+    let mut value = from_msgpack(&shallow).unwrap()["a"].clone();
+    for _ in 0..127 {
+        value = value.as_array().unwrap()[0].clone();
+    }
+    assert_eq!(value, json!(null));
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "remote")]
+
+use pdf_eval::error::EvaluationError;
+use pdf_eval::options::ValidationMode;
+use pdf_eval::remote::{fetch_text, load_ground_truth_from_url_with, RemoteError};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+/// Serves `body` to exactly one GET request and returns the URL to fetch it from.
+fn serve_once(status_line: &'static str, body: &'static str) -> (String, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/truth.json", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0_u8; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let read = stream.read(&mut buffer).unwrap();
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let response = format!(
+            "{status_line}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+    (url, server)
+}
+
+#[test]
+fn fetched_ground_truth_is_cached_for_the_process() {
+    let (url, server) = serve_once(
+        "HTTP/1.0 200 OK",
+        r#"[{"document_id": "doc-1", "fields": {"total": 10}}]"#,
+    );
+    let first = load_ground_truth_from_url_with(&url, ValidationMode::Lenient).unwrap();
+    server.join().unwrap();
+    // The server is gone, so the second load can only come from the cache.
+    let second = load_ground_truth_from_url_with(&url, ValidationMode::Lenient).unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first["doc-1"].fields, second["doc-1"].fields);
+}
+
+#[test]
+fn fetched_ground_truth_is_validated_like_a_file() {
+    let (url, server) = serve_once(
+        "HTTP/1.0 200 OK",
+        r#"[{"document_id": "doc-1", "fields": 3}]"#,
+    );
+    let err = load_ground_truth_from_url_with(&url, ValidationMode::FailFast).unwrap_err();
+    server.join().unwrap();
+    assert!(matches!(
+        err,
+        RemoteError::Invalid(EvaluationError::InvalidRecord(_))
+    ));
+}
+
+#[test]
+fn error_statuses_and_https_are_reported() {
+    let (url, server) = serve_once("HTTP/1.0 404 Not Found", "");
+    let err = fetch_text(&url).unwrap_err();
+    server.join().unwrap();
+    assert!(err.to_string().contains("404 Not Found"), "{err}");
+
+    let err = fetch_text("https://example.com/truth.json").unwrap_err();
+    assert!(matches!(err, RemoteError::TlsUnsupported(_)));
+}
@@ -0,0 +1,66 @@
+use pdf_eval::retry::{is_transient, read_with_retry, ReadRetry};
+use std::io;
+use std::time::Duration;
+
+fn no_backoff(retries: u32) -> ReadRetry {
+    ReadRetry {
+        retries,
+        backoff: Duration::ZERO,
+    }
+}
+
+/// A read that fails with `kind` for its first `failures` attempts, counting attempts.
+fn flaky_read(
+    failures: u32,
+    kind: io::ErrorKind,
+    attempts: &mut u32,
+) -> impl FnMut() -> io::Result<String> + '_ {
+    move || {
+        *attempts += 1;
+        if *attempts <= failures {
+            Err(io::Error::new(kind, "stale file handle"))
+        } else {
+            Ok("[]".to_string())
+        }
+    }
+}
+
+#[test]
+fn transient_read_errors_succeed_on_retry() {
+    let mut attempts = 0;
+    let payload = read_with_retry(
+        &no_backoff(2),
+        flaky_read(2, io::ErrorKind::Other, &mut attempts),
+    )
+    .unwrap();
+    assert_eq!(payload, "[]");
+    assert_eq!(attempts, 3);
+}
+
+#[test]
+fn transient_read_errors_fail_once_retries_run_out() {
+    let mut attempts = 0;
+    let error = read_with_retry(
+        &no_backoff(1),
+        flaky_read(2, io::ErrorKind::Other, &mut attempts),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::Other);
+    assert_eq!(attempts, 2);
+}
+
+#[test]
+fn missing_files_are_not_retried() {
+    let mut attempts = 0;
+    let error = read_with_retry(
+        &no_backoff(3),
+        flaky_read(1, io::ErrorKind::NotFound, &mut attempts),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    assert_eq!(attempts, 1);
+    assert!(!is_transient(&io::Error::from(
+        io::ErrorKind::PermissionDenied
+    )));
+    assert!(is_transient(&io::Error::from(io::ErrorKind::TimedOut)));
+}
@@ -0,0 +1,20 @@
+use pdf_eval::run_id::derive_run_id;
+
+#[test]
+fn run_ids_are_deterministic_per_run_and_distinct_across_runs() {
+    let inputs = vec!["pdf_eval".to_string(), "--predictions".to_string()];
+    let id = derive_run_id(&inputs, 1_700_000_000_000_000_000, 42);
+    assert_eq!(id, derive_run_id(&inputs, 1_700_000_000_000_000_000, 42));
+    assert_ne!(id, derive_run_id(&inputs, 1_700_000_000_000_000_001, 42));
+    assert_ne!(id, derive_run_id(&inputs, 1_700_000_000_000_000_000, 43));
+    assert_ne!(
+        id,
+        derive_run_id(&inputs[..1], 1_700_000_000_000_000_000, 42)
+    );
+
+    let groups: Vec<usize> = id.split('-').map(str::len).collect();
+    assert_eq!(groups, [8, 4, 4, 4, 12]);
+    assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+    assert_eq!(&id[14..15], "8");
+    assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+}
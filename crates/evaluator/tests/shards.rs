@@ -0,0 +1,68 @@
+use pdf_eval::digest::sha256_hex;
+use pdf_eval::embedded::verify_shards;
+use pdf_eval::evaluator::{load_ground_truth_from_path, load_ground_truth_from_shards};
+use pdf_eval::shards::{compress, split_documents};
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/fixtures")
+        .join(name)
+}
+
+#[test]
+fn sharded_ground_truth_matches_single_blob() {
+    let path = fixture_path("sharded_ground_truth.json");
+    let raw = std::fs::read(&path).unwrap();
+    let blob = compress(&raw);
+    let single = load_ground_truth_from_shards(&[blob.as_slice()]).unwrap();
+    assert_eq!(single, load_ground_truth_from_path(&path).unwrap());
+    assert_eq!(single.len(), 7);
+
+    for count in [2, 3, 10] {
+        let payloads = split_documents(&raw, count).unwrap();
+        assert_eq!(payloads.len(), count.min(7), "shards for {count}");
+        let compressed: Vec<Vec<u8>> = payloads.iter().map(|payload| compress(payload)).collect();
+        let shards: Vec<&[u8]> = compressed.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            load_ground_truth_from_shards(&shards).unwrap(),
+            single,
+            "{count} shards"
+        );
+    }
+}
+
+#[test]
+fn loading_no_shards_is_an_error() {
+    assert!(load_ground_truth_from_shards(&[]).is_err());
+}
+
+/// Build info as `build.rs` records it for `raw` split into `count` shards.
+fn sharded_build(raw: &[u8], count: usize) -> (String, Vec<Vec<u8>>) {
+    let payloads = split_documents(raw, count).unwrap();
+    let build_info = serde_json::json!({
+        "ground_truth_sha256": sha256_hex(raw),
+        "ground_truth_shard_sha256": payloads.iter().map(|payload| sha256_hex(payload)).collect::<Vec<_>>(),
+    });
+    let compressed = payloads.iter().map(|payload| compress(payload)).collect();
+    (build_info.to_string(), compressed)
+}
+
+#[test]
+fn sharded_verification_reports_the_whole_file_digest() {
+    let raw = std::fs::read(fixture_path("sharded_ground_truth.json")).unwrap();
+    for count in [1, 3] {
+        let (build_info, compressed) = sharded_build(&raw, count);
+        let shards: Vec<&[u8]> = compressed.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            verify_shards(&build_info, &shards).unwrap(),
+            sha256_hex(&raw),
+            "{count} shards"
+        );
+    }
+
+    let (build_info, mut compressed) = sharded_build(&raw, 3);
+    compressed.swap(0, 1);
+    let shards: Vec<&[u8]> = compressed.iter().map(Vec::as_slice).collect();
+    assert!(verify_shards(&build_info, &shards).is_err());
+}
@@ -0,0 +1,26 @@
+use pdf_eval::evaluator::{evaluate_predictions, load_ground_truth_from_embed, load_predictions};
+use pdf_eval::summary::terminal_summary;
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../resources/fixtures")
+        .join(name)
+}
+
+#[test]
+fn summary_colors_scores_by_band() {
+    let ground_truth = load_ground_truth_from_embed().unwrap();
+    let predictions = load_predictions(&fixture_path("dummy_predictions.json")).unwrap();
+    let metrics = evaluate_predictions(&ground_truth, &predictions).unwrap();
+
+    let plain = terminal_summary(&metrics, false);
+    assert!(!plain.contains('\u{1b}'));
+    assert!(plain.contains("  overall score            0.8518\n"));
+    assert!(plain.contains("  doc-2                    0.7875\n"));
+
+    let colored = terminal_summary(&metrics, true);
+    // Green at 0.9 and above, yellow from 0.7, red below.
+    assert!(colored.contains("\u{1b}[32m1.0000\u{1b}[0m"));
+    assert!(colored.contains("\u{1b}[33m0.8518\u{1b}[0m"));
+}